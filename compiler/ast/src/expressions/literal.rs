@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{GroupLiteral, IntegerType};
+use crate::{GroupLiteral, IntegerType, Value};
 
 use super::*;
 
@@ -41,6 +41,20 @@ pub enum Literal {
     String(String, #[serde(with = "leo_span::span_json")] Span, NodeID),
 }
 
+impl Literal {
+    /// Returns the integer literal with the value of `self` and the type `type_`.
+    /// Returns `None` if `self` is not an integer literal, or if its value is out of the range of `type_`.
+    pub fn with_integer_type(&self, type_: IntegerType) -> Option<Self> {
+        match self {
+            Self::Integer(_, value, span, id) => {
+                let literal = Self::Integer(type_, value.clone(), *span, *id);
+                Value::try_from(&literal).is_ok().then_some(literal)
+            }
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for Literal {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self {
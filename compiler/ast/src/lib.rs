@@ -145,6 +145,15 @@ impl Ast {
     }
 }
 
+/// Writes the complete AST, including spans and node IDs, to a JSON file at the given path.
+/// Unlike the snapshots written by `to_json_file_without_keys`, the file can be read back with `Ast::from_json_file`.
+pub fn dump_ast_json(ast: &Ast, path: &std::path::Path) -> Result<()> {
+    let file = std::fs::File::create(path).map_err(|e| AstError::failed_to_create_ast_json_file(path, &e))?;
+    let writer = std::io::BufWriter::new(file);
+    Ok(serde_json::to_writer_pretty(writer, &ast.ast)
+        .map_err(|e| AstError::failed_to_write_ast_to_json_file(path, &e))?)
+}
+
 impl AsRef<Program> for Ast {
     fn as_ref(&self) -> &Program {
         &self.ast
@@ -273,6 +273,7 @@ pub trait StatementReconstructor: ExpressionReconstructor {
                         self.reconstruct_expression(right).0,
                     ),
                 },
+                message: input.message,
                 span: input.span,
                 id: input.id,
             }),
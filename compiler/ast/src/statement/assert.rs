@@ -33,10 +33,14 @@ pub enum AssertVariant {
 }
 
 /// An assert statement, `assert(<expr>)`, `assert_eq(<expr>)` or `assert_neq(<expr>)`.
+/// Each variant may be followed by a message, e.g. `assert_eq(<expr>, <expr>, "message")`.
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
 pub struct AssertStatement {
     /// The variant of the assert statement.
     pub variant: AssertVariant,
+    /// The message reported if the assertion fails, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
     /// The span, excluding the semicolon.
     pub span: Span,
     /// The ID of the node.
@@ -45,10 +49,14 @@ pub struct AssertStatement {
 
 impl fmt::Display for AssertStatement {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match &self.message {
+            Some(message) => format!(", \"{message}\""),
+            None => String::new(),
+        };
         match self.variant {
-            AssertVariant::Assert(ref expr) => write!(f, "assert({expr});"),
-            AssertVariant::AssertEq(ref expr1, ref expr2) => write!(f, "assert_eq({expr1}, {expr2});"),
-            AssertVariant::AssertNeq(ref expr1, ref expr2) => write!(f, "assert_neq({expr1}, {expr2});"),
+            AssertVariant::Assert(ref expr) => write!(f, "assert({expr}{message});"),
+            AssertVariant::AssertEq(ref expr1, ref expr2) => write!(f, "assert_eq({expr1}, {expr2}{message});"),
+            AssertVariant::AssertNeq(ref expr1, ref expr2) => write!(f, "assert_neq({expr1}, {expr2}{message});"),
         }
     }
 }
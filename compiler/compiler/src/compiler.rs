@@ -54,6 +54,8 @@ pub struct Compiler<'a> {
     assigner: Assigner,
     /// The type table.
     type_table: TypeTable,
+    /// The source location of each emitted instruction, recorded during code generation if the source map is enabled.
+    instruction_locations: Vec<InstructionLocation>,
 }
 
 impl<'a> Compiler<'a> {
@@ -81,6 +83,7 @@ impl<'a> Compiler<'a> {
             node_builder,
             assigner,
             type_table,
+            instruction_locations: Vec::new(),
         }
     }
 
@@ -121,7 +124,7 @@ impl<'a> Compiler<'a> {
         }
 
         if self.compiler_options.output.initial_ast {
-            self.write_ast_to_json("initial_ast.json")?;
+            self.write_ast_to_json(&self.ast, "initial_ast.json")?;
         }
 
         Ok(())
@@ -180,6 +183,9 @@ impl<'a> Compiler<'a> {
     pub fn type_checker_pass(&'a self, symbol_table: SymbolTable) -> Result<(SymbolTable, StructGraph, CallGraph)> {
         let (symbol_table, struct_graph, call_graph) =
             TypeChecker::do_pass((&self.ast, self.handler, symbol_table, &self.type_table))?;
+        if self.compiler_options.build.unused_input_warnings_enabled {
+            UnusedInputDetector::do_pass((&self.ast, self.handler, &symbol_table))?;
+        }
         if self.compiler_options.output.type_checked_symbol_table {
             self.write_symbol_table_to_json("type_checked_symbol_table.json", &symbol_table)?;
         }
@@ -194,11 +200,12 @@ impl<'a> Compiler<'a> {
             &self.node_builder,
             symbol_table,
             &self.type_table,
+            self.compiler_options.build.max_loop_iterations,
         ))?;
         self.ast = ast;
 
         if self.compiler_options.output.unrolled_ast {
-            self.write_ast_to_json("unrolled_ast.json")?;
+            self.write_ast_to_json(&self.ast, "unrolled_ast.json")?;
         }
 
         if self.compiler_options.output.unrolled_symbol_table {
@@ -208,85 +215,199 @@ impl<'a> Compiler<'a> {
         Ok(symbol_table)
     }
 
-    /// Runs the static single assignment pass.
+    /// Runs the static single assignment pass, unless it is disabled.
     pub fn static_single_assignment_pass(&mut self, symbol_table: &SymbolTable) -> Result<()> {
-        self.ast = StaticSingleAssigner::do_pass((
-            std::mem::take(&mut self.ast),
-            &self.node_builder,
-            &self.assigner,
-            symbol_table,
-            &self.type_table,
-        ))?;
+        self.run_stages(|compiler, pipeline| compiler.static_single_assignment_stage(pipeline, symbol_table))
+    }
 
-        if self.compiler_options.output.ssa_ast {
-            self.write_ast_to_json("ssa_ast.json")?;
-        }
+    /// Runs the flattening pass, unless it or the static single assignment pass is disabled.
+    pub fn flattening_pass(&mut self, symbol_table: &SymbolTable) -> Result<()> {
+        self.run_stages(|compiler, pipeline| compiler.flattening_stage(pipeline, symbol_table))
+    }
 
-        Ok(())
+    /// Runs the destructuring pass, unless the AST is not flattened.
+    pub fn destructuring_pass(&mut self) -> Result<()> {
+        self.run_stages(|compiler, pipeline| compiler.destructuring_stage(pipeline))
     }
 
-    /// Runs the flattening pass.
-    pub fn flattening_pass(&mut self, symbol_table: &SymbolTable) -> Result<()> {
-        self.ast = Flattener::do_pass((
-            std::mem::take(&mut self.ast),
-            symbol_table,
-            &self.type_table,
-            &self.node_builder,
-            &self.assigner,
-        ))?;
+    /// Runs the function inlining pass, unless the AST is not flattened.
+    pub fn function_inlining_pass(&mut self, call_graph: &CallGraph) -> Result<()> {
+        self.run_stages(|compiler, pipeline| compiler.function_inlining_stage(pipeline, call_graph))
+    }
 
-        if self.compiler_options.output.flattened_ast {
-            self.write_ast_to_json("flattened_ast.json")?;
-        }
+    /// Runs the common subexpression elimination pass, if it is enabled and the AST is flattened.
+    pub fn common_subexpression_elimination_pass(&mut self) -> Result<()> {
+        self.run_stages(|compiler, pipeline| compiler.common_subexpression_elimination_stage(pipeline))
+    }
+
+    /// Runs the temporary elimination pass, if it is enabled and the AST is flattened.
+    pub fn temporary_elimination_pass(&mut self) -> Result<()> {
+        self.run_stages(|compiler, pipeline| compiler.temporary_elimination_stage(pipeline))
+    }
+
+    /// Runs the dead code elimination pass, if it is enabled and the AST is flattened.
+    pub fn dead_code_elimination_pass(&mut self) -> Result<()> {
+        self.run_stages(|compiler, pipeline| compiler.dead_code_elimination_stage(pipeline))
+    }
 
+    /// Runs the passes added to a pipeline by `stages` on the AST, replacing the AST with the result.
+    fn run_stages(&mut self, stages: impl FnOnce(&Self, Pipeline<'a>) -> Pipeline<'a>) -> Result<()> {
+        let pipeline = Pipeline::new(std::mem::take(&mut self.ast), self.handler);
+        self.ast = stages(self, pipeline).finish()?;
         Ok(())
     }
 
-    /// Runs the destructuring pass.
-    pub fn destructuring_pass(&mut self) -> Result<()> {
-        self.ast = Destructurer::do_pass((
-            std::mem::take(&mut self.ast),
-            &self.type_table,
-            &self.node_builder,
-            &self.assigner,
-        ))?;
+    /// Adds the static single assignment pass to `pipeline`, unless it is disabled.
+    fn static_single_assignment_stage(&self, pipeline: Pipeline<'a>, symbol_table: &SymbolTable) -> Pipeline<'a> {
+        if !self.compiler_options.build.ssa_enabled {
+            return pipeline;
+        }
 
-        if self.compiler_options.output.destructured_ast {
-            self.write_ast_to_json("destructured_ast.json")?;
+        pipeline
+            .run::<StaticSingleAssigner>(
+                |ast| {
+                    (
+                        ast,
+                        self.handler,
+                        &self.node_builder,
+                        &self.assigner,
+                        symbol_table,
+                        &self.type_table,
+                        StaticSingleAssignerOptions {
+                            constant_propagation_enabled: self.compiler_options.build.constant_propagation_enabled,
+                            assert_lowering_enabled: self.compiler_options.build.assert_lowering_enabled,
+                        },
+                    )
+                },
+                |output| output.map(|(ast, _)| ast),
+            )
+            .inspect(|ast| match self.compiler_options.build.ssa_verification_enabled {
+                true => SingleAssignmentVerifier::do_pass((ast, self.handler)),
+                false => Ok(()),
+            })
+            .inspect(|ast| {
+                if self.compiler_options.output.ssa_ast {
+                    self.write_ast_to_json(ast, "ssa_ast.json")?;
+                }
+                if self.compiler_options.output.ast_dumps {
+                    self.dump_ast_to_json(ast, "ssa_ast.dump.json")?;
+                }
+                Ok(())
+            })
+    }
+
+    /// Adds the flattening pass to `pipeline`, unless it or the static single assignment pass is disabled.
+    fn flattening_stage(&self, pipeline: Pipeline<'a>, symbol_table: &SymbolTable) -> Pipeline<'a> {
+        if !self.is_flattened() {
+            return pipeline;
         }
 
-        Ok(())
+        pipeline
+            .then::<Flattener>(|ast| {
+                (
+                    ast,
+                    self.handler,
+                    symbol_table,
+                    &self.type_table,
+                    &self.node_builder,
+                    &self.assigner,
+                    FlattenerOptions {
+                        return_folding_enabled: self.compiler_options.build.return_folding_enabled,
+                        finalize_folding_enabled: self.compiler_options.build.finalize_folding_enabled,
+                        mapping_update_rejection_enabled: self.compiler_options.build.mapping_update_rejection_enabled,
+                        named_returns_enabled: self.compiler_options.build.named_returns_enabled,
+                        unreachable_assertions_enabled: self.compiler_options.build.unreachable_assertions_enabled,
+                    },
+                )
+            })
+            .inspect(|ast| {
+                if self.compiler_options.output.flattened_ast {
+                    self.write_ast_to_json(ast, "flattened_ast.json")?;
+                }
+                if self.compiler_options.output.ast_dumps {
+                    self.dump_ast_to_json(ast, "flattened_ast.dump.json")?;
+                }
+                Ok(())
+            })
     }
 
-    /// Runs the function inlining pass.
-    pub fn function_inlining_pass(&mut self, call_graph: &CallGraph) -> Result<()> {
-        let ast = FunctionInliner::do_pass((
-            std::mem::take(&mut self.ast),
-            &self.node_builder,
-            call_graph,
-            &self.assigner,
-            &self.type_table,
-        ))?;
-        self.ast = ast;
+    /// Adds the destructuring pass to `pipeline`, unless the AST is not flattened.
+    fn destructuring_stage(&self, pipeline: Pipeline<'a>) -> Pipeline<'a> {
+        if !self.is_flattened() {
+            return pipeline;
+        }
 
-        if self.compiler_options.output.inlined_ast {
-            self.write_ast_to_json("inlined_ast.json")?;
+        let pipeline = pipeline.then::<Destructurer>(|ast| (ast, &self.type_table, &self.node_builder, &self.assigner));
+
+        pipeline.inspect(|ast| {
+            if self.compiler_options.output.destructured_ast {
+                self.write_ast_to_json(ast, "destructured_ast.json")?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Adds the function inlining pass to `pipeline`, unless the AST is not flattened.
+    fn function_inlining_stage(&self, pipeline: Pipeline<'a>, call_graph: &CallGraph) -> Pipeline<'a> {
+        if !self.is_flattened() {
+            return pipeline;
         }
 
-        Ok(())
+        pipeline
+            .then::<FunctionInliner>(|ast| {
+                (
+                    ast,
+                    &self.node_builder,
+                    call_graph,
+                    &self.assigner,
+                    &self.type_table,
+                    self.compiler_options.build.trivial_inlining_threshold,
+                )
+            })
+            .inspect(|ast| {
+                if self.compiler_options.output.inlined_ast {
+                    self.write_ast_to_json(ast, "inlined_ast.json")?;
+                }
+                Ok(())
+            })
     }
 
-    /// Runs the dead code elimination pass.
-    pub fn dead_code_elimination_pass(&mut self) -> Result<()> {
-        if self.compiler_options.build.dce_enabled {
-            self.ast = DeadCodeEliminator::do_pass((std::mem::take(&mut self.ast), &self.node_builder))?;
+    /// Adds the common subexpression elimination pass to `pipeline`, if it is enabled and the AST is flattened.
+    fn common_subexpression_elimination_stage(&self, pipeline: Pipeline<'a>) -> Pipeline<'a> {
+        match self.compiler_options.build.cse_enabled && self.is_flattened() {
+            true => pipeline.then::<CommonSubexpressionEliminator>(|ast| (ast, &self.node_builder)),
+            false => pipeline,
         }
+    }
 
-        if self.compiler_options.output.dce_ast {
-            self.write_ast_to_json("dce_ast.json")?;
+    /// Adds the temporary elimination pass to `pipeline`, if it is enabled and the AST is flattened.
+    fn temporary_elimination_stage(&self, pipeline: Pipeline<'a>) -> Pipeline<'a> {
+        match self.compiler_options.build.temporary_elimination_enabled && self.is_flattened() {
+            true => pipeline.then::<TemporaryEliminator>(|ast| (ast, &self.node_builder)),
+            false => pipeline,
         }
+    }
 
-        Ok(())
+    /// Adds the dead code elimination pass to `pipeline`, if it is enabled and the AST is flattened.
+    fn dead_code_elimination_stage(&self, pipeline: Pipeline<'a>) -> Pipeline<'a> {
+        let pipeline = match self.compiler_options.build.dce_enabled && self.is_flattened() {
+            true => pipeline.then::<DeadCodeEliminator>(|ast| (ast, &self.node_builder)),
+            false => pipeline,
+        };
+
+        pipeline.inspect(|ast| {
+            if self.compiler_options.output.dce_ast {
+                self.write_ast_to_json(ast, "dce_ast.json")?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns whether the static single assignment and flattening passes are enabled.
+    /// The passes that follow flattening, except for code generation, require the AST to be flattened.
+    /// If it is not, they are skipped, and code generation reports the statements that were not lowered.
+    fn is_flattened(&self) -> bool {
+        self.compiler_options.build.ssa_enabled && self.compiler_options.build.flattening_enabled
     }
 
     /// Runs the code generation pass.
@@ -296,7 +417,30 @@ impl<'a> Compiler<'a> {
         struct_graph: &StructGraph,
         call_graph: &CallGraph,
     ) -> Result<String> {
-        CodeGenerator::do_pass((&self.ast, symbol_table, &self.type_table, struct_graph, call_graph, &self.ast.ast))
+        let (bytecode, instruction_locations) = CodeGenerator::do_pass((
+            &self.ast,
+            self.handler,
+            symbol_table,
+            &self.type_table,
+            struct_graph,
+            call_graph,
+            &self.ast.ast,
+            CodeGeneratorOptions {
+                overflow_mode: self.compiler_options.build.overflow_mode,
+                debug_comments_enabled: self.compiler_options.build.debug_comments_enabled,
+                source_map_enabled: self.compiler_options.build.source_map_enabled,
+                annotation_comments_enabled: self.compiler_options.build.annotation_comments_enabled,
+            },
+        ))?;
+        self.instruction_locations = instruction_locations;
+
+        Ok(bytecode)
+    }
+
+    /// Returns the source location of each instruction emitted by code generation, serialized as JSON.
+    /// Note that no locations are recorded unless the source map is enabled.
+    pub fn instruction_map(&self) -> Result<String> {
+        instruction_locations_to_json_string(&self.instruction_locations)
     }
 
     /// Runs the compiler stages.
@@ -307,15 +451,16 @@ impl<'a> Compiler<'a> {
         // TODO: Make this pass optional.
         let st = self.loop_unrolling_pass(st)?;
 
-        self.static_single_assignment_pass(&st)?;
-
-        self.flattening_pass(&st)?;
-
-        self.destructuring_pass()?;
-
-        self.function_inlining_pass(&call_graph)?;
-
-        self.dead_code_elimination_pass()?;
+        // Run the passes that transform the AST in order, stopping at the first pass that fails.
+        self.run_stages(|compiler, pipeline| {
+            let pipeline = compiler.static_single_assignment_stage(pipeline, &st);
+            let pipeline = compiler.flattening_stage(pipeline, &st);
+            let pipeline = compiler.destructuring_stage(pipeline);
+            let pipeline = compiler.function_inlining_stage(pipeline, &call_graph);
+            let pipeline = compiler.common_subexpression_elimination_stage(pipeline);
+            let pipeline = compiler.temporary_elimination_stage(pipeline);
+            compiler.dead_code_elimination_stage(pipeline)
+        })?;
 
         Ok((st, struct_graph, call_graph))
     }
@@ -332,12 +477,12 @@ impl<'a> Compiler<'a> {
     }
 
     /// Writes the AST to a JSON file.
-    fn write_ast_to_json(&self, file_suffix: &str) -> Result<()> {
+    fn write_ast_to_json(&self, ast: &Ast, file_suffix: &str) -> Result<()> {
         // Remove `Span`s if they are not enabled.
         if self.compiler_options.output.ast_spans_enabled {
-            self.ast.to_json_file(self.output_directory.clone(), &format!("{}.{file_suffix}", self.program_name))?;
+            ast.to_json_file(self.output_directory.clone(), &format!("{}.{file_suffix}", self.program_name))?;
         } else {
-            self.ast.to_json_file_without_keys(
+            ast.to_json_file_without_keys(
                 self.output_directory.clone(),
                 &format!("{}.{file_suffix}", self.program_name),
                 &["_span", "span"],
@@ -346,6 +491,11 @@ impl<'a> Compiler<'a> {
         Ok(())
     }
 
+    /// Writes the complete AST, including spans, to a JSON file that can be read back.
+    fn dump_ast_to_json(&self, ast: &Ast, file_suffix: &str) -> Result<()> {
+        leo_ast::dump_ast_json(ast, &self.output_directory.join(format!("{}.{file_suffix}", self.program_name)))
+    }
+
     /// Writes the Symbol Table to a JSON file.
     fn write_symbol_table_to_json(&self, file_suffix: &str, symbol_table: &SymbolTable) -> Result<()> {
         // Remove `Span`s if they are not enabled.
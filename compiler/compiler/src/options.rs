@@ -14,7 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
 
-// NOTE: If compiler passes are made optional, pass preconditions and invariants may not necessarily hold true.
+pub use leo_passes::{OverflowMode, DEFAULT_MAX_LOOP_ITERATIONS};
+
+// NOTE: Since compiler passes can be made optional, pass preconditions and invariants may not necessarily hold true.
 
 #[derive(Clone, Default)]
 pub struct CompilerOptions {
@@ -24,10 +26,81 @@ pub struct CompilerOptions {
     pub output: OutputOptions,
 }
 
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct BuildOptions {
+    /// Whether to run the static single assignment pass.
+    /// Note that the flattening pass and all later passes, except for code generation, are skipped if disabled.
+    pub ssa_enabled: bool,
+    /// Whether to run the flattening pass.
+    /// Note that all later passes, except for code generation, are skipped if disabled.
+    pub flattening_enabled: bool,
     /// Whether to enable dead code elimination.
     pub dce_enabled: bool,
+    /// Whether to reuse the variables holding pure expressions that are computed more than once in a function.
+    pub cse_enabled: bool,
+    /// Whether to remove the assignments to variables that are never used, unless their values may have side effects.
+    pub temporary_elimination_enabled: bool,
+    /// Whether integer arithmetic is emitted with checked or wrapping instructions.
+    pub overflow_mode: OverflowMode,
+    /// Whether to verify that each variable is assigned at most once after static single assignment.
+    pub ssa_verification_enabled: bool,
+    /// Whether to replace uses of variables that are known to equal a literal with the literal.
+    pub constant_propagation_enabled: bool,
+    /// Whether to emit warnings for function inputs that are never used.
+    pub unused_input_warnings_enabled: bool,
+    /// Whether to lower assertions of inequalities, e.g. `assert(a != b)`, directly to `assert.neq`.
+    pub assert_lowering_enabled: bool,
+    /// Whether to fold the returns in function bodies into a single return at the end of the function.
+    /// If disabled, return statements are left in place, as if each function were annotated with `@noflatten`.
+    pub return_folding_enabled: bool,
+    /// Whether to fold the returns in finalize blocks into a single return at the end of the block.
+    pub finalize_folding_enabled: bool,
+    /// Whether to reject mapping updates inside conditional statements, rather than performing them on every execution path.
+    pub mapping_update_rejection_enabled: bool,
+    /// Whether to precede the instructions for each statement with a comment containing the statement.
+    pub debug_comments_enabled: bool,
+    /// Whether to record the source location of each emitted instruction, e.g. to write a map file alongside the program.
+    pub source_map_enabled: bool,
+    /// Whether to precede each function with comments containing its annotations.
+    pub annotation_comments_enabled: bool,
+    /// The maximum number of statements in a standard function that is inlined at its call sites after flattening.
+    /// If zero, only `inline` functions are inlined.
+    pub trivial_inlining_threshold: usize,
+    /// Whether to name the variables produced by folding returns after the function, e.g. `main$out` rather than `$ret`.
+    pub named_returns_enabled: bool,
+    /// Whether to assert that the guards of folded returns that can never be true are false, as a debugging aid.
+    pub unreachable_assertions_enabled: bool,
+    /// The maximum number of iterations of a loop that is unrolled, which is checked by the loop unroller.
+    /// Defaults to `DEFAULT_MAX_LOOP_ITERATIONS`. If zero, there is no maximum.
+    pub max_loop_iterations: usize,
+}
+
+impl Default for BuildOptions {
+    /// Returns the default build options, in which all of the required compiler passes are enabled.
+    fn default() -> Self {
+        Self {
+            ssa_enabled: true,
+            flattening_enabled: true,
+            dce_enabled: false,
+            cse_enabled: false,
+            temporary_elimination_enabled: false,
+            overflow_mode: OverflowMode::default(),
+            ssa_verification_enabled: false,
+            constant_propagation_enabled: false,
+            unused_input_warnings_enabled: false,
+            assert_lowering_enabled: false,
+            return_folding_enabled: true,
+            finalize_folding_enabled: true,
+            mapping_update_rejection_enabled: false,
+            debug_comments_enabled: false,
+            source_map_enabled: false,
+            annotation_comments_enabled: false,
+            trivial_inlining_threshold: 0,
+            named_returns_enabled: false,
+            unreachable_assertions_enabled: false,
+            max_loop_iterations: DEFAULT_MAX_LOOP_ITERATIONS,
+        }
+    }
 }
 
 #[derive(Clone, Default)]
@@ -58,4 +131,7 @@ pub struct OutputOptions {
     pub inlined_ast: bool,
     /// If enabled writes the AST after dead code elimination.
     pub dce_ast: bool,
+    /// If enabled writes the complete AST after static single assignment and flattening, regardless of `ast_spans_enabled`.
+    /// Unlike the snapshots, these files can be read back with `Ast::from_json_file`.
+    pub ast_dumps: bool,
 }
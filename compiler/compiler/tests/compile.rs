@@ -99,6 +99,7 @@ fn run_test(test: Test, handler: &Handler, buf: &BufferEmitter) -> Result<Value,
                 destructured_ast: true,
                 inlined_ast: true,
                 dce_ast: true,
+                ast_dumps: false,
             },
         };
 
@@ -113,6 +113,7 @@ fn run_test(test: Test, handler: &Handler, err_buf: &BufferEmitter) -> Result<Va
                 destructured_ast: true,
                 inlined_ast: true,
                 dce_ast: true,
+                ast_dumps: false,
             },
         };
 
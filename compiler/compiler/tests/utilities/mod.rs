@@ -17,7 +17,7 @@
 mod check_unique_node_ids;
 use check_unique_node_ids::*;
 
-use leo_compiler::{BuildOptions, Compiler, CompilerOptions};
+use leo_compiler::{BuildOptions, Compiler, CompilerOptions, OverflowMode};
 use leo_errors::{
     emitter::{Buffer, Emitter, Handler},
     LeoError,
@@ -85,22 +85,94 @@ pub fn get_build_options(test_config: &TestConfig) -> Vec<BuildOptions> {
                 .iter()
                 .map(|config| {
                     let config = config.as_mapping().expect("Expected the compiler configuration to be a mapping.");
-                    assert_eq!(
-                        config.len(),
-                        1,
-                        "A compiler configuration must have exactly one key-value pair. e.g. `dce_enabled`: true"
-                    );
-                    BuildOptions {
-                        dce_enabled: config
-                            .get(&serde_yaml::Value::String("dce_enabled".to_string()))
-                            .expect("Expected key `dce_enabled`")
-                            .as_bool()
-                            .expect("Expected value to be a boolean."),
+                    // Options that are not specified take on their default values.
+                    let mut build_options = BuildOptions { dce_enabled: true, ..Default::default() };
+                    for (key, value) in config.iter() {
+                        match key.as_str().expect("Expected the configuration key to be a string.") {
+                            "ssa_enabled" => {
+                                build_options.ssa_enabled = value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "flattening_enabled" => {
+                                build_options.flattening_enabled =
+                                    value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "dce_enabled" => {
+                                build_options.dce_enabled = value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "cse_enabled" => {
+                                build_options.cse_enabled = value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "temporary_elimination_enabled" => {
+                                build_options.temporary_elimination_enabled =
+                                    value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "constant_propagation_enabled" => {
+                                build_options.constant_propagation_enabled =
+                                    value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "unused_input_warnings_enabled" => {
+                                build_options.unused_input_warnings_enabled =
+                                    value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "assert_lowering_enabled" => {
+                                build_options.assert_lowering_enabled =
+                                    value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "return_folding_enabled" => {
+                                build_options.return_folding_enabled =
+                                    value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "finalize_folding_enabled" => {
+                                build_options.finalize_folding_enabled =
+                                    value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "mapping_update_rejection_enabled" => {
+                                build_options.mapping_update_rejection_enabled =
+                                    value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "debug_comments_enabled" => {
+                                build_options.debug_comments_enabled =
+                                    value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "source_map_enabled" => {
+                                build_options.source_map_enabled =
+                                    value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "annotation_comments_enabled" => {
+                                build_options.annotation_comments_enabled =
+                                    value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "trivial_inlining_threshold" => {
+                                build_options.trivial_inlining_threshold =
+                                    value.as_u64().expect("Expected value to be an integer.") as usize
+                            }
+                            "named_returns_enabled" => {
+                                build_options.named_returns_enabled =
+                                    value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "unreachable_assertions_enabled" => {
+                                build_options.unreachable_assertions_enabled =
+                                    value.as_bool().expect("Expected value to be a boolean.")
+                            }
+                            "max_loop_iterations" => {
+                                build_options.max_loop_iterations =
+                                    value.as_u64().expect("Expected value to be an integer.") as usize
+                            }
+                            "overflow_mode" => {
+                                build_options.overflow_mode = match value.as_str() {
+                                    Some("checked") => OverflowMode::Checked,
+                                    Some("wrapping") => OverflowMode::Wrapping,
+                                    _ => panic!("Expected value to be one of `checked` or `wrapping`."),
+                                }
+                            }
+                            key => panic!("Unknown compiler configuration key `{key}`."),
+                        }
                     }
+                    build_options
                 })
                 .collect()
         }
-        None => vec![BuildOptions { dce_enabled: true }],
+        None => vec![BuildOptions { dce_enabled: true, ..Default::default() }],
     }
 }
 
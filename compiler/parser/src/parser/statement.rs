@@ -77,13 +77,32 @@ impl ParserContext<'_> {
             }),
             _ => unreachable!("The call the `expect_any` ensures that only one of the three tokens is true."),
         };
+        // Parse the optional message.
+        let message = match self.eat(&Token::Comma) {
+            true => Some(self.parse_assert_message()?),
+            false => None,
+        };
         // Parse the right parenthesis token.
         self.expect(&Token::RightParen)?;
         // Parse the semicolon token.
         self.expect(&Token::Semicolon)?;
 
         // Return the assertion statement.
-        Ok(Statement::Assert(AssertStatement { variant, span, id: self.node_builder.next_id() }))
+        Ok(Statement::Assert(AssertStatement { variant, message, span, id: self.node_builder.next_id() }))
+    }
+
+    /// Returns the message of an assertion statement if the next token is a string literal.
+    /// Since Aleo instructions cannot print values, a message that interpolates values is rejected.
+    fn parse_assert_message(&mut self) -> Result<String> {
+        let message = match &self.token.token {
+            Token::StaticString(message) => message.clone(),
+            token => return Err(ParserError::unexpected_str(token, "string literal", self.token.span).into()),
+        };
+        self.bump();
+        if self.check(&Token::Comma) {
+            return Err(ParserError::assert_message_cannot_interpolate(self.token.span).into());
+        }
+        Ok(message)
     }
 
     /// Returns a [`AssignStatement`] AST node if the next tokens represent a assign, otherwise expects an expression statement.
@@ -75,6 +75,60 @@ impl ParserContext<'_> {
         ))
     }
 
+    /// Returns the length of an array type, which is a constant expression, e.g. `4` or `2 + 2`.
+    /// Since array types store their length as a number, the expression is evaluated as it is parsed.
+    fn parse_array_length(&mut self) -> Result<NonNegativeNumber> {
+        let start = self.token.span;
+        let length = self.parse_array_length_sum()?;
+        match length {
+            Some(length) => Ok(NonNegativeNumber::from(length)),
+            None => Err(ParserError::array_length_out_of_range(start + self.prev_token.span).into()),
+        }
+    }
+
+    /// Parses and evaluates a sum or difference of array length products.
+    /// Returns `None` if the result is out of range.
+    fn parse_array_length_sum(&mut self) -> Result<Option<usize>> {
+        let mut length = self.parse_array_length_product()?;
+        loop {
+            length = if self.eat(&Token::Add) {
+                let right = self.parse_array_length_product()?;
+                length.zip(right).and_then(|(left, right)| left.checked_add(right))
+            } else if self.eat(&Token::Sub) {
+                let right = self.parse_array_length_product()?;
+                length.zip(right).and_then(|(left, right)| left.checked_sub(right))
+            } else {
+                return Ok(length);
+            };
+        }
+    }
+
+    /// Parses and evaluates a product of array length factors.
+    /// Returns `None` if the result is out of range.
+    fn parse_array_length_product(&mut self) -> Result<Option<usize>> {
+        let mut length = self.parse_array_length_factor()?;
+        while self.eat(&Token::Mul) {
+            let right = self.parse_array_length_factor()?;
+            length = length.zip(right).and_then(|(left, right)| left.checked_mul(right));
+        }
+        Ok(length)
+    }
+
+    /// Parses and evaluates a whole number or a parenthesized array length.
+    /// Returns `None` if the result is out of range.
+    fn parse_array_length_factor(&mut self) -> Result<Option<usize>> {
+        match &self.token.token {
+            Token::Integer(_) => Ok(Some(self.eat_whole_number()?.0.value())),
+            Token::LeftParen => {
+                self.expect(&Token::LeftParen)?;
+                let length = self.parse_array_length_sum()?;
+                self.expect(&Token::RightParen)?;
+                Ok(length)
+            }
+            token => Err(ParserError::array_length_must_be_constant(token, self.token.span).into()),
+        }
+    }
+
     /// Returns a [`(Type, Span)`] tuple of AST nodes if the next token represents a type.
     /// Also returns the span of the parsed token.
     pub fn parse_type(&mut self) -> Result<(Type, Span)> {
@@ -88,7 +142,7 @@ impl ParserContext<'_> {
             // Parse the semi-colon.
             self.expect(&Token::Semicolon)?;
             // Parse the length.
-            let (length, _) = self.eat_whole_number()?;
+            let length = self.parse_array_length()?;
             // Parse the right bracket.
             self.expect(&Token::RightSquare)?;
             // Return the array type.
@@ -0,0 +1,193 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::CodeGenerator;
+
+use leo_ast::{
+    AccessExpression,
+    ArrayExpression,
+    AssertStatement,
+    AssertVariant,
+    AssignStatement,
+    BinaryExpression,
+    BinaryOperation,
+    CallExpression,
+    CastExpression,
+    Expression,
+    ExpressionStatement,
+    ExpressionVisitor,
+    Function,
+    Identifier,
+    ReturnStatement,
+    StatementVisitor,
+    StructExpression,
+    TernaryExpression,
+    TupleExpression,
+    UnaryExpression,
+};
+use leo_span::Symbol;
+
+use indexmap::IndexMap;
+
+impl CodeGenerator<'_> {
+    /// Estimates the circuit depth of the given function after code generation.
+    /// The function is expected to be consolidated, i.e. flattened, destructured, and inlined.
+    /// Ternaries and multiplicative operations each add one to the depth of their operands,
+    /// while all other operations are free. The estimate is the maximum depth along any path
+    /// through the body of the function. Note that the finalize block is not included.
+    pub fn estimate_depth(function: &Function) -> usize {
+        let mut estimator = DepthEstimator::default();
+        estimator.visit_block(&function.block);
+        estimator.max_depth
+    }
+}
+
+/// A read-only visitor that computes the depth of each variable defined in a function body.
+#[derive(Default)]
+struct DepthEstimator {
+    /// The depth of each variable defined so far. Inputs and undefined variables have depth zero.
+    depths: IndexMap<Symbol, usize>,
+    /// The maximum depth of any expression visited so far.
+    max_depth: usize,
+}
+
+impl DepthEstimator {
+    /// Returns the maximum depth of the given expressions.
+    fn max_depth_of<'a>(&mut self, expressions: impl IntoIterator<Item = &'a Expression>) -> usize {
+        expressions.into_iter().map(|expression| self.visit_expression(expression, &())).max().unwrap_or_default()
+    }
+
+    /// Records `depth` as a candidate for the maximum depth of the function.
+    fn record(&mut self, depth: usize) {
+        self.max_depth = self.max_depth.max(depth);
+    }
+}
+
+impl<'a> ExpressionVisitor<'a> for DepthEstimator {
+    type AdditionalInput = ();
+    type Output = usize;
+
+    fn visit_access(&mut self, input: &'a AccessExpression, _additional: &Self::AdditionalInput) -> Self::Output {
+        match input {
+            AccessExpression::Array(array) => self.max_depth_of([array.array.as_ref(), array.index.as_ref()]),
+            AccessExpression::AssociatedFunction(function) => self.max_depth_of(&function.arguments),
+            AccessExpression::Member(member) => self.visit_expression(&member.inner, &()),
+            AccessExpression::Tuple(tuple) => self.visit_expression(&tuple.tuple, &()),
+            AccessExpression::AssociatedConstant(_) => 0,
+        }
+    }
+
+    fn visit_array(&mut self, input: &'a ArrayExpression, _additional: &Self::AdditionalInput) -> Self::Output {
+        self.max_depth_of(&input.elements)
+    }
+
+    fn visit_binary(&mut self, input: &'a BinaryExpression, _additional: &Self::AdditionalInput) -> Self::Output {
+        let cost = match input.op {
+            BinaryOperation::Mul
+            | BinaryOperation::MulWrapped
+            | BinaryOperation::Div
+            | BinaryOperation::DivWrapped
+            | BinaryOperation::Rem
+            | BinaryOperation::RemWrapped
+            | BinaryOperation::Mod
+            | BinaryOperation::Pow
+            | BinaryOperation::PowWrapped => 1,
+            _ => 0,
+        };
+        cost + self.max_depth_of([input.left.as_ref(), input.right.as_ref()])
+    }
+
+    fn visit_call(&mut self, input: &'a CallExpression, _additional: &Self::AdditionalInput) -> Self::Output {
+        // Note that the depth of the callee is not known, so only the depth of the arguments is counted.
+        self.max_depth_of(&input.arguments)
+    }
+
+    fn visit_cast(&mut self, input: &'a CastExpression, _additional: &Self::AdditionalInput) -> Self::Output {
+        self.visit_expression(&input.expression, &())
+    }
+
+    fn visit_struct_init(&mut self, input: &'a StructExpression, _additional: &Self::AdditionalInput) -> Self::Output {
+        input
+            .members
+            .iter()
+            .map(|member| match &member.expression {
+                Some(expression) => self.visit_expression(expression, &()),
+                // A member without an expression is initialized with the variable of the same name.
+                None => self.visit_identifier(&member.identifier, &()),
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn visit_identifier(&mut self, input: &'a Identifier, _additional: &Self::AdditionalInput) -> Self::Output {
+        self.depths.get(&input.name).copied().unwrap_or_default()
+    }
+
+    fn visit_ternary(&mut self, input: &'a TernaryExpression, _additional: &Self::AdditionalInput) -> Self::Output {
+        1 + self.max_depth_of([input.condition.as_ref(), input.if_true.as_ref(), input.if_false.as_ref()])
+    }
+
+    fn visit_tuple(&mut self, input: &'a TupleExpression, _additional: &Self::AdditionalInput) -> Self::Output {
+        self.max_depth_of(&input.elements)
+    }
+
+    fn visit_unary(&mut self, input: &'a UnaryExpression, _additional: &Self::AdditionalInput) -> Self::Output {
+        self.visit_expression(&input.receiver, &())
+    }
+}
+
+impl<'a> StatementVisitor<'a> for DepthEstimator {
+    fn visit_assert(&mut self, input: &'a AssertStatement) {
+        let depth = match &input.variant {
+            AssertVariant::Assert(expression) => self.visit_expression(expression, &()),
+            AssertVariant::AssertEq(left, right) | AssertVariant::AssertNeq(left, right) => {
+                self.max_depth_of([left, right])
+            }
+        };
+        self.record(depth);
+    }
+
+    fn visit_assign(&mut self, input: &'a AssignStatement) {
+        let depth = self.visit_expression(&input.value, &());
+        self.record(depth);
+        // Every variable defined by the statement inherits the depth of the value.
+        match &input.place {
+            Expression::Identifier(identifier) => {
+                self.depths.insert(identifier.name, depth);
+            }
+            Expression::Tuple(tuple) => tuple.elements.iter().for_each(|element| {
+                if let Expression::Identifier(identifier) = element {
+                    self.depths.insert(identifier.name, depth);
+                }
+            }),
+            _ => {}
+        }
+    }
+
+    fn visit_expression_statement(&mut self, input: &'a ExpressionStatement) {
+        let depth = self.visit_expression(&input.expression, &());
+        self.record(depth);
+    }
+
+    fn visit_return(&mut self, input: &'a ReturnStatement) {
+        let depth = self.visit_expression(&input.expression, &());
+        self.record(depth);
+        if let Some(arguments) = &input.finalize_arguments {
+            let depth = self.max_depth_of(arguments);
+            self.record(depth);
+        }
+    }
+}
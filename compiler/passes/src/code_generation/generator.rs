@@ -16,12 +16,61 @@
 
 use crate::{CallGraph, StructGraph, SymbolTable, TypeTable};
 
-use leo_ast::{Function, Program, ProgramId};
+use leo_ast::{Function, Program, ProgramId, Type};
+use leo_errors::{emitter::Handler, CompilerError, Result};
 use leo_span::Symbol;
 
 use indexmap::IndexMap;
+use serde::Serialize;
+
+/// Determines how integer arithmetic is lowered to Aleo instructions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowMode {
+    /// Emits checked instructions, e.g. `add`, which halt on overflow.
+    #[default]
+    Checked,
+    /// Emits wrapping instructions, e.g. `add.w`, which wrap around on overflow.
+    Wrapping,
+}
+
+/// The options that configure code generation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CodeGeneratorOptions {
+    /// Determines whether integer arithmetic is emitted with checked or wrapping instructions.
+    pub overflow_mode: OverflowMode,
+    /// Whether each statement is preceded by a comment containing the statement.
+    pub debug_comments_enabled: bool,
+    /// Whether the source location of each emitted instruction is recorded.
+    pub source_map_enabled: bool,
+    /// Whether each function is preceded by comments containing its annotations.
+    pub annotation_comments_enabled: bool,
+}
+
+/// The source location of an emitted Aleo instruction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct InstructionLocation {
+    /// The name of the function containing the instruction.
+    pub function: String,
+    /// The index of the instruction within the function, counting the instructions in its finalize block after those in its body.
+    pub index: usize,
+    /// The 1-based line of the statement that produced the instruction, if it has a location in the source.
+    pub line: Option<usize>,
+    /// The 1-based column of the statement that produced the instruction, if it has a location in the source.
+    pub column: Option<usize>,
+    /// The message of the assertion that produced the instruction, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Serializes the source locations of emitted instructions into a JSON string.
+pub fn instruction_locations_to_json_string(locations: &[InstructionLocation]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(locations)
+        .map_err(|e| CompilerError::failed_to_convert_instruction_map_to_json_string(&e))?)
+}
 
 pub struct CodeGenerator<'a> {
+    /// The error handler.
+    pub(crate) handler: &'a Handler,
     /// The symbol table for the program.
     pub(crate) symbol_table: &'a SymbolTable,
     /// A mapping between expressions and their types.
@@ -31,6 +80,8 @@ pub struct CodeGenerator<'a> {
     /// The call graph for the program.
     pub(crate) _call_graph: &'a CallGraph,
     /// A counter to track the next available register.
+    /// Note that registers cannot be reused, since Aleo instructions require that each register is assigned exactly once,
+    /// in increasing order, within a function.
     pub(crate) next_register: u64,
     /// Reference to the current function.
     pub(crate) current_function: Option<&'a Function>,
@@ -53,19 +104,34 @@ pub struct CodeGenerator<'a> {
     pub(crate) program: &'a Program,
     // The program ID of the current program.
     pub(crate) program_id: Option<ProgramId>,
+    /// Determines whether integer arithmetic is emitted with checked or wrapping instructions.
+    pub(crate) overflow_mode: OverflowMode,
+    /// The number of instructions emitted for each function, including its finalize block.
+    pub(crate) instruction_counts: IndexMap<Symbol, usize>,
+    /// Whether each statement is preceded by a comment containing the statement.
+    pub(crate) debug_comments_enabled: bool,
+    /// Whether the source location of each emitted instruction is recorded.
+    pub(crate) source_map_enabled: bool,
+    /// Whether each function is preceded by comments containing its annotations.
+    pub(crate) annotation_comments_enabled: bool,
+    /// The source location of each emitted instruction, in the order the instructions are emitted.
+    pub(crate) instruction_locations: Vec<InstructionLocation>,
 }
 
 impl<'a> CodeGenerator<'a> {
     /// Initializes a new `CodeGenerator`.
     pub fn new(
+        handler: &'a Handler,
         symbol_table: &'a SymbolTable,
         type_table: &'a TypeTable,
         struct_graph: &'a StructGraph,
         _call_graph: &'a CallGraph,
         program: &'a Program,
+        overflow_mode: OverflowMode,
     ) -> Self {
         // Initialize variable mapping.
         Self {
+            handler,
             symbol_table,
             type_table,
             struct_graph,
@@ -80,6 +146,63 @@ impl<'a> CodeGenerator<'a> {
             futures: Vec::new(),
             program,
             program_id: None,
+            overflow_mode,
+            instruction_counts: IndexMap::new(),
+            debug_comments_enabled: false,
+            source_map_enabled: false,
+            instruction_locations: Vec::new(),
+            annotation_comments_enabled: false,
         }
     }
+
+    /// Sets the options that configure code generation, other than the overflow mode, which is set on construction.
+    pub fn with_options(self, options: CodeGeneratorOptions) -> Self {
+        self.with_debug_comments(options.debug_comments_enabled)
+            .with_source_map(options.source_map_enabled)
+            .with_annotation_comments(options.annotation_comments_enabled)
+    }
+
+    /// Sets whether each statement is preceded by a comment containing the statement.
+    pub fn with_debug_comments(mut self, enabled: bool) -> Self {
+        self.debug_comments_enabled = enabled;
+        self
+    }
+
+    /// Sets whether the source location of each emitted instruction is recorded.
+    pub fn with_source_map(mut self, enabled: bool) -> Self {
+        self.source_map_enabled = enabled;
+        self
+    }
+
+    /// Sets whether each function is preceded by comments containing its annotations.
+    pub fn with_annotation_comments(mut self, enabled: bool) -> Self {
+        self.annotation_comments_enabled = enabled;
+        self
+    }
+
+    /// Returns the source location of each emitted instruction, in the order the instructions are emitted.
+    /// Note that this is empty unless the source map is enabled.
+    pub fn instruction_locations(&self) -> &[InstructionLocation] {
+        &self.instruction_locations
+    }
+
+    /// Returns the output type of the function being visited, or of its finalize block if it is being visited.
+    /// Returns an error if no function is being visited.
+    pub fn current_output_type(&self) -> Result<&'a Type> {
+        let function = self.active_function("return statement")?;
+        match (self.in_finalize, &function.finalize) {
+            (true, Some(finalize)) => Ok(&finalize.output_type),
+            _ => Ok(&function.output_type),
+        }
+    }
+
+    /// Returns the function being visited, or an error naming the `construct` that requires one if there is none.
+    pub(crate) fn active_function(&self, construct: &str) -> Result<&'a Function> {
+        self.current_function.ok_or_else(|| CompilerError::no_current_function(construct).into())
+    }
+
+    /// Returns the number of instructions emitted for each function, including its finalize block.
+    pub fn stats(&self) -> &IndexMap<Symbol, usize> {
+        &self.instruction_counts
+    }
 }
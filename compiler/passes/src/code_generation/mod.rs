@@ -17,6 +17,10 @@
 pub mod generator;
 pub use generator::*;
 
+mod depth_estimation;
+
+mod unlowered_statements;
+
 mod visit_expressions;
 
 mod visit_program;
@@ -28,16 +32,1500 @@ mod visit_type;
 use crate::{CallGraph, Pass, StructGraph, SymbolTable, TypeTable};
 
 use leo_ast::{Ast, Program};
-use leo_errors::Result;
+use leo_errors::{emitter::Handler, Result};
 
 impl<'a> Pass for CodeGenerator<'a> {
-    type Input = (&'a Ast, &'a SymbolTable, &'a TypeTable, &'a StructGraph, &'a CallGraph, &'a Program);
-    type Output = Result<String>;
+    type Input = (
+        &'a Ast,
+        &'a Handler,
+        &'a SymbolTable,
+        &'a TypeTable,
+        &'a StructGraph,
+        &'a CallGraph,
+        &'a Program,
+        CodeGeneratorOptions,
+    );
+    type Output = Result<(String, Vec<InstructionLocation>)>;
+
+    fn do_pass(
+        (ast, handler, symbol_table, type_table, struct_graph, call_graph, program, options): Self::Input,
+    ) -> Self::Output {
+        let mut generator =
+            Self::new(handler, symbol_table, type_table, struct_graph, call_graph, program, options.overflow_mode)
+                .with_options(options);
+        // Statements that cannot be lowered are reported before any instructions are generated.
+        generator.check_unlowered_statements(ast.as_repr());
+        handler.last_err().map_err(|e| *e)?;
 
-    fn do_pass((ast, symbol_table, type_table, struct_graph, call_graph, program): Self::Input) -> Self::Output {
-        let mut generator = Self::new(symbol_table, type_table, struct_graph, call_graph, program);
         let bytecode = generator.visit_program(ast.as_repr());
+        handler.last_err().map_err(|e| *e)?;
+
+        Ok((bytecode, generator.instruction_locations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_helpers::TestCompiler,
+        DeadCodeEliminator,
+        Destructurer,
+        FunctionInliner,
+        StaticSingleAssignerOptions,
+        SymbolTableCreator,
+        TypeChecker,
+    };
+
+    use leo_ast::{
+        AssertStatement,
+        AssertVariant,
+        BinaryExpression,
+        BinaryOperation,
+        DeclarationType,
+        DefinitionStatement,
+        Expression,
+        Identifier,
+        IntegerType,
+        Literal,
+        NodeBuilder,
+        ReturnStatement,
+        Statement,
+        TupleExpression,
+        Type,
+    };
+    use leo_span::{symbol::create_session_if_not_set_then, Symbol};
+
+    use indexmap::IndexMap;
+
+    /// Runs all of the compiler passes on the given program and returns the generated bytecode.
+    fn generate_bytecode(source: &str) -> String {
+        compile(source, OverflowMode::default()).0
+    }
+
+    /// Runs all of the compiler passes on the given program.
+    /// Returns the generated bytecode and the number of instructions emitted for each function.
+    fn compile(source: &str, overflow_mode: OverflowMode) -> (String, IndexMap<Symbol, usize>) {
+        compile_with_imports(source, &[], overflow_mode)
+    }
+
+    /// Runs all of the compiler passes on the given program, which may call the given imported programs.
+    /// Note that the imports are added to the AST directly, since the parser reads imported programs from disk.
+    fn compile_with_imports(
+        source: &str,
+        imports: &[&str],
+        overflow_mode: OverflowMode,
+    ) -> (String, IndexMap<Symbol, usize>) {
+        compile_with_ssa_options(source, imports, overflow_mode, Default::default())
+    }
+
+    /// Runs all of the compiler passes on the given program, converting it into static single assignment form
+    /// with the given options, e.g. to enable constant propagation.
+    fn compile_with_ssa_options(
+        source: &str,
+        imports: &[&str],
+        overflow_mode: OverflowMode,
+        ssa_options: StaticSingleAssignerOptions,
+    ) -> (String, IndexMap<Symbol, usize>) {
+        let compiler = TestCompiler::default();
+        let TestCompiler { handler, node_builder, type_table, assigner, .. } = &compiler;
+
+        let mut program = compiler.parse(source).into_repr();
+        for import in imports {
+            let import = compiler.parse(import).into_repr();
+            let name = *import.program_scopes.keys().next().unwrap();
+            program.imports.insert(name, (import, Default::default()));
+        }
+        let ast = Ast::new(program);
+        let (symbol_table, struct_graph, call_graph) = compiler.type_check_ast(&ast);
+        let (ast, symbol_table) = compiler.unroll(ast, symbol_table);
+        let ast = compiler.static_single_assignment_with_options(ast, &symbol_table, ssa_options);
+        let ast = compiler.flatten(ast, &symbol_table);
+        let ast = Destructurer::do_pass((ast, type_table, node_builder, assigner)).unwrap();
+        let ast = FunctionInliner::do_pass((ast, node_builder, &call_graph, assigner, type_table, 0)).unwrap();
+        let ast = DeadCodeEliminator::do_pass((ast, node_builder)).unwrap();
+
+        let mut generator = CodeGenerator::new(
+            handler,
+            &symbol_table,
+            type_table,
+            &struct_graph,
+            &call_graph,
+            ast.as_repr(),
+            overflow_mode,
+        );
+        let bytecode = generator.visit_program(ast.as_repr());
+        (bytecode, generator.stats().clone())
+    }
+
+    /// The options that enable debug comments.
+    const DEBUG_COMMENTS: CodeGeneratorOptions = CodeGeneratorOptions {
+        overflow_mode: OverflowMode::Checked,
+        debug_comments_enabled: true,
+        source_map_enabled: false,
+        annotation_comments_enabled: false,
+    };
+
+    /// The options that enable the source map.
+    const SOURCE_MAP: CodeGeneratorOptions = CodeGeneratorOptions {
+        overflow_mode: OverflowMode::Checked,
+        debug_comments_enabled: false,
+        source_map_enabled: true,
+        annotation_comments_enabled: false,
+    };
+
+    /// The options that enable annotation comments.
+    const ANNOTATION_COMMENTS: CodeGeneratorOptions = CodeGeneratorOptions {
+        overflow_mode: OverflowMode::Checked,
+        debug_comments_enabled: false,
+        source_map_enabled: false,
+        annotation_comments_enabled: true,
+    };
+
+    /// Runs the compiler passes on the given program and returns the generated bytecode and instruction locations.
+    /// If flattening is disabled, the passes that follow it are skipped, as in the compiler.
+    /// If code generation fails, the messages of the reported errors are returned instead.
+    fn generate_bytecode_with_options(
+        source: &str,
+        flattening_enabled: bool,
+        options: CodeGeneratorOptions,
+    ) -> std::result::Result<(String, Vec<InstructionLocation>), String> {
+        let compiler = TestCompiler::default();
+        let TestCompiler { handler, node_builder, type_table, assigner, .. } = &compiler;
+
+        let (ast, symbol_table, struct_graph, call_graph) = compiler.type_check(source);
+        let (ast, symbol_table) = compiler.unroll(ast, symbol_table);
+        let mut ast = compiler.static_single_assignment(ast, &symbol_table);
+        if flattening_enabled {
+            ast = compiler.flatten(ast, &symbol_table);
+            ast = Destructurer::do_pass((ast, type_table, node_builder, assigner)).unwrap();
+            ast = FunctionInliner::do_pass((ast, node_builder, &call_graph, assigner, type_table, 0)).unwrap();
+        }
+
+        CodeGenerator::do_pass((
+            &ast,
+            handler,
+            &symbol_table,
+            type_table,
+            &struct_graph,
+            &call_graph,
+            ast.as_repr(),
+            options,
+        ))
+        .map_err(|_| compiler.errors().join("\n"))
+    }
+
+    #[test]
+    fn test_hash_and_commit_instructions() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    transition main(a: u8, r: scalar) -> (field, group) {
+        let hash: field = BHP256::hash_to_field(a);
+        let commitment: group = Pedersen64::commit_to_group(a, r);
+        return (hash, commitment);
+    }
+}",
+            );
+
+            assert!(bytecode.contains("    hash.bhp256 r0 into r2 as field;\n"));
+            assert!(bytecode.contains("    commit.ped64 r0 r1 into r3 as group;\n"));
+        })
+    }
+
+    #[test]
+    fn test_composite_assertions() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    struct Point {
+        x: u8,
+        y: u8,
+    }
+
+    struct Line {
+        start: Point,
+        end: Point,
+    }
+
+    transition same(a: Line, b: Line) {
+        assert_eq(a, b);
+    }
+
+    transition different(a: Point, b: Point) {
+        assert_neq(a, b);
+    }
+}",
+            );
+
+            // Nested structs are compared member by member.
+            assert!(bytecode.contains(concat!(
+                "    assert.eq r0.start.x r1.start.x;\n",
+                "    assert.eq r0.start.y r1.start.y;\n",
+                "    assert.eq r0.end.x r1.end.x;\n",
+                "    assert.eq r0.end.y r1.end.y;\n",
+            )));
+            // Structs are unequal if any pair of members is unequal.
+            assert!(bytecode.contains(concat!(
+                "    is.neq r0.x r1.x into r2;\n",
+                "    is.neq r0.y r1.y into r3;\n",
+                "    or r2 r3 into r4;\n",
+                "    assert.eq r4 true;\n",
+            )));
+        })
+    }
+
+    /// Constructs a definition statement binding `place` to `value`.
+    fn definition(node_builder: &NodeBuilder, place: Expression, value: Expression) -> Statement {
+        Statement::Definition(DefinitionStatement {
+            declaration_type: DeclarationType::Let,
+            place,
+            type_: Type::Err,
+            value,
+            span: Default::default(),
+            id: node_builder.next_id(),
+        })
+    }
+
+    #[test]
+    fn test_tuple_definitions() {
+        create_session_if_not_set_then(|_| {
+            let handler = Handler::default();
+            let node_builder = NodeBuilder::default();
+            let symbol_table = SymbolTable::default();
+            let type_table = TypeTable::default();
+            let graph = StructGraph::new(Default::default());
+            let ast =
+                leo_parser::parse_ast(&handler, &node_builder, "program test.aleo {}", Default::default()).unwrap();
+
+            let identifier =
+                |name: &str| Expression::Identifier(Identifier::new(Symbol::intern(name), node_builder.next_id()));
+            let literal = |value: &str| {
+                Expression::Literal(Literal::Integer(
+                    IntegerType::U8,
+                    value.to_string(),
+                    Default::default(),
+                    node_builder.next_id(),
+                ))
+            };
+            let tuple = |elements: Vec<Expression>| {
+                Expression::Tuple(TupleExpression { elements, span: Default::default(), id: node_builder.next_id() })
+            };
+
+            // let (a, b) = (1u8, 2u8);
+            let flat = definition(
+                &node_builder,
+                tuple(vec![identifier("a"), identifier("b")]),
+                tuple(vec![literal("1"), literal("2")]),
+            );
+            // let (c, (d, e)) = (3u8, (4u8, 5u8));
+            let nested = definition(
+                &node_builder,
+                tuple(vec![identifier("c"), tuple(vec![identifier("d"), identifier("e")])]),
+                tuple(vec![literal("3"), tuple(vec![literal("4"), literal("5")])]),
+            );
+
+            let mut generator = CodeGenerator::new(
+                &handler,
+                &symbol_table,
+                &type_table,
+                &graph,
+                &graph,
+                ast.as_repr(),
+                OverflowMode::default(),
+            );
+            assert!(generator.visit_statement(&flat).is_empty());
+            assert!(generator.visit_statement(&nested).is_empty());
+
+            for (name, operand) in [("a", "1u8"), ("b", "2u8"), ("c", "3u8"), ("d", "4u8"), ("e", "5u8")] {
+                assert_eq!(generator.variable_mapping.get(&Symbol::intern(name)).unwrap(), operand);
+            }
+        })
+    }
+
+    #[test]
+    fn test_binary_literal_operand_types() {
+        create_session_if_not_set_then(|_| {
+            let (handler, buf) = Handler::new_with_buf();
+            let node_builder = NodeBuilder::default();
+            let symbol_table = SymbolTable::default();
+            let type_table = TypeTable::default();
+            let graph = StructGraph::new(Default::default());
+            let ast =
+                leo_parser::parse_ast(&handler, &node_builder, "program test.aleo {}", Default::default()).unwrap();
+
+            // `x: u64` is held in `r0` and `y: u8` is held in `r1`.
+            let x = Symbol::intern("x");
+            let x_identifier = Identifier::new(x, node_builder.next_id());
+            type_table.insert(x_identifier.id, Type::Integer(IntegerType::U64));
+            let y = Symbol::intern("y");
+            let y_identifier = Identifier::new(y, node_builder.next_id());
+            type_table.insert(y_identifier.id, Type::Integer(IntegerType::U8));
+
+            let literal = |type_: IntegerType, value: &str| {
+                Expression::Literal(Literal::Integer(
+                    type_,
+                    value.to_string(),
+                    Default::default(),
+                    node_builder.next_id(),
+                ))
+            };
+            let binary = |op: BinaryOperation, left: Expression, right: Expression| {
+                Expression::Binary(BinaryExpression {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    op,
+                    span: Default::default(),
+                    id: node_builder.next_id(),
+                })
+            };
+
+            // `x + 1`, where constant folding left the literal as a `u8`.
+            let folded =
+                binary(BinaryOperation::Add, Expression::Identifier(x_identifier), literal(IntegerType::U8, "1"));
+            // `1 + x`, where the literal is the left operand.
+            let swapped =
+                binary(BinaryOperation::Add, literal(IntegerType::U8, "1"), Expression::Identifier(x_identifier));
+            // `x + 1u64`, where the literal already has the type of `x`.
+            let typed =
+                binary(BinaryOperation::Add, Expression::Identifier(x_identifier), literal(IntegerType::U64, "1"));
+            // `x << 1u8`, where the shift amount need not have the type of `x`.
+            let shift =
+                binary(BinaryOperation::Shl, Expression::Identifier(x_identifier), literal(IntegerType::U8, "1"));
+            // `y + 300u16`, where the literal is out of the range of the type of `y`.
+            let out_of_range =
+                binary(BinaryOperation::Add, Expression::Identifier(y_identifier), literal(IntegerType::U16, "300"));
+
+            let mut generator = CodeGenerator::new(
+                &handler,
+                &symbol_table,
+                &type_table,
+                &graph,
+                &graph,
+                ast.as_repr(),
+                OverflowMode::default(),
+            );
+            generator.variable_mapping.insert(&x, "r0".to_string());
+            generator.variable_mapping.insert(&y, "r1".to_string());
+            generator.next_register = 2;
+
+            // Literals are re-typed, rather than cast at runtime.
+            let (register, instructions) = generator.visit_expression(&folded);
+            assert_eq!(instructions, "    add r0 1u64 into r2;\n");
+            assert_eq!(register, "r2");
+
+            let (_, instructions) = generator.visit_expression(&swapped);
+            assert_eq!(instructions, "    add 1u64 r0 into r3;\n");
+
+            let (_, instructions) = generator.visit_expression(&typed);
+            assert_eq!(instructions, "    add r0 1u64 into r4;\n");
+
+            let (_, instructions) = generator.visit_expression(&shift);
+            assert_eq!(instructions, "    shl r0 1u8 into r5;\n");
+            assert_eq!(handler.err_count(), 0);
+
+            // A literal that is out of range is reported, and no instruction is emitted.
+            let (_, instructions) = generator.visit_expression(&out_of_range);
+            assert!(instructions.is_empty());
+            assert_eq!(generator.next_register, 6);
+            assert_eq!(handler.err_count(), 1);
+            let error = buf.extract_errs().last_entry().unwrap().to_string();
+            assert!(error.contains("The literal `300u16` must have the type `u8`"), "{error}");
+        })
+    }
+
+    #[test]
+    fn test_propagated_literal_operands() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(x: u64) -> u64 {
+        let one: u64 = 1u64;
+        let y: u64 = x + one;
+        return one + y;
+    }
+}";
+            let ssa_options = StaticSingleAssignerOptions { constant_propagation_enabled: true, ..Default::default() };
+            let (bytecode, _) = compile_with_ssa_options(program, &[], OverflowMode::default(), ssa_options);
+
+            // The propagated literals are used as operands of the type of the other operand, without a runtime cast.
+            assert!(bytecode.contains("    add r0 1u64 into r1;\n"), "{bytecode}");
+            assert!(bytecode.contains("    add 1u64 r1 into r2;\n"), "{bytecode}");
+            assert!(!bytecode.contains("cast"), "{bytecode}");
+        })
+    }
+
+    #[test]
+    fn test_return_without_current_function() {
+        create_session_if_not_set_then(|_| {
+            let (handler, buf) = Handler::new_with_buf();
+            let node_builder = NodeBuilder::default();
+            let symbol_table = SymbolTable::default();
+            let type_table = TypeTable::default();
+            let graph = StructGraph::new(Default::default());
+            let ast =
+                leo_parser::parse_ast(&handler, &node_builder, "program test.aleo {}", Default::default()).unwrap();
+
+            let mut generator = CodeGenerator::new(
+                &handler,
+                &symbol_table,
+                &type_table,
+                &graph,
+                &graph,
+                ast.as_repr(),
+                OverflowMode::default(),
+            );
+            assert!(generator.current_output_type().is_err());
+
+            // Generating a return statement outside of a function reports an error instead of panicking.
+            let return_ = Statement::Return(ReturnStatement {
+                expression: Expression::Literal(Literal::Integer(
+                    IntegerType::U8,
+                    "1".to_string(),
+                    Default::default(),
+                    node_builder.next_id(),
+                )),
+                finalize_arguments: None,
+                span: Default::default(),
+                id: node_builder.next_id(),
+            });
+            assert!(generator.visit_statement(&return_).is_empty());
+            assert_eq!(handler.err_count(), 1);
+            let error = buf.extract_errs().last_entry().unwrap().to_string();
+            assert!(error.contains("cannot generate code for a return statement outside of a function"), "{error}");
+        })
+    }
+
+    #[test]
+    fn test_instruction_counts() {
+        create_session_if_not_set_then(|_| {
+            let (_, stats) = compile(
+                "
+program test.aleo {
+    function helper(a: u8) -> u8 {
+        return a * a;
+    }
+
+    transition main(a: u8, b: u8) -> u8 {
+        let c: u8 = a + b;
+        assert_neq(c, 0u8);
+        return helper(c);
+    }
+}",
+                OverflowMode::default(),
+            );
+
+            // `mul` and `output`.
+            assert_eq!(stats.get(&Symbol::intern("helper")), Some(&2));
+            // `add`, `assert.neq`, `call`, and `output`.
+            assert_eq!(stats.get(&Symbol::intern("main")), Some(&4));
+        })
+    }
+
+    /// Runs the compiler passes up to code generation on the given program.
+    /// Returns the estimated depth of each function, in declaration order.
+    fn estimate_depths(source: &str) -> Vec<usize> {
+        let compiler = TestCompiler::default();
+        let TestCompiler { node_builder, type_table, assigner, .. } = &compiler;
+
+        let (ast, symbol_table, _, call_graph) = compiler.type_check(source);
+        let (ast, symbol_table) = compiler.unroll(ast, symbol_table);
+        let ast = compiler.static_single_assignment(ast, &symbol_table);
+        let ast = compiler.flatten(ast, &symbol_table);
+        let ast = Destructurer::do_pass((ast, type_table, node_builder, assigner)).unwrap();
+        let ast = FunctionInliner::do_pass((ast, node_builder, &call_graph, assigner, type_table, 0)).unwrap();
+        let ast = DeadCodeEliminator::do_pass((ast, node_builder)).unwrap();
+
+        let scope = ast.as_repr().program_scopes.values().next().unwrap();
+        scope.functions.iter().map(|(_, function)| CodeGenerator::estimate_depth(function)).collect()
+    }
+
+    #[test]
+    fn test_estimate_depth_of_flat_function() {
+        create_session_if_not_set_then(|_| {
+            let depths = estimate_depths(
+                "
+program test.aleo {
+    transition main(a: u8, b: u8) -> u8 {
+        let c: u8 = a + b;
+        let d: u8 = c - a;
+        assert_neq(d, 0u8);
+        return d ^ b;
+    }
+
+    transition product(a: u8, b: u8) -> u8 {
+        let c: u8 = a * b;
+        return c + a;
+    }
+}",
+            );
+
+            // Additions, subtractions, and bitwise operations are free, while each multiplication adds one.
+            assert_eq!(depths, vec![0, 1]);
+        })
+    }
+
+    #[test]
+    fn test_estimate_depth_of_nested_ternaries() {
+        create_session_if_not_set_then(|_| {
+            let depths = estimate_depths(
+                "
+program test.aleo {
+    transition main(flag: bool, a: u8, b: u8) -> u8 {
+        let c: u8 = flag ? a : b;
+        let d: u8 = flag ? c : a;
+        let e: u8 = flag ? b : d;
+        let f: u8 = a + b;
+        return e * f;
+    }
+}",
+            );
+
+            // Each ternary builds on the previous one, followed by a multiplication.
+            assert_eq!(depths, vec![4]);
+        })
+    }
+
+    #[test]
+    fn test_record_and_scalar_output_visibilities() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    record Token {
+        owner: address,
+        amount: u64,
+    }
+
+    transition mint(receiver: address, amount: u64) -> (Token, public u64) {
+        return (Token { owner: receiver, amount: amount }, amount);
+    }
+}",
+            );
+
+            assert!(bytecode.contains("    output r2 as Token.record;\n    output r1 as u64.public;\n"));
+        })
+    }
+
+    #[test]
+    fn test_record_outputs() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    record Token {
+        amount: u64,
+        owner: address,
+    }
+
+    transition mint(receiver: address, amount: u64) -> Token {
+        return Token { amount: amount, owner: receiver };
+    }
+}",
+            );
+
+            // The `owner` is declared first, as required by Aleo, regardless of its position in the Leo record.
+            assert!(bytecode.contains("record Token:\n    owner as address.private;\n    amount as u64.private;\n"));
+            // The record is constructed with its `owner` first, and output by name.
+            assert!(bytecode.contains("    cast r0 r1 into r2 as Token.record;\n    output r2 as Token.record;\n"));
+        })
+    }
+
+    #[test]
+    fn test_constant_array_lengths() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    transition main(a: [u8; 2 + 2], b: [[bool; (1 + 2) * 2 - 4]; 3]) -> u8 {
+        return a[0u32];
+    }
+}",
+            );
+
+            // Constant lengths are evaluated to a number in the type.
+            assert!(bytecode.contains("    input r0 as [u8; 4u32].private;\n"), "{bytecode}");
+            assert!(bytecode.contains("    input r1 as [[boolean; 2u32]; 3u32].private;\n"), "{bytecode}");
+
+            // Returns the error produced by parsing a program whose input has the given array length.
+            let parse_error = |length: &str| {
+                let program = format!("program test.aleo {{ transition main(a: [u8; {length}]) {{}} }}");
+                let handler = Handler::default();
+                match leo_parser::parse_ast(&handler, &NodeBuilder::default(), &program, Default::default()) {
+                    Ok(_) => panic!("Expected the length `{length}` to be rejected."),
+                    Err(error) => error.to_string(),
+                }
+            };
+
+            // Lengths that are not constant are reported.
+            let error = parse_error("2 + n");
+            assert!(error.contains("The length of an array must be a constant expression -- found 'n'"), "{error}");
+
+            // Lengths that are out of range are reported as well.
+            let error = parse_error("1 - 2");
+            assert!(error.contains("The length of the array is out of range."), "{error}");
+        })
+    }
+
+    #[test]
+    fn test_external_calls() {
+        create_session_if_not_set_then(|_| {
+            let import = "
+program math.aleo {
+    transition double(a: u8) -> u8 {
+        return a + a;
+    }
+
+    transition swap(a: u8, b: u8) -> (u8, u8) {
+        return (b, a);
+    }
+}";
+            let program = "
+program test.aleo {
+    function identity(a: u8) -> u8 {
+        return a;
+    }
+
+    transition main(a: u8, b: u8) -> (u8, u8, u8) {
+        let c: u8 = math.leo/double(a);
+        let (d, e): (u8, u8) = math.leo/swap(c, b);
+        let f: u8 = identity(d);
+        return (d, e, f);
+    }
+}";
+            let (bytecode, _) = compile_with_imports(program, &[import], OverflowMode::default());
+
+            // Calls to imported programs are qualified with the program ID.
+            assert!(bytecode.starts_with("import math.aleo;\n"), "{bytecode}");
+            assert!(bytecode.contains("    call math.aleo/double r0 into r2;\n"), "{bytecode}");
+            assert!(bytecode.contains("    call math.aleo/swap r2 r1 into r3 r4;\n"), "{bytecode}");
+            // Local calls are not.
+            assert!(bytecode.contains("    call identity r3 into r5;\n"), "{bytecode}");
+        })
+    }
+
+    #[test]
+    fn test_unqualified_external_calls() {
+        create_session_if_not_set_then(|_| {
+            let import = "
+program math.aleo {
+    transition double(a: u8) -> u8 {
+        return a + a;
+    }
+}";
+            let program = "
+program test.aleo {
+    transition main(a: u8) -> u8 {
+        return double(a);
+    }
+}";
+            let (bytecode, _) = compile_with_imports(program, &[import], OverflowMode::default());
+
+            // Calls to imported transitions are qualified with the program they are imported from.
+            assert!(bytecode.contains("    call math.aleo/double r0 into r1;\n"), "{bytecode}");
+        })
+    }
+
+    #[test]
+    fn test_hash_and_commit_destination_types() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    transition main(a: u8, s: scalar) -> (field, u8, address, group) {
+        let h: field = BHP256::hash_to_field(a);
+        let k: u8 = Keccak256::hash_to_u8(a);
+        let c: address = BHP256::commit_to_address(a, s);
+        let p: group = Pedersen64::commit_to_group(a, s);
+        return (h, k, c, p);
+    }
+}",
+            );
+
+            assert!(bytecode.contains("    hash.bhp256 r0 into r2 as field;\n"));
+            assert!(bytecode.contains("    hash.keccak256 r0 into r3 as u8;\n"));
+            assert!(bytecode.contains("    commit.bhp256 r0 r1 into r4 as address;\n"));
+            assert!(bytecode.contains("    commit.ped64 r0 r1 into r5 as group;\n"));
+        })
+    }
+
+    #[test]
+    fn test_equality_values() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    struct Point {
+        x: u8,
+        y: u8,
+    }
+
+    transition main(a: u8, b: u8, p: Point, q: Point) -> (bool, bool, bool) {
+        let same: bool = a == b;
+        let different: bool = a != b;
+        let same_point: bool = p == q;
+        return (same, different, same_point);
+    }
+}",
+            );
+
+            // Equalities used as values are stored in registers, rather than asserted.
+            assert!(bytecode.contains("    is.eq r0 r1 into r4;\n    is.neq r0 r1 into r5;\n"));
+            // Structs are compared directly.
+            assert!(bytecode.contains("    is.eq r2 r3 into r6;\n"));
+            assert!(!bytecode.contains("assert"));
+        })
+    }
+
+    #[test]
+    fn test_tuple_assertions() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    transition main(a: u8, b: u8, c: u8, d: u8) {
+        let x: (u8, u8) = (a, b);
+        let y: (u8, u8) = (c, d);
+        assert_eq(x, y);
+        assert_neq(x, y);
+    }
+}",
+            );
+
+            // Tuples are equal if each pair of elements is equal.
+            assert!(bytecode.contains("    assert.eq r0 r2;\n    assert.eq r1 r3;\n"));
+            // Tuples are unequal if any pair of elements is unequal.
+            assert!(bytecode.contains(
+                "    is.neq r0 r2 into r4;\n    is.neq r1 r3 into r5;\n    or r4 r5 into r6;\n    assert.eq r6 true;\n"
+            ));
+        })
+    }
+
+    #[test]
+    fn test_tuple_assertion_length_mismatch() {
+        create_session_if_not_set_then(|_| {
+            let (handler, buf) = Handler::new_with_buf();
+            let node_builder = NodeBuilder::default();
+            let symbol_table = SymbolTable::default();
+            let type_table = TypeTable::default();
+            let graph = StructGraph::new(Default::default());
+            let ast =
+                leo_parser::parse_ast(&handler, &node_builder, "program test.aleo {}", Default::default()).unwrap();
+
+            let mut generator = CodeGenerator::new(
+                &handler,
+                &symbol_table,
+                &type_table,
+                &graph,
+                &graph,
+                ast.as_repr(),
+                OverflowMode::default(),
+            );
+
+            // Constructs a tuple of `u8` literals.
+            let tuple = |values: &[&str]| {
+                Expression::Tuple(TupleExpression {
+                    elements: values
+                        .iter()
+                        .map(|value| {
+                            Expression::Literal(Literal::Integer(
+                                IntegerType::U8,
+                                value.to_string(),
+                                Default::default(),
+                                node_builder.next_id(),
+                            ))
+                        })
+                        .collect(),
+                    span: Default::default(),
+                    id: node_builder.next_id(),
+                })
+            };
+
+            // Comparing tuples of different lengths reports an error instead of panicking, and compares the leading pairs.
+            let assert = Statement::Assert(AssertStatement {
+                variant: AssertVariant::AssertEq(tuple(&["1", "2"]), tuple(&["1", "2", "3"])),
+                message: None,
+                span: Default::default(),
+                id: node_builder.next_id(),
+            });
+            let instructions = generator.visit_statement(&assert);
+            assert_eq!(instructions, "    assert.eq 1u8 1u8;\n    assert.eq 2u8 2u8;\n");
+            assert_eq!(handler.err_count(), 1);
+            let error = buf.extract_errs().last_entry().unwrap().to_string();
+            assert!(error.contains("The tuples compared by the assertion have different lengths, 2 and 3."), "{error}");
+        })
+    }
+
+    #[test]
+    fn test_unary_instructions() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(b: bool, x: i8) -> (bool, i8, i8, i8) {
+        return (!b, -x, x.abs(), x.abs_wrapped());
+    }
+}";
+
+            let (bytecode, _) = compile(program, OverflowMode::Checked);
+            assert!(bytecode.contains("    not r0 into r2;\n"));
+            assert!(bytecode.contains("    neg r1 into r3;\n"));
+            assert!(bytecode.contains("    abs r1 into r4;\n"));
+            assert!(bytecode.contains("    abs.w r1 into r5;\n"));
+
+            // Absolute values are wrapping if the overflow mode is `Wrapping`.
+            let (bytecode, _) = compile(program, OverflowMode::Wrapping);
+            assert!(bytecode.contains("    abs.w r1 into r4;\n"));
+            assert!(bytecode.contains("    abs.w r1 into r5;\n"));
+        })
+    }
+
+    #[test]
+    fn test_division_instructions() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(a: u8, b: u8, c: i8, d: i8) -> (u8, u8, i8, i8, u8, i8) {
+        return (a / b, a % b, c / d, c % d, a.div_wrapped(b), c.rem_wrapped(d));
+    }
+}";
+            // Division and remainder are checked, for both signed and unsigned operands.
+            let (bytecode, _) = compile(program, OverflowMode::Checked);
+            assert!(bytecode.contains("    div r0 r1 into r4;\n"), "{bytecode}");
+            assert!(bytecode.contains("    rem r0 r1 into r5;\n"), "{bytecode}");
+            assert!(bytecode.contains("    div r2 r3 into r6;\n"), "{bytecode}");
+            assert!(bytecode.contains("    rem r2 r3 into r7;\n"), "{bytecode}");
+            // The wrapping variants are always wrapping.
+            assert!(bytecode.contains("    div.w r0 r1 into r8;\n"), "{bytecode}");
+            assert!(bytecode.contains("    rem.w r2 r3 into r9;\n"), "{bytecode}");
+
+            // Division and remainder are wrapping if the overflow mode is `Wrapping`.
+            let (bytecode, _) = compile(program, OverflowMode::Wrapping);
+            assert!(bytecode.contains("    div.w r0 r1 into r4;\n"), "{bytecode}");
+            assert!(bytecode.contains("    rem.w r0 r1 into r5;\n"), "{bytecode}");
+            assert!(bytecode.contains("    div.w r2 r3 into r6;\n"), "{bytecode}");
+            assert!(bytecode.contains("    rem.w r2 r3 into r7;\n"), "{bytecode}");
+        })
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        create_session_if_not_set_then(|_| {
+            // Returns the error reported when type checking the given expression.
+            let type_check = |expression: &str, type_: &str| {
+                let handler = Handler::default();
+                let node_builder = NodeBuilder::default();
+                let source = format!(
+                    "
+program test.aleo {{
+    transition main(a: {type_}) -> {type_} {{
+        return {expression};
+    }}
+}}"
+                );
+                let ast = leo_parser::parse_ast(&handler, &node_builder, &source, Default::default()).unwrap();
+                let symbol_table = SymbolTableCreator::do_pass((&ast, &handler)).unwrap();
+                TypeChecker::do_pass((&ast, &handler, symbol_table, &TypeTable::default())).err().map(|e| e.to_string())
+            };
+
+            for (expression, type_) in
+                [("a / 0u8", "u8"), ("a % 0i8", "i8"), ("a.div_wrapped(0u8)", "u8"), ("a / 0field", "field")]
+            {
+                let error = type_check(expression, type_).expect("Expected division by zero to be rejected.");
+                assert!(error.contains("is zero, so the operation always fails"), "{error}");
+            }
+            assert!(type_check("a / 10u8", "u8").is_none());
+        })
+    }
+
+    #[test]
+    fn test_group_coordinates() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    transition main(g: group) -> (field, field, field) {
+        return (g.x, g.y, group::GEN.x);
+    }
+}",
+            );
+
+            // The coordinates are cast out of the group element.
+            assert!(bytecode.contains("    cast r0 into r1 as group.x;\n"), "{bytecode}");
+            assert!(bytecode.contains("    cast r0 into r2 as group.y;\n"), "{bytecode}");
+            assert!(bytecode.contains("    cast group::GEN into r3 as group.x;\n"), "{bytecode}");
+
+            // Group elements do not have any other members.
+            let handler = Handler::default();
+            let node_builder = NodeBuilder::default();
+            let source = "
+program test.aleo {
+    transition main(g: group) -> field {
+        return g.z;
+    }
+}";
+            let ast = leo_parser::parse_ast(&handler, &node_builder, source, Default::default()).unwrap();
+            let symbol_table = SymbolTableCreator::do_pass((&ast, &handler)).unwrap();
+            match TypeChecker::do_pass((&ast, &handler, symbol_table, &TypeTable::default())) {
+                Err(error) => assert!(error.to_string().contains("`z` is not a coordinate"), "{error}"),
+                Ok(_) => panic!("Expected the access to be rejected."),
+            }
+        })
+    }
+
+    #[test]
+    fn test_associated_constants() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    transition main(a: group) -> group {
+        let g: group = group::GEN;
+        let h: group = g + a;
+        return helper(group::GEN) + h;
+    }
+
+    function helper(x: group) -> group {
+        return x + group::GEN;
+    }
+
+    transition constant() -> group {
+        return group::GEN;
+    }
+}",
+            );
+
+            // The constant is used directly as an operand of binary instructions and calls.
+            assert!(bytecode.contains("    add r0 group::GEN into "), "{bytecode}");
+            assert!(bytecode.contains("    add group::GEN r0 into "), "{bytecode}");
+            assert!(bytecode.contains("    call helper group::GEN into "), "{bytecode}");
+            // A returned constant is assigned to a variable by SSA, which refers to the constant.
+            assert!(bytecode.contains("    output group::GEN as group.private;"), "{bytecode}");
+        })
+    }
+
+    #[test]
+    fn test_mapping_declarations() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    struct Point {
+        x: u8,
+        y: u8,
+    }
+
+    mapping balances: address => u64;
+    mapping points: field => Point;
+
+    transition main(a: u8) -> u8 {
+        return a;
+    }
+}",
+            );
+
+            let balances = "\nmapping balances:\n\tkey as address.public;\n\tvalue as u64.public;\n";
+            let points = "\nmapping points:\n\tkey as field.public;\n\tvalue as Point.public;\n";
+            assert!(bytecode.contains(balances));
+            assert!(bytecode.contains(points));
+
+            // Mappings are declared before the functions.
+            assert!(bytecode.find(points).unwrap() < bytecode.find("function main:").unwrap());
+        })
+    }
+
+    #[test]
+    fn test_function_order() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    mapping counts: address => u8;
+
+    transition first(a: u8) -> u8 {
+        return double(a);
+    }
+
+    transition second(a: u8) {
+        return then finalize(self.caller, a);
+    }
+
+    finalize second(owner: address, a: u8) {
+        Mapping::set(counts, owner, a);
+    }
+
+    transition third(a: u8) -> u8 {
+        return helper(a);
+    }
+
+    inline double(a: u8) -> u8 {
+        return a + a;
+    }
+
+    function helper(a: u8) -> u8 {
+        return a * a;
+    }
+}",
+            );
+
+            // The functions are emitted in declaration order, except that `helper` precedes its caller.
+            // The finalize block immediately follows its transition, and the inlined function is not emitted.
+            let headers = bytecode
+                .lines()
+                .filter(|line| ["function", "finalize", "closure"].iter().any(|keyword| line.starts_with(keyword)))
+                .collect::<Vec<_>>();
+            assert_eq!(headers, [
+                "function first:",
+                "function second:",
+                "finalize second:",
+                "closure helper:",
+                "function third:"
+            ]);
+        })
+    }
+
+    #[test]
+    fn test_function_keywords() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    mapping counts: address => u8;
+
+    transition deposit(a: u8) -> u8 {
+        return helper(a) then finalize(self.caller, a);
+    }
+
+    finalize deposit(owner: address, a: u8) {
+        Mapping::set(counts, owner, a);
+    }
+
+    transition main(a: u8) -> u8 {
+        return helper(a);
+    }
+
+    @noflatten
+    function helper(a: u8) -> u8 {
+        return a * a;
+    }
+}",
+            );
+
+            // Transitions are emitted as `function`s, whether or not they have a finalize block,
+            // and standard functions are emitted as `closure`s, regardless of their annotations.
+            let headers = bytecode
+                .lines()
+                .filter(|line| ["function", "finalize", "closure"].iter().any(|keyword| line.starts_with(keyword)))
+                .collect::<Vec<_>>();
+            assert_eq!(headers, ["closure helper:", "function deposit:", "finalize deposit:", "function main:"]);
+        });
+    }
+
+    #[test]
+    fn test_assert_instructions() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    transition main(flag: bool, a: u8, b: u8) {
+        assert(flag);
+        assert_eq(a, b);
+        assert_neq(a, 1u8);
+    }
+}",
+            );
+
+            // A boolean assertion is lowered to a comparison against `true`.
+            assert!(bytecode.contains("    assert.eq r0 true;\n"));
+            // Equality assertions compare their operands directly.
+            assert!(bytecode.contains("    assert.eq r1 r2;\n"));
+            assert!(bytecode.contains("    assert.neq r1 1u8;\n"));
+            assert_eq!(bytecode.matches("assert").count(), 3);
+        })
+    }
+
+    #[test]
+    fn test_assert_messages() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(flag: bool, a: u8, b: u8) {
+        assert(flag, \"flag is not set\");
+        assert_eq(a, b, \"expected {} to equal {}\");
+        assert_neq(a, 1u8);
+    }
+}";
+            let (bytecode, locations) = generate_bytecode_with_options(program, true, SOURCE_MAP).unwrap();
+
+            // The messages are emitted as comments on the assertions, and placeholders are not interpolated.
+            assert!(bytecode.contains("    assert.eq r0 true; // flag is not set\n"), "{bytecode}");
+            assert!(bytecode.contains("    assert.eq r1 r2; // expected {} to equal {}\n"), "{bytecode}");
+            // Assertions without a message are unchanged.
+            assert!(bytecode.contains("    assert.neq r1 1u8;\n"), "{bytecode}");
+
+            // The messages are also recorded with the locations of the assertions.
+            let messages = locations.iter().map(|location| location.message.as_deref()).collect::<Vec<_>>();
+            assert_eq!(messages, [Some("flag is not set"), Some("expected {} to equal {}"), None]);
+            let json = serde_json::to_value(&locations[0]).unwrap();
+            assert_eq!(json["message"], "flag is not set");
+            assert!(serde_json::to_value(&locations[2]).unwrap().get("message").is_none());
+
+            // Values cannot be interpolated into a message.
+            let handler = Handler::default();
+            let source = "
+program test.aleo {
+    transition main(a: u8, b: u8) {
+        assert_eq(a, b, \"expected {}\", a);
+    }
+}";
+            match leo_parser::parse_ast(&handler, &NodeBuilder::default(), source, Default::default()) {
+                Err(error) => assert!(error.to_string().contains("cannot interpolate values"), "{error}"),
+                Ok(_) => panic!("Expected the interpolated message to be rejected."),
+            }
+        })
+    }
+
+    #[test]
+    fn test_ternary_instructions() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    transition main(flag: bool, a: address, b: address, g: group, h: group, s: scalar, t: scalar) -> (address, group, scalar) {
+        return (flag ? a : b, flag ? g : h, flag ? s : t);
+    }
+}",
+            );
+
+            // Ternary expressions over addresses, groups, and scalars are lowered to a single `ternary` instruction.
+            assert!(bytecode.contains("    ternary r0 r1 r2 into r7;\n"));
+            assert!(bytecode.contains("    ternary r0 r3 r4 into r8;\n"));
+            assert!(bytecode.contains("    ternary r0 r5 r6 into r9;\n"));
+            assert!(bytecode.contains("    output r7 as address.private;\n"));
+            assert!(bytecode.contains("    output r8 as group.private;\n"));
+            assert!(bytecode.contains("    output r9 as scalar.private;\n"));
+        })
+    }
+
+    #[test]
+    fn test_negation_instructions() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    transition main(a: i8, f: field, g: group) -> (i8, field, group) {
+        return (-a, -f, -g);
+    }
+}",
+            );
+
+            // Integers, fields, and groups are all negated with the `neg` instruction.
+            assert!(bytecode.contains("    neg r0 into r3;\n"));
+            assert!(bytecode.contains("    neg r1 into r4;\n"));
+            assert!(bytecode.contains("    neg r2 into r5;\n"));
+            assert!(bytecode.contains("    output r3 as i8.private;\n"));
+            assert!(bytecode.contains("    output r4 as field.private;\n"));
+            assert!(bytecode.contains("    output r5 as group.private;\n"));
+        })
+    }
+
+    #[test]
+    fn test_logical_operators() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    mapping flags: address => bool;
+
+    transition main(flag: bool, a: u8, b: u8) -> bool {
+        return flag && a == b;
+    }
+
+    transition check(flag: bool) {
+        return then finalize(self.caller, flag);
+    }
+
+    finalize check(owner: address, flag: bool) {
+        assert(flag || Mapping::contains(flags, owner));
+    }
+}",
+            );
+
+            // The rhs is evaluated before the operator, regardless of the value of the lhs.
+            // Note that this is why a rhs with side effects is rejected by static single assignment.
+            assert!(bytecode.contains("    is.eq r1 r2 into r3;\n    and r0 r3 into r4;\n"), "{bytecode}");
+            assert!(bytecode.contains("    output r4 as boolean.private;\n"), "{bytecode}");
+            assert!(
+                bytecode.contains("    contains flags[r0] into r2;\n    or r1 r2 into r3;\n    assert.eq r3 true;\n"),
+                "{bytecode}"
+            );
+        })
+    }
+
+    #[test]
+    fn test_tuple_call_destinations() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    function helper(x: u8) -> (u8, u8) {
+        return (x, x + 1u8);
+    }
+
+    transition main(x: u8) -> u8 {
+        let (a, b): (u8, u8) = helper(x);
+        return a + b;
+    }
+}",
+            );
+
+            // Each component of the returned tuple is bound to its own register.
+            assert!(bytecode.contains("    output r0 as u8;\n    output r1 as u8;\n"));
+            assert!(bytecode.contains("    call helper r0 into r1 r2;\n"));
+            assert!(bytecode.contains("    add r1 r2 into r3;\n"));
+        })
+    }
+
+    #[test]
+    fn test_registers_are_assigned_once() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    transition main(a: u8, b: u8) -> u8 {
+        let c: u8 = a + b;
+        let d: u8 = c * a;
+        let e: u8 = d - b;
+        return e + a;
+    }
+}",
+            );
+
+            // Even though the live ranges of `c`, `d`, and `e` do not overlap, each value is assigned a new register.
+            let destinations = bytecode
+                .lines()
+                .filter_map(|line| line.split(" into ").nth(1))
+                .map(|destination| destination.trim_end_matches(';').to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(destinations, ["r2", "r3", "r4", "r5"]);
+        })
+    }
+
+    #[test]
+    fn test_conditional_without_flattening() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(flag: bool, a: u8) -> u8 {
+        let b: u8 = a;
+        if (flag) {
+            b = a + a;
+        }
+        return b;
+    }
+}";
+            assert!(generate_bytecode_with_options(program, true, Default::default()).is_ok());
+
+            // Code generation reports the conditional statement instead of panicking.
+            let error = generate_bytecode_with_options(program, false, Default::default()).unwrap_err();
+            assert!(error.contains("Conditional statements cannot be compiled to Aleo instructions"), "{error}");
+        })
+    }
+
+    #[test]
+    fn test_empty_function_bodies() {
+        create_session_if_not_set_then(|_| {
+            // A transition with an empty body is compiled to a function with only inputs, which is a valid no-op.
+            let program = "
+program test.aleo {
+    transition main(a: u8) {}
+}";
+            let (bytecode, _) = generate_bytecode_with_options(program, true, DEBUG_COMMENTS).unwrap();
+            assert!(bytecode.contains("function main:\n    input r0 as u8.private;\n"), "{bytecode}");
+
+            // A standard function with an empty body would be compiled to a closure without instructions.
+            let program = "
+program test.aleo {
+    function helper(a: u8) {}
+
+    transition main(a: u8) -> u8 {
+        return a;
+    }
+}";
+            let error = generate_bytecode_with_options(program, true, DEBUG_COMMENTS).unwrap_err();
+            assert!(error.contains("The function `helper` has an empty body"), "{error}");
+        })
+    }
+
+    #[test]
+    fn test_debug_comments() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(a: u8, b: u8) -> u8 {
+        assert_neq(a, b);
+        return a + b;
+    }
+}";
+            let (bytecode, _) = generate_bytecode_with_options(program, true, Default::default()).unwrap();
+            assert!(!bytecode.contains("//"));
+
+            // Each statement is preceded by a comment, which does not change the instructions.
+            let (commented, _) = generate_bytecode_with_options(program, true, DEBUG_COMMENTS).unwrap();
+            assert!(commented.contains("    // assert_neq(a, b);\n    assert.neq r0 r1;\n"));
+            let uncommented = commented.lines().filter(|line| !line.trim_start().starts_with("//")).collect::<Vec<_>>();
+            assert_eq!(uncommented, bytecode.lines().collect::<Vec<_>>());
+        })
+    }
+
+    #[test]
+    fn test_instruction_locations() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(a: u8, b: u8, c: bool) -> u8 {
+        assert(c);
+        assert_eq(a, b);
+        return a + b;
+    }
+}";
+            let (bytecode, locations) = generate_bytecode_with_options(program, true, SOURCE_MAP).unwrap();
+
+            // There is exactly one entry for each instruction, in the order the instructions are emitted.
+            let instructions = bytecode
+                .lines()
+                .skip_while(|line| *line != "function main:")
+                .skip(1)
+                .take_while(|line| !line.is_empty())
+                .filter(|line| !line.trim_start().starts_with("input "))
+                .collect::<Vec<_>>();
+            assert_eq!(instructions.len(), 4, "{bytecode}");
+            assert_eq!(locations.len(), instructions.len());
+            for (i, location) in locations.iter().enumerate() {
+                assert_eq!(location.function, "main");
+                assert_eq!(location.index, i);
+            }
+
+            // The assertions are mapped to the lines and columns of the statements that produced them.
+            assert_eq!(instructions[0], "    assert.eq r2 true;");
+            assert_eq!((locations[0].line, locations[0].column), (Some(4), Some(9)));
+            assert_eq!(instructions[1], "    assert.eq r0 r1;");
+            assert_eq!((locations[1].line, locations[1].column), (Some(5), Some(9)));
+
+            // The entries are serialized with their source locations.
+            let json = serde_json::to_value(&locations[1]).unwrap();
+            assert_eq!(json, serde_json::json!({ "function": "main", "index": 1, "line": 5, "column": 9 }));
+
+            // No locations are recorded unless the source map is enabled.
+            let (_, locations) = generate_bytecode_with_options(program, true, Default::default()).unwrap();
+            assert!(locations.is_empty());
+        })
+    }
+
+    #[test]
+    fn test_annotation_comments() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(a: u8) -> u8 {
+        return helper(a);
+    }
+
+    @noflatten
+    function helper(a: u8) -> u8 {
+        return a * a;
+    }
+}";
+            // The annotation is emitted as a comment directly above the header, which is unchanged.
+            let (commented, _) = generate_bytecode_with_options(program, true, ANNOTATION_COMMENTS).unwrap();
+            assert!(commented.contains("\n// @noflatten\nclosure helper:\n"), "{commented}");
+
+            let (bytecode, _) = generate_bytecode_with_options(program, true, Default::default()).unwrap();
+            assert!(!bytecode.contains("@noflatten"));
+            assert!(bytecode.contains("\nclosure helper:\n"));
+            let uncommented = commented.lines().filter(|line| !line.starts_with("//")).collect::<Vec<_>>();
+            assert_eq!(uncommented, bytecode.lines().collect::<Vec<_>>());
+        })
+    }
+
+    #[test]
+    fn test_finalize_sections() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    mapping balances: address => u64;
+
+    transition deposit(public amount: u64) {
+        return then finalize(self.caller, amount);
+    }
+
+    finalize deposit(owner: address, amount: u64) {
+        let current: u64 = Mapping::get(balances, owner);
+        Mapping::set(balances, owner, current + amount);
+    }
+}",
+            );
+
+            // The transition passes the arguments of the finalize block to an `async` instruction.
+            let (function, finalize) = bytecode.split_once("\nfinalize deposit:\n").unwrap();
+            assert!(function.contains("    async deposit self.caller r0 into r1;\n"), "{bytecode}");
+
+            // The finalize block declares its inputs as public, and numbers its registers from zero.
+            let lines = finalize.lines().map(str::trim).filter(|line| !line.is_empty()).collect::<Vec<_>>();
+            assert_eq!(lines, [
+                "input r0 as address.public;",
+                "input r1 as u64.public;",
+                "get balances[r0] into r2;",
+                "add r2 r1 into r3;",
+                "set r3 into balances[r0];"
+            ]);
+        })
+    }
+
+    #[test]
+    fn test_conditional_finalize_sections() {
+        create_session_if_not_set_then(|_| {
+            let bytecode = generate_bytecode(
+                "
+program test.aleo {
+    mapping counts: u8 => u8;
+
+    transition main(flag: bool, a: u8) {
+        if (flag) {
+            return;
+        }
+        return then finalize(a);
+    }
+
+    finalize main(a: u8) {
+        let count: u8 = Mapping::get_or_use(counts, a, 0u8);
+        Mapping::set(counts, a, count + 1u8);
+    }
+}",
+            );
+
+            // The transition always finalizes, passing whether it would have finalized as an additional argument.
+            let (function, finalize) = bytecode.split_once("\nfinalize main:\n").unwrap();
+            let async_ = function.lines().map(str::trim).find(|line| line.starts_with("async ")).unwrap();
+            let operands = async_.split_whitespace().collect::<Vec<_>>();
+            assert_eq!(operands.len(), 6, "{bytecode}");
+            assert_eq!(operands[..3], ["async", "main", "r1"]);
 
-        Ok(bytecode)
+            // The finalize block receives the guard as an additional public input, and skips its body unless it holds.
+            // Note that the mapping update is part of the body, so it is only executed if the function finalizes.
+            let lines = finalize.lines().map(str::trim).filter(|line| !line.is_empty()).collect::<Vec<_>>();
+            assert_eq!(lines[..3], [
+                "input r0 as u8.public;",
+                "input r1 as boolean.public;",
+                "branch.eq r1 false to end_finalize;"
+            ]);
+            assert_eq!(lines.last(), Some(&"position end_finalize;"), "{bytecode}");
+            assert!(
+                lines.iter().any(|line| line.starts_with("set ") && line.ends_with(" into counts[r0];")),
+                "{bytecode}"
+            );
+        })
     }
 }
@@ -0,0 +1,95 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::CodeGenerator;
+
+use leo_ast::{
+    ConditionalStatement,
+    Expression,
+    ExpressionVisitor,
+    Finalize,
+    Function,
+    IterationStatement,
+    Program,
+    ProgramVisitor,
+    Statement,
+    StatementVisitor,
+};
+use leo_errors::{emitter::Handler, CompilerError};
+
+impl CodeGenerator<'_> {
+    /// Reports each conditional and iteration statement in the program, since they cannot be lowered to Aleo instructions.
+    /// These statements are only in the AST if the flattening pass is disabled or a loop was not unrolled.
+    pub(crate) fn check_unlowered_statements(&self, program: &Program) {
+        UnloweredStatementDetector { handler: self.handler }.visit_program(program);
+    }
+
+    /// Returns the conditional statement with which flattening guards the body of a conditionally executed finalize block,
+    /// along with the statements that follow it, if `finalize` is guarded.
+    /// Such a statement is the first statement of the block, has no `else` branch, is conditioned on the final input of
+    /// the block, and is followed by at most its return statement. Code generation lowers it to a branch over the body.
+    pub(crate) fn finalize_guard(finalize: &Finalize) -> Option<(&ConditionalStatement, &[Statement])> {
+        let (conditional, rest) = match finalize.block.statements.split_first()? {
+            (Statement::Conditional(conditional), rest) => (conditional, rest),
+            _ => return None,
+        };
+        let guard = finalize.input.last()?.identifier();
+        let is_guard = match &conditional.condition {
+            Expression::Identifier(condition) => condition.name == guard.name,
+            _ => false,
+        };
+        let is_last = rest.iter().all(|statement| matches!(statement, Statement::Return(_)));
+        (is_guard && conditional.otherwise.is_none() && is_last).then_some((conditional, rest))
+    }
+}
+
+/// A read-only visitor that reports the statements that code generation cannot lower.
+struct UnloweredStatementDetector<'a> {
+    /// The error handler.
+    handler: &'a Handler,
+}
+
+impl<'a> ExpressionVisitor<'a> for UnloweredStatementDetector<'_> {
+    type AdditionalInput = ();
+    type Output = ();
+}
+
+impl<'a> StatementVisitor<'a> for UnloweredStatementDetector<'_> {
+    fn visit_conditional(&mut self, input: &'a ConditionalStatement) {
+        self.handler.emit_err(CompilerError::conditional_statement_not_flattened(input.span));
+    }
+
+    fn visit_iteration(&mut self, input: &'a IterationStatement) {
+        self.handler.emit_err(CompilerError::iteration_statement_not_unrolled(input.span));
+    }
+}
+
+impl<'a> ProgramVisitor<'a> for UnloweredStatementDetector<'_> {
+    /// Visits the function body and the finalize block, if it exists.
+    /// Note that the conditional statement guarding a finalize block is lowered, so only its body is visited.
+    fn visit_function(&mut self, input: &'a Function) {
+        self.visit_block(&input.block);
+        if let Some(finalize) = &input.finalize {
+            match CodeGenerator::finalize_guard(finalize) {
+                Some((guard, rest)) => {
+                    self.visit_block(&guard.then);
+                    rest.iter().for_each(|statement| self.visit_statement(statement));
+                }
+                None => self.visit_block(&finalize.block),
+            }
+        }
+    }
+}
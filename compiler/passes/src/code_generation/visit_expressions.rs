@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::CodeGenerator;
+use crate::{CodeGenerator, OverflowMode};
 use leo_ast::{
     AccessExpression,
     ArrayAccess,
@@ -30,6 +30,7 @@ use leo_ast::{
     Identifier,
     Literal,
     MemberAccess,
+    Node,
     StructExpression,
     TernaryExpression,
     TupleExpression,
@@ -38,7 +39,8 @@ use leo_ast::{
     UnaryOperation,
     UnitExpression,
 };
-use leo_span::sym;
+use leo_errors::CompilerError;
+use leo_span::{sym, Symbol};
 use std::borrow::Borrow;
 
 use std::fmt::Write as _;
@@ -85,13 +87,48 @@ impl<'a> CodeGenerator<'a> {
         let (left_operand, left_instructions) = self.visit_expression(&input.left);
         let (right_operand, right_instructions) = self.visit_expression(&input.right);
 
-        let opcode = match input.op {
+        // The exponent of `pow` and the shift amount of `shl` and `shr` need not have the type of the other operand.
+        let (left_operand, right_operand) = match input.op {
+            BinaryOperation::Pow
+            | BinaryOperation::PowWrapped
+            | BinaryOperation::Shl
+            | BinaryOperation::ShlWrapped
+            | BinaryOperation::Shr
+            | BinaryOperation::ShrWrapped => (left_operand, right_operand),
+            _ => match (
+                self.retype_literal_operand(&input.left, &input.right, left_operand),
+                self.retype_literal_operand(&input.right, &input.left, right_operand),
+            ) {
+                (Some(left_operand), Some(right_operand)) => (left_operand, right_operand),
+                // The out of range literal has been reported, so no instruction is emitted.
+                _ => return (String::new(), String::new()),
+            },
+        };
+
+        // Integer arithmetic is lowered to its wrapping variant if the overflow mode is `Wrapping`.
+        let op = match (self.overflow_mode, self.type_table.get(&input.id)) {
+            (OverflowMode::Wrapping, Some(Type::Integer(_))) => match input.op {
+                BinaryOperation::Add => BinaryOperation::AddWrapped,
+                BinaryOperation::Div => BinaryOperation::DivWrapped,
+                BinaryOperation::Mul => BinaryOperation::MulWrapped,
+                BinaryOperation::Pow => BinaryOperation::PowWrapped,
+                BinaryOperation::Rem => BinaryOperation::RemWrapped,
+                BinaryOperation::Shl => BinaryOperation::ShlWrapped,
+                BinaryOperation::Shr => BinaryOperation::ShrWrapped,
+                BinaryOperation::Sub => BinaryOperation::SubWrapped,
+                op => op,
+            },
+            _ => input.op,
+        };
+
+        let opcode = match op {
             BinaryOperation::Add => String::from("add"),
             BinaryOperation::AddWrapped => String::from("add.w"),
             BinaryOperation::And => String::from("and"),
             BinaryOperation::BitwiseAnd => String::from("and"),
             BinaryOperation::Div => String::from("div"),
             BinaryOperation::DivWrapped => String::from("div.w"),
+            // Note that `is.eq` and `is.neq` compare structs and records directly, so they need not be compared member-wise.
             BinaryOperation::Eq => String::from("is.eq"),
             BinaryOperation::Gte => String::from("gte"),
             BinaryOperation::Gt => String::from("gt"),
@@ -132,6 +169,34 @@ impl<'a> CodeGenerator<'a> {
         (destination_register, instructions)
     }
 
+    /// Re-types `operand` to the type of `other`, if it is an integer literal of a different integer type.
+    /// This is necessary since constant folding may leave a literal whose type must be inferred from the other operand,
+    /// while AVM requires both operands of a binary instruction to have the same type.
+    /// Returns the operand to use in the binary instruction, or `None` if the literal is out of the range of the type.
+    fn retype_literal_operand(
+        &self,
+        operand: &'a Expression,
+        other: &'a Expression,
+        register: String,
+    ) -> Option<String> {
+        match (operand, self.type_table.get(&other.id())) {
+            (Expression::Literal(literal @ Literal::Integer(type_, ..)), Some(Type::Integer(other_type)))
+                if *type_ != other_type =>
+            {
+                let retyped = literal.with_integer_type(other_type);
+                if retyped.is_none() {
+                    self.handler.emit_err(CompilerError::integer_literal_out_of_range(
+                        literal,
+                        other_type,
+                        literal.span(),
+                    ));
+                }
+                retyped.map(|literal| literal.to_string())
+            }
+            _ => Some(register),
+        }
+    }
+
     fn visit_cast(&mut self, input: &'a CastExpression) -> (String, String) {
         let (expression_operand, mut instructions) = self.visit_expression(&input.expression);
 
@@ -140,8 +205,11 @@ impl<'a> CodeGenerator<'a> {
         // Increment the register counter.
         self.next_register += 1;
 
-        let cast_instruction =
-            format!("    cast {expression_operand} into {destination_register} as {};\n", input.type_);
+        // Note that the operand may be a register holding an intermediate value produced by SSA.
+        let cast_instruction = format!(
+            "    cast {expression_operand} into {destination_register} as {};\n",
+            Self::visit_type(&input.type_)
+        );
 
         // Concatenate the instructions.
         instructions.push_str(&cast_instruction);
@@ -184,13 +252,21 @@ impl<'a> CodeGenerator<'a> {
     fn visit_unary(&mut self, input: &'a UnaryExpression) -> (String, String) {
         let (expression_operand, expression_instructions) = self.visit_expression(&input.receiver);
 
+        // Integer absolute values are lowered to their wrapping variant if the overflow mode is `Wrapping`.
+        let op = match (self.overflow_mode, self.type_table.get(&input.id), input.op) {
+            (OverflowMode::Wrapping, Some(Type::Integer(_)), UnaryOperation::Abs) => UnaryOperation::AbsWrapped,
+            (_, _, op) => op,
+        };
+
         // Note that non-empty suffixes must be preceded by a space.
-        let (opcode, suffix) = match input.op {
+        let (opcode, suffix) = match op {
             UnaryOperation::Abs => ("abs", ""),
             UnaryOperation::AbsWrapped => ("abs.w", ""),
             UnaryOperation::Double => ("double", ""),
             UnaryOperation::Inverse => ("inv", ""),
             UnaryOperation::Not => ("not", ""),
+            // Note that the same instruction negates integers, fields, and groups.
+            // Type checking guarantees that other types, e.g. booleans, are not negated.
             UnaryOperation::Negate => ("neg", ""),
             UnaryOperation::Square => ("square", ""),
             UnaryOperation::SquareRoot => ("sqrt", ""),
@@ -303,8 +379,9 @@ impl<'a> CodeGenerator<'a> {
     }
 
     // group::GEN -> group::GEN
+    // Note that SSA uses associated constants directly as the operands of binary expressions and calls.
     fn visit_associated_constant(&mut self, input: &'a AssociatedConstant) -> (String, String) {
-        (format!("{input}"), String::new())
+        (format!("{}::{}", Self::visit_type(&input.ty), input.name), String::new())
     }
 
     // Pedersen64::hash() -> hash.ped64
@@ -332,11 +409,9 @@ impl<'a> CodeGenerator<'a> {
         // Helper function to construct the instruction associated with a simple function call.
         // This assumes that the function call has one output.
         let mut construct_simple_function_call = |function: &Identifier, variant: &str, arguments: Vec<String>| {
-            // Split function into [opcode, return type] e.g. hash_to_field -> [hash, field]
-            let function_name = function.name.to_string();
-            let mut names = function_name.split("_to_");
-            let opcode = names.next().expect("failed to get opcode");
-            let return_type = names.next().expect("failed to get type");
+            // Look up the opcode and the destination type, e.g. hash_to_field -> (hash, field).
+            let (opcode, return_type) = Self::hash_or_commit_instruction(function.name)
+                .expect("Type checking guarantees that hash and commit functions are supported.");
 
             let mut instruction = format!("    {opcode}.{variant}");
             for argument in arguments {
@@ -506,6 +581,31 @@ impl<'a> CodeGenerator<'a> {
         (destination, instructions)
     }
 
+    /// Returns the opcode and the destination type of the AVM instruction for the given hash or commit function.
+    /// Returns `None` if the function is not a hash or commit function.
+    fn hash_or_commit_instruction(function: Symbol) -> Option<(&'static str, &'static str)> {
+        Some(match function {
+            sym::commit_to_address => ("commit", "address"),
+            sym::commit_to_field => ("commit", "field"),
+            sym::commit_to_group => ("commit", "group"),
+            sym::hash_to_address => ("hash", "address"),
+            sym::hash_to_field => ("hash", "field"),
+            sym::hash_to_group => ("hash", "group"),
+            sym::hash_to_i8 => ("hash", "i8"),
+            sym::hash_to_i16 => ("hash", "i16"),
+            sym::hash_to_i32 => ("hash", "i32"),
+            sym::hash_to_i64 => ("hash", "i64"),
+            sym::hash_to_i128 => ("hash", "i128"),
+            sym::hash_to_u8 => ("hash", "u8"),
+            sym::hash_to_u16 => ("hash", "u16"),
+            sym::hash_to_u32 => ("hash", "u32"),
+            sym::hash_to_u64 => ("hash", "u64"),
+            sym::hash_to_u128 => ("hash", "u128"),
+            sym::hash_to_scalar => ("hash", "scalar"),
+            _ => return None,
+        })
+    }
+
     fn visit_access(&mut self, input: &'a AccessExpression) -> (String, String) {
         match input {
             AccessExpression::Array(array) => self.visit_array_access(array),
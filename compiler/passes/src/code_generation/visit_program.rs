@@ -16,7 +16,8 @@
 
 use crate::CodeGenerator;
 
-use leo_ast::{functions, Function, Mapping, Mode, Program, ProgramScope, Struct, Type, Variant};
+use leo_ast::{functions, ConditionalStatement, Function, Mapping, Mode, Program, ProgramScope, Struct, Type, Variant};
+use leo_errors::CompilerError;
 
 use indexmap::IndexMap;
 use itertools::Itertools;
@@ -87,7 +88,8 @@ impl<'a> CodeGenerator<'a> {
 
         // Visit each function in the program scope and produce an Aleo function.
         // Note that in the function inlining pass, we reorder the functions such that they are in post-order.
-        // In other words, a callee function precedes its caller function in the program scope.
+        // In other words, the functions are ordered by declaration, except that a callee function precedes its caller function.
+        // Note that a finalize block is produced immediately after its associated function.
         program_string.push_str(
             &program_scope
                 .functions
@@ -119,7 +121,11 @@ impl<'a> CodeGenerator<'a> {
     }
 
     fn visit_struct_or_record(&mut self, struct_: &'a Struct) -> String {
-        if struct_.is_record { self.visit_record(struct_) } else { self.visit_struct(struct_) }
+        if struct_.is_record {
+            self.visit_record(struct_)
+        } else {
+            self.visit_struct(struct_)
+        }
     }
 
     fn visit_struct(&mut self, struct_: &'a Struct) -> String {
@@ -176,107 +182,139 @@ impl<'a> CodeGenerator<'a> {
         // If a function is a program function, generate an Aleo `function`,
         // if it is a standard function generate an Aleo `closure`,
         // otherwise, it is an inline function, in which case a function should not be generated.
+        // Note that Aleo has no `transition` keyword, and that the keyword depends only on the variant,
+        // so annotations such as `@noflatten` and the presence of a finalize block do not affect it.
+        // If enabled, the annotations are emitted as comments above the header, and do not affect it.
+        let annotations = match self.annotation_comments_enabled {
+            true => function.annotations.iter().map(|annotation| format!("// {annotation}\n")).join(""),
+            false => String::new(),
+        };
         let mut function_string = match function.variant {
-            Variant::Transition => format!("\nfunction {}:\n", function.identifier),
-            Variant::Standard => format!("\nclosure {}:\n", function.identifier),
+            Variant::Transition => format!("\n{annotations}function {}:\n", function.identifier),
+            Variant::Standard => format!("\n{annotations}closure {}:\n", function.identifier),
             Variant::Inline => return String::from("\n"),
         };
 
         // Construct and append the input declarations of the function.
+        // The inputs of a transition are private by default.
         for input in function.input.iter() {
-            let register_string = format!("r{}", self.next_register);
-            self.next_register += 1;
-
-            let type_string = match input {
-                functions::Input::Internal(input) => {
-                    self.variable_mapping.insert(&input.identifier.name, register_string.clone());
-                    let visibility = match (self.is_transition_function, input.mode) {
-                        (true, Mode::None) => Mode::Private,
-                        _ => input.mode,
-                    };
-                    self.visit_type_with_visibility(&input.type_, visibility)
-                }
-                functions::Input::External(input) => {
-                    self.variable_mapping.insert(&input.identifier.name, register_string.clone());
-                    format!("{}.aleo/{}.record", input.program_name, input.record)
-                }
-            };
-
-            writeln!(function_string, "    input {register_string} as {type_string};",)
-                .expect("failed to write to string");
+            function_string.push_str(&self.visit_input(input, Mode::Private));
         }
 
         //  Construct and append the function body.
         let block_string = self.visit_block(&function.block);
+        // An Aleo function without instructions or outputs is a valid no-op, but an Aleo closure is not.
+        // Note that the body may still contain comments, e.g. if debug comments are enabled.
+        let is_empty = block_string.lines().map(str::trim).all(|line| line.is_empty() || line.starts_with("//"));
+        if is_empty && function.variant == Variant::Standard {
+            self.handler.emit_err(CompilerError::empty_closure_body(function.identifier, function.span));
+        }
         function_string.push_str(&block_string);
 
         // If the finalize block exists, generate the appropriate bytecode.
         if !self.futures.is_empty() || function.finalize.is_some() {
-            // Clear the register count.
-            self.next_register = 0;
-            self.in_finalize = true;
-
-            // Clear the variable mapping.
-            // TODO: Figure out a better way to initialize.
-            self.variable_mapping = IndexMap::new();
-            self.variable_mapping.insert(&sym::SelfLower, "self".to_string());
-            self.variable_mapping.insert(&sym::block, "block".to_string());
-
-            function_string.push_str(&format!("\nfinalize {}:\n", function.identifier));
-
-            // If the function contained calls that produced futures, then we need to add the futures to the finalize block as input.
-            // Store the new future registers.
-            let mut future_registers = Vec::new();
-            for (_, future_type) in self.futures.drain(..) {
-                let register_string = format!("r{}", self.next_register);
-                writeln!(function_string, "    input {register_string} as {future_type}.future;")
-                    .expect("failed to write to string");
-                future_registers.push(register_string);
-                self.next_register += 1;
+            function_string.push_str(&self.visit_finalize(function));
+        }
+
+        function_string
+    }
+
+    /// Produces the `finalize` section of a transition, which immediately follows the transition in the program.
+    /// The section declares the futures produced by the calls in the transition, followed by the inputs of the
+    /// finalize block, awaits the futures, and then executes the body of the finalize block.
+    /// Note that the finalize block reuses the name of the transition, rather than its own identifier.
+    fn visit_finalize(&mut self, function: &'a Function) -> String {
+        // Clear the register count.
+        self.next_register = 0;
+        self.in_finalize = true;
+
+        // Clear the variable mapping.
+        // TODO: Figure out a better way to initialize.
+        self.variable_mapping = IndexMap::new();
+        self.variable_mapping.insert(&sym::SelfLower, "self".to_string());
+        self.variable_mapping.insert(&sym::block, "block".to_string());
+
+        let mut finalize_string = format!("\nfinalize {}:\n", function.identifier);
+
+        // If the function contained calls that produced futures, then we need to add the futures to the finalize block as input.
+        // Store the new future registers.
+        let mut future_registers = Vec::new();
+        for (_, future_type) in self.futures.drain(..) {
+            let register_string = format!("r{}", self.next_register);
+            writeln!(finalize_string, "    input {register_string} as {future_type}.future;")
+                .expect("failed to write to string");
+            future_registers.push(register_string);
+            self.next_register += 1;
+        }
+
+        // Construct and append the input declarations of the finalize block, if it exists.
+        // The inputs of a finalize block are public by default.
+        if let Some(finalize) = &function.finalize {
+            for input in finalize.input.iter() {
+                finalize_string.push_str(&self.visit_input(input, Mode::Public));
             }
+        }
+
+        // Invoke `await` on each future.
+        for register in future_registers {
+            writeln!(finalize_string, "    await {register};").expect("failed to write to string");
+        }
 
-            // Construct and append the input declarations of the finalize block, if it exists.
-            if let Some(finalize) = &function.finalize {
-                for input in finalize.input.iter() {
-                    let register_string = format!("r{}", self.next_register);
-                    self.next_register += 1;
-
-                    // TODO: Dedup code.
-                    let type_string = match input {
-                        functions::Input::Internal(input) => {
-                            self.variable_mapping.insert(&input.identifier.name, register_string.clone());
-
-                            let visibility = match (self.is_transition_function, input.mode) {
-                                (true, Mode::None) => Mode::Public,
-                                _ => input.mode,
-                            };
-                            self.visit_type_with_visibility(&input.type_, visibility)
-                        }
-                        functions::Input::External(input) => {
-                            self.variable_mapping.insert(&input.program_name.name, register_string.clone());
-                            format!("{}.aleo/{}.record", input.program_name, input.record)
-                        }
-                    };
-
-                    writeln!(function_string, "    input {register_string} as {type_string};",)
-                        .expect("failed to write to string");
+        // Construct and append the finalize block body, if it exists.
+        // Note that the outputs of the finalize block are produced by its return statement.
+        if let Some(finalize) = &function.finalize {
+            match Self::finalize_guard(finalize) {
+                Some((guard, rest)) => {
+                    finalize_string.push_str(&self.visit_finalize_guard(guard));
+                    rest.iter().for_each(|statement| finalize_string.push_str(&self.visit_statement(statement)));
                 }
+                None => finalize_string.push_str(&self.visit_block(&finalize.block)),
             }
+        }
 
-            // Invoke `await` on each future.
-            for register in future_registers {
-                writeln!(function_string, "    await {register};").expect("failed to write to string");
-            }
+        self.in_finalize = false;
 
-            // Construct and append the finalize block body, if it exists.
-            if let Some(finalize) = &function.finalize {
-                function_string.push_str(&self.visit_block(&finalize.block));
-            }
+        finalize_string
+    }
 
-            self.in_finalize = false;
-        }
+    /// Produces the body of a finalize block guarded by flattening, which branches past the body unless the guard holds.
+    /// Note that a finalize block has at most one guard, so the label is unique.
+    fn visit_finalize_guard(&mut self, guard: &'a ConditionalStatement) -> String {
+        let (condition, mut branch) = self.visit_expression(&guard.condition);
+        writeln!(branch, "    branch.eq {condition} false to end_finalize;").expect("failed to write to string");
+        self.count_instructions(&branch, guard.span, None);
 
-        function_string
+        let body = self.visit_block(&guard.then);
+
+        let position = "    position end_finalize;\n".to_string();
+        self.count_instructions(&position, guard.span, None);
+
+        format!("{branch}{body}{position}")
+    }
+
+    /// Produces the declaration of an input in the next register, e.g. `input r0 as u8.private;`,
+    /// and maps the name of the input to the register.
+    /// If the current function is a transition, an input without a mode is given the default mode.
+    fn visit_input(&mut self, input: &'a functions::Input, default_mode: Mode) -> String {
+        let register_string = format!("r{}", self.next_register);
+        self.next_register += 1;
+
+        let type_string = match input {
+            functions::Input::Internal(input) => {
+                self.variable_mapping.insert(&input.identifier.name, register_string.clone());
+                let visibility = match (self.is_transition_function, input.mode) {
+                    (true, Mode::None) => default_mode,
+                    _ => input.mode,
+                };
+                self.visit_type_with_visibility(&input.type_, visibility)
+            }
+            functions::Input::External(input) => {
+                self.variable_mapping.insert(&input.identifier.name, register_string.clone());
+                format!("{}.aleo/{}.record", input.program_name, input.record)
+            }
+        };
+
+        format!("    input {register_string} as {type_string};\n")
     }
 
     fn visit_mapping(&mut self, mapping: &'a Mapping) -> String {
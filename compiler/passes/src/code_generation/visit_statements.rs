@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::CodeGenerator;
+use crate::{CodeGenerator, InstructionLocation};
 
 use leo_ast::{
     AssertStatement,
@@ -28,20 +28,27 @@ use leo_ast::{
     ExpressionStatement,
     IterationStatement,
     Mode,
+    Node,
     Output,
     ReturnStatement,
     Statement,
+    TupleExpression,
+    Type,
 };
 
+use leo_errors::{emitter::Handler, CompilerError};
+use leo_span::{symbol::with_session_globals, Span, Symbol};
+
 use itertools::Itertools;
 use std::fmt::Write as _;
 
 impl<'a> CodeGenerator<'a> {
-    fn visit_statement(&mut self, input: &'a Statement) -> String {
-        match input {
+    pub(crate) fn visit_statement(&mut self, input: &'a Statement) -> String {
+        let instructions = match input {
             Statement::Assert(stmt) => self.visit_assert(stmt),
             Statement::Assign(stmt) => self.visit_assign(stmt),
-            Statement::Block(stmt) => self.visit_block(stmt),
+            // The statements in a nested block are counted as they are visited, so the block is returned directly.
+            Statement::Block(stmt) => return self.visit_block(stmt),
             Statement::Conditional(stmt) => self.visit_conditional(stmt),
             Statement::Console(stmt) => self.visit_console(stmt),
             Statement::Const(_) => {
@@ -51,53 +58,248 @@ impl<'a> CodeGenerator<'a> {
             Statement::Expression(stmt) => self.visit_expression_statement(stmt),
             Statement::Iteration(stmt) => self.visit_iteration(stmt),
             Statement::Return(stmt) => self.visit_return(stmt),
+        };
+        let message = match input {
+            Statement::Assert(stmt) => stmt.message.as_deref(),
+            _ => None,
+        };
+        self.count_instructions(&instructions, input.span(), message);
+        match self.debug_comments_enabled {
+            // Note that comments are not counted as instructions.
+            true => format!("    // {}\n{instructions}", input.to_string().lines().map(str::trim).join(" ")),
+            false => instructions,
+        }
+    }
+
+    /// Adds the number of instructions in `instructions` to the count for the current function.
+    /// If the source map is enabled, each instruction is also recorded with the location of `span`,
+    /// and with the `message` of the assertion that produced it, if any.
+    /// Blank lines and comments are not counted.
+    pub(crate) fn count_instructions(&mut self, instructions: &str, span: Span, message: Option<&str>) {
+        let count =
+            instructions.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with("//")).count();
+        if let Some(function) = self.current_function {
+            let total = self.instruction_counts.entry(function.identifier.name).or_default();
+            let start = *total;
+            *total += count;
+
+            if self.source_map_enabled {
+                // Synthesized statements, e.g. those introduced by flattening, may not have a location in the source.
+                let location = match span.is_dummy() {
+                    true => None,
+                    false => with_session_globals(|s| s.source_map.span_to_location(span)),
+                };
+                let (line, column) = match location {
+                    Some(location) => (Some(location.line_start), Some(location.col_start)),
+                    None => (None, None),
+                };
+                self.instruction_locations.extend((start..start + count).map(|index| InstructionLocation {
+                    function: function.identifier.name.to_string(),
+                    index,
+                    line,
+                    column,
+                    message: message.map(str::to_string),
+                }));
+            }
         }
     }
 
     fn visit_assert(&mut self, input: &'a AssertStatement) -> String {
+        // Aleo instructions do not have a message operand, so the message, if any, is emitted as a comment on each assertion.
+        // Note that the comment must fit on a single line.
+        let comment = match &input.message {
+            Some(message) => format!(" // {}", message.lines().join(" ")),
+            None => String::new(),
+        };
+        // Note that the handler is copied out, since the closure below borrows `self` mutably.
+        let handler = self.handler;
         let mut generate_assert_instruction = |name: &str, left: &'a Expression, right: &'a Expression| {
             let (left_operand, left_instructions) = self.visit_expression(left);
             let (right_operand, right_instructions) = self.visit_expression(right);
-            let assert_instruction = format!("    {name} {left_operand} {right_operand};\n");
 
             // Concatenate the instructions.
             let mut instructions = left_instructions;
             instructions.push_str(&right_instructions);
-            instructions.push_str(&assert_instruction);
+
+            // Structs and records are compared member by member.
+            // Note that type checking guarantees that both operands have the same type.
+            let paths = self.member_paths(self.type_table.get(&left.id()).as_ref());
+            match name {
+                // Composites are unequal if any pair of members is unequal.
+                "assert.neq" if paths.len() > 1 => {
+                    let comparisons = paths
+                        .iter()
+                        .map(|path| {
+                            let destination_register = format!("r{}", self.next_register);
+                            self.next_register += 1;
+                            writeln!(
+                                instructions,
+                                "    is.neq {left_operand}{path} {right_operand}{path} into {destination_register};"
+                            )
+                            .expect("failed to write to string");
+                            destination_register
+                        })
+                        .collect_vec();
+                    instructions.push_str(&self.assert_any(comparisons, &comment));
+                }
+                _ => paths.iter().for_each(|path| {
+                    writeln!(instructions, "    {name} {left_operand}{path} {right_operand}{path};{comment}")
+                        .expect("failed to write to string")
+                }),
+            }
 
             instructions
         };
         match &input.variant {
+            // Note that Aleo instructions do not have a single-operand `assert`, so the operand is compared against `true`.
             AssertVariant::Assert(expr) => {
                 let (operand, mut instructions) = self.visit_expression(expr);
-                let assert_instruction = format!("    assert.eq {operand} true;\n");
+                let assert_instruction = format!("    assert.eq {operand} true;{comment}\n");
 
                 instructions.push_str(&assert_instruction);
                 instructions
             }
-            AssertVariant::AssertEq(left, right) => generate_assert_instruction("assert.eq", left, right),
-            AssertVariant::AssertNeq(left, right) => generate_assert_instruction("assert.neq", left, right),
+            AssertVariant::AssertEq(left, right) => match (left, right) {
+                // Tuples are equal if each pair of elements is equal, so each pair is asserted individually.
+                (Expression::Tuple(left), Expression::Tuple(right)) => Self::zip_tuple_elements(handler, left, right)
+                    .map(|(left, right)| generate_assert_instruction("assert.eq", left, right))
+                    .join(""),
+                _ => generate_assert_instruction("assert.eq", left, right),
+            },
+            AssertVariant::AssertNeq(left, right) => match (left, right) {
+                // Tuples are unequal if any pair of elements is unequal.
+                // Note that asserting that each pair is unequal would be too strict.
+                (Expression::Tuple(left), Expression::Tuple(right)) => {
+                    let mut instructions = String::new();
+
+                    // Compare each pair of elements.
+                    let comparisons = Self::zip_tuple_elements(handler, left, right)
+                        .map(|(left, right)| {
+                            let (left_operand, left_instructions) = self.visit_expression(left);
+                            let (right_operand, right_instructions) = self.visit_expression(right);
+                            instructions.push_str(&left_instructions);
+                            instructions.push_str(&right_instructions);
+
+                            let destination_register = format!("r{}", self.next_register);
+                            self.next_register += 1;
+                            writeln!(
+                                instructions,
+                                "    is.neq {left_operand} {right_operand} into {destination_register};"
+                            )
+                            .expect("failed to write to string");
+                            destination_register
+                        })
+                        .collect_vec();
+
+                    // Combine the comparisons and assert the result.
+                    instructions.push_str(&self.assert_any(comparisons, &comment));
+
+                    instructions
+                }
+                _ => generate_assert_instruction("assert.neq", left, right),
+            },
+        }
+    }
+
+    /// Returns the instructions asserting that at least one of the given boolean registers is `true`.
+    fn assert_any(&mut self, conditions: Vec<String>, comment: &str) -> String {
+        let mut instructions = String::new();
+
+        // Combine the conditions and assert the result.
+        let result = conditions
+            .into_iter()
+            .reduce(|accumulator, condition| {
+                let destination_register = format!("r{}", self.next_register);
+                self.next_register += 1;
+                writeln!(instructions, "    or {accumulator} {condition} into {destination_register};")
+                    .expect("failed to write to string");
+                destination_register
+            })
+            .expect("Type checking guarantees that tuples and structs have at least one element.");
+        writeln!(instructions, "    assert.eq {result} true;{comment}").expect("failed to write to string");
+
+        instructions
+    }
+
+    /// Returns the paths to the scalar members of a value of the given type, e.g. `.inner.x`, in order of declaration.
+    /// Structs and records are expanded recursively, while a value of any other type is its own single, empty path.
+    fn member_paths(&self, type_: Option<&Type>) -> Vec<String> {
+        match type_.and_then(|type_| match type_ {
+            Type::Identifier(identifier) => self.symbol_table.lookup_struct(identifier.name),
+            _ => None,
+        }) {
+            Some(struct_) => struct_
+                .members
+                .iter()
+                .flat_map(|member| {
+                    self.member_paths(Some(&member.type_))
+                        .into_iter()
+                        .map(move |path| format!(".{}{path}", member.name()))
+                })
+                .collect(),
+            None => vec![String::new()],
+        }
+    }
+
+    /// Pairs the elements of two tuples that are compared by an assertion.
+    /// If the tuples have different lengths, an error is reported and only the leading pairs are compared.
+    fn zip_tuple_elements(
+        handler: &Handler,
+        left: &'a TupleExpression,
+        right: &'a TupleExpression,
+    ) -> impl Iterator<Item = (&'a Expression, &'a Expression)> {
+        if left.elements.len() != right.elements.len() {
+            handler.emit_err(CompilerError::assertion_tuple_length_mismatch(
+                left.elements.len(),
+                right.elements.len(),
+                left.span + right.span,
+            ));
         }
+        left.elements.iter().zip(right.elements.iter())
     }
 
     fn visit_return(&mut self, input: &'a ReturnStatement) -> String {
+        // Note that `current_function` is set in `visit_function`, so this only fails if code generation is entered out of order.
+        let function = match self.active_function("return statement") {
+            Ok(function) => function,
+            Err(error) => {
+                self.handler.emit_err(error);
+                return String::new();
+            }
+        };
+
         let mut outputs = match input.expression {
             // Skip empty return statements.
             Expression::Unit(_) => String::new(),
             _ => {
-                let (operand, mut expression_instructions) = self.visit_expression(&input.expression);
-                // Get the output type of the function.
-                let output = if self.in_finalize {
-                    // Note that the first unwrap is safe, since `current_function` is set in `visit_function`.
-                    self.current_function.unwrap().finalize.as_ref().unwrap().output.iter()
-                } else {
-                    // Note that this unwrap is safe, since `current_function` is set in `visit_function`.
-                    self.current_function.unwrap().output.iter()
+                // Collect the operands, one for each output of the function.
+                let (operand_strings, mut expression_instructions) = match &input.expression {
+                    // If the returned value is a tuple, visit each element individually.
+                    Expression::Tuple(tuple) => {
+                        let mut operands = Vec::with_capacity(tuple.elements.len());
+                        let mut instructions = String::new();
+                        for element in tuple.elements.iter() {
+                            let (operand, element_instructions) = self.visit_expression(element);
+                            operands.push(operand);
+                            instructions.push_str(&element_instructions);
+                        }
+                        (operands, instructions)
+                    }
+                    // Otherwise, the operand may be a register list, e.g. the result of a call that returns a tuple.
+                    expression => {
+                        let (operand, instructions) = self.visit_expression(expression);
+                        // If the operand string is empty, initialize an empty vector.
+                        let operands = match operand.is_empty() {
+                            true => vec![],
+                            false => operand.split(' ').map(|operand| operand.to_string()).collect_vec(),
+                        };
+                        (operands, instructions)
+                    }
                 };
-                // If the operand string is empty, initialize an empty vector.
-                let operand_strings = match operand.is_empty() {
-                    true => vec![],
-                    false => operand.split(' ').collect_vec(),
+                // Get the output type of the function.
+                let output = match (self.in_finalize, &function.finalize) {
+                    (true, Some(finalize)) => finalize.output.iter(),
+                    _ => function.output.iter(),
                 };
                 let instructions = operand_strings
                     .iter()
@@ -150,8 +352,7 @@ impl<'a> CodeGenerator<'a> {
         // If there are any futures or if the return instruction has `finalize_arguments`, then
         // create an `async` instruction that uses them.
         if !self.futures.is_empty() || input.finalize_arguments.is_some() {
-            // Note that this unwrap is safe, since `current_function` is set in `visit_function`.
-            let function_id = self.current_function.unwrap().name();
+            let function_id = function.name();
             let mut async_instruction = format!("    async {function_id}");
             // Add the futures to the async instruction.
             for (future_register, _) in self.futures.iter() {
@@ -188,12 +389,36 @@ impl<'a> CodeGenerator<'a> {
         instructions
     }
 
-    fn visit_definition(&mut self, _input: &'a DefinitionStatement) -> String {
-        // TODO: If SSA is made optional, then conditionally enable codegen for DefinitionStatement
-        // let (operand, expression_instructions) = self.visit_expression(&input.value);
-        // self.variable_mapping.insert(&input.variable_name.name, operand);
-        // expression_instructions
-        unreachable!("DefinitionStatement's should not exist in SSA form.")
+    fn visit_definition(&mut self, input: &'a DefinitionStatement) -> String {
+        // Note that definitions are only visited if SSA is disabled, since SSA lowers them into assignments.
+        let (operand, expression_instructions) = self.visit_expression(&input.value);
+        match &input.place {
+            Expression::Identifier(identifier) => {
+                self.variable_mapping.insert(&identifier.name, operand);
+            }
+            Expression::Tuple(_) => {
+                // Tuple operands are joined with ' ', with nested tuples flattened in order.
+                // Bind each destructured name, from left to right, to its corresponding operand.
+                let mut names = Vec::new();
+                Self::collect_definition_names(&input.place, &mut names);
+                names.into_iter().zip_eq(operand.split(' ')).for_each(|(name, operand)| {
+                    self.variable_mapping.insert(name, operand.to_string());
+                });
+            }
+            _ => unreachable!("Parsing guarantees that the lhs of a definition is an identifier or a tuple."),
+        }
+        expression_instructions
+    }
+
+    /// Collects the names bound by a (possibly nested) tuple pattern, from left to right.
+    fn collect_definition_names(place: &'a Expression, names: &mut Vec<&'a Symbol>) {
+        match place {
+            Expression::Identifier(identifier) => names.push(&identifier.name),
+            Expression::Tuple(tuple) => {
+                tuple.elements.iter().for_each(|element| Self::collect_definition_names(element, names))
+            }
+            _ => unreachable!("Parsing guarantees that the lhs of a definition is an identifier or a tuple."),
+        }
     }
 
     fn visit_expression_statement(&mut self, input: &'a ExpressionStatement) -> String {
@@ -230,14 +455,19 @@ impl<'a> CodeGenerator<'a> {
         }
     }
 
-    fn visit_conditional(&mut self, _input: &'a ConditionalStatement) -> String {
-        // TODO: Once SSA is made optional, create a Leo error informing the user to enable the SSA pass.
-        unreachable!("`ConditionalStatement`s should not be in the AST at this phase of compilation.")
+    /// Reports an error, since conditional statements are only in the AST if the flattening pass is disabled.
+    /// Note that the conditional statement guarding a finalize block is lowered by `visit_finalize_guard` instead.
+    /// Note that `check_unlowered_statements` reports these statements before any instructions are generated.
+    fn visit_conditional(&mut self, input: &'a ConditionalStatement) -> String {
+        self.handler.emit_err(CompilerError::conditional_statement_not_flattened(input.span));
+        String::new()
     }
 
-    fn visit_iteration(&mut self, _input: &'a IterationStatement) -> String {
-        // TODO: Once loop unrolling is made optional, create a Leo error informing the user to enable the loop unrolling pass..
-        unreachable!("`IterationStatement`s should not be in the AST at this phase of compilation.");
+    /// Reports an error, since iteration statements are only in the AST if they are not unrolled.
+    /// Note that `check_unlowered_statements` reports these statements before any instructions are generated.
+    fn visit_iteration(&mut self, input: &'a IterationStatement) -> String {
+        self.handler.emit_err(CompilerError::iteration_statement_not_unrolled(input.span));
+        String::new()
     }
 
     fn visit_console(&mut self, _: &'a ConsoleStatement) -> String {
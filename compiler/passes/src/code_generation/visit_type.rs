@@ -47,6 +47,8 @@ impl<'a> CodeGenerator<'a> {
     pub(crate) fn visit_type_with_visibility(&self, type_: &'a Type, visibility: Mode) -> String {
         match type_ {
             // When the type is a record.
+            // Note that records are referenced by name, and carry no visibility, since the visibility of each member,
+            // including the implicit `owner`, is declared in the `record` block. Aleo records no longer have `gates`.
             // Note that this unwrap is safe because all composite types have been added to the mapping.
             Type::Identifier(identifier) if self.composite_mapping.get(&identifier.name).unwrap().0 => {
                 format!("{identifier}.record")
@@ -17,6 +17,7 @@
 use leo_ast::{AssignStatement, Expression, Identifier, NodeID, Statement};
 use leo_span::Symbol;
 
+use indexmap::IndexSet;
 use std::{cell::RefCell, fmt::Display};
 
 /// A struct used to create assignment statements.
@@ -33,6 +34,11 @@ impl Assigner {
         self.inner.borrow_mut().unique_symbol(arg, separator)
     }
 
+    /// Returns `true` if `symbol` was produced by `unique_symbol`.
+    pub fn is_unique_symbol(&self, symbol: Symbol) -> bool {
+        self.inner.borrow().symbols.contains(&symbol)
+    }
+
     /// Constructs the assignment statement `place = expr;`.
     /// This function should be the only place where `AssignStatement`s are constructed.
     pub fn simple_assign_statement(&self, identifier: Identifier, value: Expression, id: NodeID) -> Statement {
@@ -46,13 +52,17 @@ impl Assigner {
 pub struct AssignerInner {
     /// A strictly increasing counter, used to ensure that new variable names are unique.
     pub(crate) counter: usize,
+    /// The symbols produced so far.
+    pub(crate) symbols: IndexSet<Symbol>,
 }
 
 impl AssignerInner {
     /// Return a new unique `Symbol` from a `&str`.
     fn unique_symbol(&mut self, arg: impl Display, separator: impl Display) -> Symbol {
         self.counter += 1;
-        Symbol::intern(&format!("{}{}{}", arg, separator, self.counter - 1))
+        let symbol = Symbol::intern(&format!("{}{}{}", arg, separator, self.counter - 1));
+        self.symbols.insert(symbol);
+        symbol
     }
 
     /// Constructs the assignment statement `place = expr;`.
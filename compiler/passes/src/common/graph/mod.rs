@@ -72,6 +72,24 @@ impl<N: Node> DiGraph<N> {
         self.nodes.contains(&node)
     }
 
+    /// Returns `true` if there is a path of at least one edge from `from` to `to`.
+    /// In particular, `graph.has_path(node, node)` is `true` if and only if `node` is on a cycle.
+    pub fn has_path(&self, from: N, to: N) -> bool {
+        let mut visited: IndexSet<N> = IndexSet::new();
+        let mut stack = vec![from];
+        while let Some(node) = stack.pop() {
+            for child in self.edges.get(&node).into_iter().flatten() {
+                if *child == to {
+                    return true;
+                }
+                if visited.insert(*child) {
+                    stack.push(*child);
+                }
+            }
+        }
+        false
+    }
+
     /// Returns the post-order ordering of the graph.
     /// Detects if there is a cycle in the graph.
     pub fn post_order(&self) -> Result<IndexSet<N>, DiGraphError<N>> {
@@ -214,4 +232,21 @@ mod test {
 
         check_post_order(&graph, &[1, 2, 3, 4, 5]);
     }
+
+    #[test]
+    fn test_has_path() {
+        let mut graph = DiGraph::<u32>::new(IndexSet::from([6]));
+
+        graph.add_edge(1, 2);
+        graph.add_edge(2, 3);
+        graph.add_edge(3, 4);
+        graph.add_edge(4, 3);
+
+        assert!(graph.has_path(1, 4));
+        assert!(!graph.has_path(4, 1));
+        // A node only has a path to itself if it is on a cycle.
+        assert!(!graph.has_path(1, 1));
+        assert!(graph.has_path(3, 3));
+        assert!(!graph.has_path(6, 6));
+    }
 }
@@ -17,7 +17,7 @@
 use leo_ast::NodeID;
 use leo_span::Symbol;
 
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 
 /// `RenameTable` tracks the names assigned by static single assignment in a single scope.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -30,12 +30,28 @@ pub struct RenameTable {
     /// These are used to ensure that newly introduced symbols reference the appropriate information
     /// that has been previously indexed by node ID. e,g. `TypeTable`.
     ids: IndexMap<Symbol, NodeID>,
+    /// The names in the original AST that are defined in this scope, possibly shadowing a variable in a parent scope.
+    definitions: IndexSet<Symbol>,
 }
 
 impl RenameTable {
     /// Create a new `RenameTable` with the given parent.
     pub(crate) fn new(parent: Option<Box<RenameTable>>) -> Self {
-        Self { parent, names: IndexMap::new(), ids: IndexMap::new() }
+        Self { parent, names: IndexMap::new(), ids: IndexMap::new(), definitions: IndexSet::new() }
+    }
+
+    /// Pushes a new scope, setting the current scope as the new scope's parent.
+    pub(crate) fn push_scope(&mut self) {
+        let parent = core::mem::take(self);
+        *self = RenameTable::new(Some(Box::new(parent)));
+    }
+
+    /// Pops the current scope, restoring its parent, and returns the popped scope.
+    /// Note that the parent is moved out of the popped scope, so lookups on it only resolve the names renamed in it.
+    /// If the current scope does not have a parent, it is replaced with a default `RenameTable`.
+    pub(crate) fn pop_scope(&mut self) -> RenameTable {
+        let parent = self.parent.take().unwrap_or_default();
+        core::mem::replace(self, *parent)
     }
 
     /// Returns the symbols that were renamed in the current scope.
@@ -43,6 +59,19 @@ impl RenameTable {
         self.names.keys()
     }
 
+    /// Returns the symbols that were renamed in the current scope, but not defined in it.
+    /// These are the variables of the enclosing scopes that are assigned in the current scope.
+    /// Note that a variable defined in the current scope goes out of scope with it, even if it shadows another variable.
+    pub(crate) fn local_assignments(&self) -> impl Iterator<Item = &Symbol> {
+        self.local_names().filter(|symbol| !self.definitions.contains(*symbol))
+    }
+
+    /// Records that `symbol` is defined in the current scope, and updates `self.mapping` with the desired entry.
+    pub(crate) fn define(&mut self, symbol: Symbol, new_symbol: Symbol, id: NodeID) {
+        self.definitions.insert(symbol);
+        self.update(symbol, new_symbol, id);
+    }
+
     /// Updates `self.mapping` with the desired entry.
     /// Creates a new entry if `symbol` is not already in `self.mapping`.
     pub(crate) fn update(&mut self, symbol: Symbol, new_symbol: Symbol, id: NodeID) {
@@ -72,3 +101,70 @@ impl RenameTable {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use leo_span::symbol::create_session_if_not_set_then;
+
+    #[test]
+    fn test_push_and_pop_scope() {
+        create_session_if_not_set_then(|_| {
+            let x = Symbol::intern("x");
+            let y = Symbol::intern("y");
+            let mut table = RenameTable::default();
+            table.update(x, Symbol::intern("x$0"), 0);
+
+            // Shadow `x` in an inner scope.
+            table.push_scope();
+            table.update(x, Symbol::intern("x$1"), 1);
+            table.update(y, Symbol::intern("y$0"), 2);
+            assert_eq!(table.lookup(x), Some(&Symbol::intern("x$1")));
+            assert_eq!(table.local_names().collect::<Vec<_>>(), [&x, &y]);
+
+            // Popping the inner scope restores the outer binding.
+            let inner = table.pop_scope();
+            assert_eq!(table.lookup(x), Some(&Symbol::intern("x$0")));
+            assert_eq!(table.lookup(y), None);
+            assert_eq!(table.lookup_id(&Symbol::intern("x$1")), None);
+
+            // The popped scope only resolves the names renamed in it.
+            assert_eq!(inner.lookup(x), Some(&Symbol::intern("x$1")));
+            assert_eq!(inner.lookup_id(&Symbol::intern("x$0")), None);
+            assert!(inner.parent.is_none());
+        })
+    }
+
+    #[test]
+    fn test_local_assignments() {
+        create_session_if_not_set_then(|_| {
+            let x = Symbol::intern("x");
+            let y = Symbol::intern("y");
+            let mut table = RenameTable::default();
+            table.define(x, x, 0);
+            table.define(y, y, 1);
+
+            // Assign `y` and shadow `x` in an inner scope.
+            table.push_scope();
+            table.define(x, Symbol::intern("x$0"), 2);
+            table.update(y, Symbol::intern("y$1"), 3);
+            table.update(x, Symbol::intern("x$2"), 4);
+            assert_eq!(table.local_names().collect::<Vec<_>>(), [&x, &y]);
+            assert_eq!(table.local_assignments().collect::<Vec<_>>(), [&y]);
+        })
+    }
+
+    #[test]
+    fn test_pop_scope_without_parent() {
+        create_session_if_not_set_then(|_| {
+            let x = Symbol::intern("x");
+            let mut table = RenameTable::default();
+            table.update(x, x, 0);
+
+            let popped = table.pop_scope();
+            assert_eq!(popped.lookup(x), Some(&x));
+            assert_eq!(table, RenameTable::default());
+        })
+    }
+}
@@ -22,14 +22,34 @@ pub use variable_symbol::*;
 
 use std::cell::RefCell;
 
-use leo_ast::{normalize_json_value, remove_key_from_json, Function, Struct};
+use leo_ast::{normalize_json_value, remove_key_from_json, Function, Struct, Type};
 use leo_errors::{AstError, Result};
 use leo_span::{Span, Symbol};
 
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 use serde::{Deserialize, Serialize};
 use serde_json;
 
+/// The version of the format written by `SymbolTable::save`.
+/// This must be incremented whenever the serialized form of the symbol table changes, so that old files are rejected.
+pub const SYMBOL_TABLE_FORMAT_VERSION: u32 = 1;
+
+/// A symbol table, tagged with the version of the format, as written by `SymbolTable::save`.
+#[derive(Serialize)]
+struct VersionedSymbolTable<'a> {
+    version: u32,
+    symbol_table: &'a SymbolTable,
+}
+
+/// A symbol table read by `SymbolTable::load`, whose contents are only deserialized if the version matches.
+#[derive(Deserialize)]
+struct UncheckedSymbolTable {
+    #[serde(default)]
+    version: Option<u32>,
+    #[serde(default)]
+    symbol_table: serde_json::Value,
+}
+
 // TODO (@d0cd) Consider a safe interface for the symbol table.
 // TODO (@d0cd) Cleanup API
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -50,6 +70,10 @@ pub struct SymbolTable {
     pub(crate) scope_index: usize,
     /// The sub-scopes of this scope.
     pub(crate) scopes: Vec<RefCell<SymbolTable>>,
+    /// Maps the name of each function to the names of the functions that call it.
+    /// This field is only populated if the caller index is enabled during symbol table creation.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub(crate) callers: IndexMap<Symbol, IndexSet<Symbol>>,
 }
 
 impl SymbolTable {
@@ -114,6 +138,51 @@ impl SymbolTable {
         self.scope_index()
     }
 
+    /// Records that the function `caller` calls the function `callee`.
+    pub fn insert_call(&mut self, caller: Symbol, callee: Symbol) {
+        self.callers.entry(callee).or_default().insert(caller);
+    }
+
+    /// Returns the names of the functions that call the function `name`, in the order they were recorded.
+    /// Note that this is empty unless the caller index was enabled during symbol table creation.
+    pub fn callers_of(&self, name: Symbol) -> Vec<Symbol> {
+        self.callers.get(&name).map(|callers| callers.iter().copied().collect()).unwrap_or_default()
+    }
+
+    /// Merges the function symbols of `other` into the symbol table, under the program namespace `namespace`.
+    /// Each function `foo` in `other` is inserted as `{namespace}/foo`, along with its associated scope.
+    /// If any namespaced function already exists in the symbol table, an error is returned and nothing is merged.
+    pub fn merge(&mut self, other: SymbolTable, namespace: Symbol) -> Result<()> {
+        // Check for collisions before modifying the symbol table.
+        for (name, function) in other.functions.iter() {
+            let namespaced = Self::namespaced_symbol(namespace, *name);
+            if self.functions.contains_key(&namespaced) {
+                return Err(AstError::shadowed_function(namespaced, function._span).into());
+            }
+        }
+
+        let mut scopes = other.scopes;
+        for (name, mut function) in other.functions.into_iter() {
+            // Move the scope associated with the function into this symbol table.
+            let scope = scopes.get_mut(function.id).map(std::mem::take).unwrap_or_default();
+            function.id = self.scope_index();
+            self.scopes.push(scope);
+            self.functions.insert(Self::namespaced_symbol(namespace, name), function);
+        }
+
+        Ok(())
+    }
+
+    /// Attempts to lookup a function that was merged into the symbol table under the program namespace `namespace`.
+    pub fn lookup_namespaced_fn_symbol(&self, namespace: Symbol, symbol: Symbol) -> Option<&FunctionSymbol> {
+        self.lookup_fn_symbol(Self::namespaced_symbol(namespace, symbol))
+    }
+
+    /// Returns the symbol of `symbol` qualified by the program namespace `namespace`.
+    fn namespaced_symbol(namespace: Symbol, symbol: Symbol) -> Symbol {
+        Symbol::intern(&format!("{namespace}/{symbol}"))
+    }
+
     /// Attempts to lookup a function in the symbol table.
     pub fn lookup_fn_symbol(&self, symbol: Symbol) -> Option<&FunctionSymbol> {
         if let Some(func) = self.functions.get(&symbol) {
@@ -136,6 +205,15 @@ impl SymbolTable {
         }
     }
 
+    /// Attempts to lookup the type of a member of a struct in the symbol table.
+    pub fn lookup_struct_member(&self, struct_: Symbol, member: Symbol) -> Option<&Type> {
+        self.lookup_struct(struct_)?
+            .members
+            .iter()
+            .find(|candidate| candidate.name() == member)
+            .map(|candidate| &candidate.type_)
+    }
+
     /// Attempts to lookup a variable in the symbol table.
     pub fn lookup_variable(&self, symbol: Symbol) -> Option<&VariableSymbol> {
         if let Some(var) = self.variables.get(&symbol) {
@@ -161,6 +239,15 @@ impl SymbolTable {
         }
     }
 
+    /// Returns the span of the variable shadowed by `symbol` in the sub-scope associated with `index`, if any.
+    /// A variable in the sub-scope shadows a variable of the same name in this scope or any of its parent scopes.
+    pub fn shadows(&self, symbol: Symbol, index: usize) -> Option<Span> {
+        match self.lookup_scope_by_index(index)?.borrow().variable_in_local_scope(symbol) {
+            true => self.lookup_variable(symbol).map(|variable| variable.span),
+            false => None,
+        }
+    }
+
     /// Returns a mutable reference to the `VariableSymbol` if it exists in the symbol table.
     pub fn lookup_variable_mut(&mut self, symbol: Symbol) -> Option<&mut VariableSymbol> {
         if let Some(var) = self.variables.get_mut(&symbol) {
@@ -237,4 +324,220 @@ impl SymbolTable {
         let data = std::fs::read_to_string(&path).map_err(|e| AstError::failed_to_read_json_file(&path, &e))?;
         Self::from_json_string(&data)
     }
+
+    /// Saves the symbol table to a file, so that a later compilation can reuse it with `load`.
+    /// Unlike `to_json_file`, the file is tagged with `SYMBOL_TABLE_FORMAT_VERSION`.
+    pub fn save(&self, path: &std::path::Path) -> Result<()> {
+        let file =
+            std::fs::File::create(path).map_err(|e| AstError::failed_to_create_symbol_table_json_file(path, &e))?;
+        let writer = std::io::BufWriter::new(file);
+        let versioned = VersionedSymbolTable { version: SYMBOL_TABLE_FORMAT_VERSION, symbol_table: self };
+        Ok(serde_json::to_writer(writer, &versioned)
+            .map_err(|e| AstError::failed_to_write_symbol_table_to_json_file(path, &e))?)
+    }
+
+    /// Loads a symbol table previously written by `save`.
+    /// Files written with a different version of the format, or without a version, are rejected.
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path).map_err(|e| AstError::failed_to_read_json_file(path, &e))?;
+        let unchecked: UncheckedSymbolTable =
+            serde_json::from_str(&data).map_err(|e| AstError::failed_to_read_json_string_to_symbol_table(&e))?;
+
+        match unchecked.version {
+            Some(SYMBOL_TABLE_FORMAT_VERSION) => Ok(serde_json::from_value(unchecked.symbol_table)
+                .map_err(|e| AstError::failed_to_read_json_string_to_symbol_table(&e))?),
+            version => {
+                let found = version.map_or_else(|| "none".to_string(), |version| version.to_string());
+                Err(AstError::incompatible_symbol_table_cache(path, found, SYMBOL_TABLE_FORMAT_VERSION).into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use leo_ast::{Block, Identifier, IntegerType, Member, Mode, NodeBuilder, Variant};
+    use leo_span::{span::BytePos, symbol::create_session_if_not_set_then};
+
+    fn function(name: &str, node_builder: &NodeBuilder) -> Function {
+        Function::new(
+            Vec::new(),
+            Variant::Transition,
+            Identifier::new(Symbol::intern(name), node_builder.next_id()),
+            Vec::new(),
+            Vec::new(),
+            Block { statements: Vec::new(), span: Default::default(), id: node_builder.next_id() },
+            None,
+            Default::default(),
+            node_builder.next_id(),
+        )
+    }
+
+    fn new_symbol_table(names: &[&str], node_builder: &NodeBuilder) -> SymbolTable {
+        let mut symbol_table = SymbolTable::default();
+        for name in names {
+            symbol_table.insert_fn(Symbol::intern(name), &function(name, node_builder)).unwrap();
+        }
+        symbol_table
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        create_session_if_not_set_then(|_| {
+            let node_builder = NodeBuilder::default();
+            let mut symbol_table = new_symbol_table(&["main", "foo"], &node_builder);
+            symbol_table.insert_variable(Symbol::intern("x"), variable(0, 1)).unwrap();
+
+            let path = std::env::temp_dir().join(format!("leo-symbol-table-save-{}.json", std::process::id()));
+            symbol_table.save(&path).unwrap();
+            let loaded = SymbolTable::load(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(loaded.to_json_string().unwrap(), symbol_table.to_json_string().unwrap());
+            assert!(loaded.lookup_fn_symbol(Symbol::intern("foo")).is_some());
+            assert_eq!(loaded.lookup_variable(Symbol::intern("x")), Some(&variable(0, 1)));
+        })
+    }
+
+    #[test]
+    fn test_load_version_mismatch() {
+        create_session_if_not_set_then(|_| {
+            let node_builder = NodeBuilder::default();
+            let symbol_table = new_symbol_table(&["main"], &node_builder);
+            let path = std::env::temp_dir().join(format!("leo-symbol-table-version-{}.json", std::process::id()));
+
+            // A file written with a different version of the format is rejected.
+            let versioned =
+                VersionedSymbolTable { version: SYMBOL_TABLE_FORMAT_VERSION + 1, symbol_table: &symbol_table };
+            std::fs::write(&path, serde_json::to_string(&versioned).unwrap()).unwrap();
+            let error = SymbolTable::load(&path).unwrap_err().to_string();
+            assert!(error.contains(&format!("has version {}", SYMBOL_TABLE_FORMAT_VERSION + 1)), "{error}");
+
+            // A file without a version, e.g. a snapshot, is rejected as well.
+            std::fs::write(&path, symbol_table.to_json_string().unwrap()).unwrap();
+            let error = SymbolTable::load(&path).unwrap_err().to_string();
+            assert!(error.contains("has version none"), "{error}");
+
+            std::fs::remove_file(&path).unwrap();
+        })
+    }
+
+    #[test]
+    fn test_merge() {
+        create_session_if_not_set_then(|_| {
+            let node_builder = NodeBuilder::default();
+            let mut symbol_table = new_symbol_table(&["main", "foo"], &node_builder);
+            let other = new_symbol_table(&["foo", "bar"], &node_builder);
+
+            let namespace = Symbol::intern("credits");
+            symbol_table.merge(other, namespace).unwrap();
+
+            // Local functions are unaffected.
+            assert!(symbol_table.lookup_fn_symbol(Symbol::intern("main")).is_some());
+            assert!(symbol_table.lookup_fn_symbol(Symbol::intern("bar")).is_none());
+            // Merged functions are accessible under the namespace.
+            let foo = symbol_table.lookup_namespaced_fn_symbol(namespace, Symbol::intern("foo")).unwrap();
+            let bar = symbol_table.lookup_namespaced_fn_symbol(namespace, Symbol::intern("bar")).unwrap();
+            assert_ne!(foo.id, bar.id);
+            assert!(symbol_table.lookup_fn_scope(Symbol::intern("credits/bar")).is_some());
+        })
+    }
+
+    #[test]
+    fn test_merge_collision() {
+        create_session_if_not_set_then(|_| {
+            let node_builder = NodeBuilder::default();
+            let mut symbol_table = new_symbol_table(&["main"], &node_builder);
+            let namespace = Symbol::intern("credits");
+
+            symbol_table.merge(new_symbol_table(&["foo"], &node_builder), namespace).unwrap();
+            assert!(symbol_table.merge(new_symbol_table(&["bar", "foo"], &node_builder), namespace).is_err());
+            // Nothing is merged when a collision is reported.
+            assert!(symbol_table.lookup_namespaced_fn_symbol(namespace, Symbol::intern("bar")).is_none());
+        })
+    }
+
+    fn variable(lo: u32, hi: u32) -> VariableSymbol {
+        VariableSymbol {
+            type_: Type::Boolean,
+            span: Span::new(BytePos(lo), BytePos(hi)),
+            declaration: VariableType::Mut,
+        }
+    }
+
+    #[test]
+    fn test_shadows() {
+        create_session_if_not_set_then(|_| {
+            let (x, y) = (Symbol::intern("x"), Symbol::intern("y"));
+            let mut parent = SymbolTable::default();
+            parent.insert_variable(x, variable(0, 1)).unwrap();
+            let mut symbol_table = SymbolTable { parent: Some(Box::new(parent)), ..Default::default() };
+            symbol_table.insert_variable(y, variable(2, 3)).unwrap();
+
+            // Note that a sub-scope does not have a parent, so shadowing variables can be inserted.
+            let index = symbol_table.insert_block();
+            let scope = symbol_table.lookup_scope_by_index(index).unwrap();
+            scope.borrow_mut().insert_variable(x, variable(4, 5)).unwrap();
+            scope.borrow_mut().insert_variable(y, variable(6, 7)).unwrap();
+
+            // The variables in the sub-scope shadow the variables in this scope and its parent.
+            assert_eq!(symbol_table.shadows(x, index), Some(variable(0, 1).span));
+            assert_eq!(symbol_table.shadows(y, index), Some(variable(2, 3).span));
+            // There is no sub-scope with the given index.
+            assert_eq!(symbol_table.shadows(x, index + 1), None);
+        })
+    }
+
+    #[test]
+    fn test_does_not_shadow() {
+        create_session_if_not_set_then(|_| {
+            let (x, y) = (Symbol::intern("x"), Symbol::intern("y"));
+            let mut symbol_table = SymbolTable::default();
+            symbol_table.insert_variable(x, variable(0, 1)).unwrap();
+
+            let index = symbol_table.insert_block();
+            let scope = symbol_table.lookup_scope_by_index(index).unwrap();
+            scope.borrow_mut().insert_variable(y, variable(2, 3)).unwrap();
+
+            // `y` is not defined in an enclosing scope and `x` is not defined in the sub-scope.
+            assert_eq!(symbol_table.shadows(y, index), None);
+            assert_eq!(symbol_table.shadows(x, index), None);
+        })
+    }
+
+    #[test]
+    fn test_lookup_struct_member() {
+        create_session_if_not_set_then(|_| {
+            let node_builder = NodeBuilder::default();
+            let member = |name: &str, type_: Type| Member {
+                mode: Mode::None,
+                identifier: Identifier::new(Symbol::intern(name), node_builder.next_id()),
+                type_,
+                span: Default::default(),
+                id: node_builder.next_id(),
+            };
+            let point = Symbol::intern("Point");
+            let (x, y, z) = (Symbol::intern("x"), Symbol::intern("y"), Symbol::intern("z"));
+            let struct_ = Struct {
+                identifier: Identifier::new(point, node_builder.next_id()),
+                members: vec![member("x", Type::Integer(IntegerType::U8)), member("y", Type::Boolean)],
+                is_record: false,
+                span: Default::default(),
+                id: node_builder.next_id(),
+            };
+
+            let mut parent = SymbolTable::default();
+            parent.insert_struct(point, &struct_).unwrap();
+            let symbol_table = SymbolTable { parent: Some(Box::new(parent)), ..Default::default() };
+
+            // Members are resolved through the enclosing scopes.
+            assert_eq!(symbol_table.lookup_struct_member(point, x), Some(&Type::Integer(IntegerType::U8)));
+            assert_eq!(symbol_table.lookup_struct_member(point, y), Some(&Type::Boolean));
+            // Neither a missing member nor a member of a missing struct is resolved.
+            assert_eq!(symbol_table.lookup_struct_member(point, z), None);
+            assert_eq!(symbol_table.lookup_struct_member(Symbol::intern("Line"), x), None);
+        })
+    }
 }
@@ -0,0 +1,82 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+use leo_ast::{AccessExpression, Expression, Identifier, NodeBuilder, Type};
+use leo_span::{sym, Symbol};
+
+use indexmap::IndexMap;
+
+pub struct CommonSubexpressionEliminator<'a> {
+    /// A counter to generate unique node IDs.
+    pub(crate) node_builder: &'a NodeBuilder,
+    /// A stack of mappings from the pure expressions computed in each enclosing block to the variables holding them.
+    pub(crate) expressions: Vec<IndexMap<String, Symbol>>,
+    /// A mapping from the eliminated variables in the current function body to the variables that replace them.
+    pub(crate) replacements: IndexMap<Symbol, Symbol>,
+}
+
+impl<'a> CommonSubexpressionEliminator<'a> {
+    /// Initializes a new `CommonSubexpressionEliminator`.
+    pub fn new(node_builder: &'a NodeBuilder) -> Self {
+        Self { node_builder, expressions: Default::default(), replacements: Default::default() }
+    }
+
+    /// Resets the state of the eliminator, e.g. before traversing a function body or finalize block.
+    pub(crate) fn clear(&mut self) {
+        self.expressions.clear();
+        self.replacements.clear();
+    }
+
+    /// Returns the variable holding the value of the expression with the given key, if it was computed in an enclosing block.
+    pub(crate) fn lookup(&self, key: &str) -> Option<Symbol> {
+        self.expressions.iter().rev().find_map(|scope| scope.get(key).copied())
+    }
+
+    /// Records that `name` holds the value of the expression with the given key in the current block.
+    pub(crate) fn insert(&mut self, key: String, name: Symbol) {
+        if let Some(scope) = self.expressions.last_mut() {
+            scope.insert(key, name);
+        }
+    }
+}
+
+/// Returns whether evaluating `expression` twice is guaranteed to produce the same value, without side effects.
+/// Calls are never pure, since the callee may have side effects.
+/// Mapping operations and random number generation are never pure, since their results depend on when they are evaluated.
+pub(crate) fn is_pure(expression: &Expression) -> bool {
+    match expression {
+        Expression::Access(AccessExpression::Array(access)) => is_pure(&access.array) && is_pure(&access.index),
+        Expression::Access(AccessExpression::AssociatedConstant(_)) => true,
+        Expression::Access(AccessExpression::AssociatedFunction(function)) => {
+            !matches!(function.ty, Type::Identifier(Identifier { name: sym::Mapping | sym::ChaCha, .. }))
+                && function.arguments.iter().all(is_pure)
+        }
+        Expression::Access(AccessExpression::Member(access)) => is_pure(&access.inner),
+        Expression::Access(AccessExpression::Tuple(access)) => is_pure(&access.tuple),
+        Expression::Array(array) => array.elements.iter().all(is_pure),
+        Expression::Binary(binary) => is_pure(&binary.left) && is_pure(&binary.right),
+        Expression::Call(_) => false,
+        Expression::Cast(cast) => is_pure(&cast.expression),
+        Expression::Struct(struct_) => struct_.members.iter().all(|member| member.expression.iter().all(is_pure)),
+        Expression::Err(_) => false,
+        Expression::Identifier(_) | Expression::Literal(_) | Expression::Unit(_) => true,
+        Expression::Ternary(ternary) => {
+            is_pure(&ternary.condition) && is_pure(&ternary.if_true) && is_pure(&ternary.if_false)
+        }
+        Expression::Tuple(tuple) => tuple.elements.iter().all(is_pure),
+        Expression::Unary(unary) => is_pure(&unary.receiver),
+    }
+}
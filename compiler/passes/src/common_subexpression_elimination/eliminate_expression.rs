@@ -0,0 +1,31 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::CommonSubexpressionEliminator;
+
+use leo_ast::{Expression, ExpressionReconstructor, Identifier};
+
+impl ExpressionReconstructor for CommonSubexpressionEliminator<'_> {
+    type AdditionalOutput = ();
+
+    /// Replaces the uses of eliminated variables with the variables that hold the same value.
+    fn reconstruct_identifier(&mut self, input: Identifier) -> (Expression, Self::AdditionalOutput) {
+        match self.replacements.get(&input.name) {
+            Some(name) => (Expression::Identifier(Identifier { name: *name, ..input }), Default::default()),
+            None => (Expression::Identifier(input), Default::default()),
+        }
+    }
+}
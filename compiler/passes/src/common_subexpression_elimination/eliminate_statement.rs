@@ -0,0 +1,58 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{is_pure, CommonSubexpressionEliminator};
+
+use leo_ast::{AssignStatement, Block, Expression, ExpressionReconstructor, Statement, StatementReconstructor};
+
+impl StatementReconstructor for CommonSubexpressionEliminator<'_> {
+    /// Reconstructs an assignment statement, eliminating it if its value was already computed in an enclosing block.
+    fn reconstruct_assign(&mut self, input: AssignStatement) -> (Statement, Self::AdditionalOutput) {
+        // Note that the operands are reconstructed first, so that chains of identical expressions are eliminated.
+        let value = self.reconstruct_expression(input.value).0;
+
+        // Only assignments of pure expressions to a single variable are candidates for elimination.
+        // Assignments of identifiers and literals are skipped, since they do not compute anything.
+        if let Expression::Identifier(place) = &input.place {
+            if is_pure(&value) && !matches!(value, Expression::Identifier(_) | Expression::Literal(_)) {
+                let key = value.to_string();
+                match self.lookup(&key) {
+                    Some(name) => {
+                        self.replacements.insert(place.name, name);
+                        return (Statement::dummy(Default::default(), self.node_builder.next_id()), Default::default());
+                    }
+                    None => self.insert(key, place.name),
+                }
+            }
+        }
+
+        (
+            Statement::Assign(Box::new(AssignStatement { place: input.place, value, span: input.span, id: input.id })),
+            Default::default(),
+        )
+    }
+
+    /// Reconstructs the statements inside a block.
+    /// Expressions computed inside the block are not reused after it, since the block may not be executed.
+    fn reconstruct_block(&mut self, block: Block) -> (Block, Self::AdditionalOutput) {
+        self.expressions.push(Default::default());
+        let statements =
+            block.statements.into_iter().map(|statement| self.reconstruct_statement(statement).0).collect();
+        self.expressions.pop();
+
+        (Block { statements, span: block.span, id: block.id }, Default::default())
+    }
+}
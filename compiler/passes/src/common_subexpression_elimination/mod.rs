@@ -0,0 +1,135 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! The Common Subexpression Elimination pass traverses the AST and reuses the variables holding pure expressions,
+//! when an identical expression is computed again within the same `transition` or `function`.
+//! The pass is run after the Function Inlining pass and before the Dead Code Elimination pass.
+//!
+//! See https://en.wikipedia.org/wiki/Common_subexpression_elimination for more information.
+//!
+//! Consider the following flattened Leo code.
+//! ```leo
+//! function main(a: u8, b: u8) -> u8 {
+//!     $var$0 = a + b;
+//!     $var$1 = a + b;
+//!     $var$2 = $var$0 * $var$1;
+//!     return $var$2;
+//! }
+//! ```
+//!
+//! The common subexpression elimination pass produces the following code.
+//! ```leo
+//! function main(a: u8, b: u8) -> u8 {
+//!     $var$0 = a + b;
+//!     $var$2 = $var$0 * $var$0;
+//!     return $var$2;
+//! }
+//! ```
+//! Note that calls, mapping operations, and random number generation are never reused, since they may have side effects.
+//! Expressions computed inside a block are only reused inside that block, and the function body and finalize block are
+//! handled independently.
+//!
+//! Note this pass relies on the following invariants:
+//! - Unique variable names (provided by SSA)
+//! - Operands are identifiers or literals (provided by SSA), so that identical expressions have identical `Display`s
+
+mod eliminate_expression;
+
+mod eliminate_statement;
+
+mod eliminate_program;
+
+pub mod common_subexpression_eliminator;
+pub use common_subexpression_eliminator::*;
+
+use crate::Pass;
+
+use leo_ast::{Ast, NodeBuilder, ProgramReconstructor};
+use leo_errors::Result;
+
+impl<'a> Pass for CommonSubexpressionEliminator<'a> {
+    type Input = (Ast, &'a NodeBuilder);
+    type Output = Result<Ast>;
+
+    fn do_pass((ast, node_builder): Self::Input) -> Self::Output {
+        let mut reconstructor = CommonSubexpressionEliminator::new(node_builder);
+        let program = reconstructor.reconstruct_program(ast.into_repr());
+
+        Ok(Ast::new(program))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_helpers::TestCompiler, SingleAssignmentVerifier};
+
+    use leo_span::symbol::create_session_if_not_set_then;
+
+    const PROGRAM: &str = "
+program test.aleo {
+    function helper(a: u8) -> u8 {
+        assert(a != 0u8);
+        return a;
+    }
+
+    transition main(flag: bool, a: u8, b: u8) -> u8 {
+        let p: u8 = helper(a);
+        let q: u8 = helper(a);
+        let c: u8 = (a + b) * (a + b);
+        if (flag) {
+            c = a * b;
+        }
+        let d: u8 = a * b;
+        return c + d + p + q;
+    }
+}";
+
+    #[test]
+    fn test_eliminate_common_subexpressions() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+            let (ast, symbol_table, ..) = compiler.type_check(PROGRAM);
+            let ast = compiler.static_single_assignment(ast, &symbol_table);
+
+            // Before flattening, the product computed in the conditional is not reused after it.
+            let ssa = CommonSubexpressionEliminator::do_pass((ast, &compiler.node_builder)).unwrap();
+            let main = ssa.as_repr().program_scopes.values().next().unwrap().functions[1].1.to_string();
+            assert_eq!(main.matches("a + b").count(), 1, "{main}");
+            assert_eq!(main.matches("a * b").count(), 2, "{main}");
+            assert!(SingleAssignmentVerifier::do_pass((&ssa, &compiler.handler)).is_ok());
+
+            // After flattening, the product is computed unconditionally, so it is reused.
+            let ast = compiler.flatten(ssa, &symbol_table);
+            let ast = CommonSubexpressionEliminator::do_pass((ast, &compiler.node_builder)).unwrap();
+            let main = ast.as_repr().program_scopes.values().next().unwrap().functions[1].1.to_string();
+            assert_eq!(main.matches("a + b").count(), 1, "{main}");
+            assert_eq!(main.matches("a * b").count(), 1, "{main}");
+
+            // Calls are never reused, since the callee may have side effects.
+            assert_eq!(main.matches("helper(a)").count(), 2, "{main}");
+
+            // Both operands of the product of the sums are the same variable.
+            let product = main.lines().find(|line| line.contains(" * ") && !line.contains("a * b")).unwrap();
+            let (_, value) = product.trim().trim_end_matches(';').split_once(" = ").unwrap();
+            let (left, right) = value.split_once(" * ").unwrap();
+            assert_eq!(left, right, "{main}");
+
+            // The output is still in single assignment form.
+            assert!(SingleAssignmentVerifier::do_pass((&ast, &compiler.handler)).is_ok());
+        })
+    }
+}
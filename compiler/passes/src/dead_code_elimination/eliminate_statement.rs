@@ -50,6 +50,7 @@ impl StatementReconstructor for DeadCodeEliminator<'_> {
                     AssertVariant::AssertNeq(self.reconstruct_expression(left).0, self.reconstruct_expression(right).0)
                 }
             },
+            message: input.message,
             span: input.span,
             id: input.id,
         });
@@ -80,8 +81,11 @@ impl StatementReconstructor for DeadCodeEliminator<'_> {
             ),
         };
 
-        match lhs_is_used {
-            // If the lhs is used, then we return the original statement.
+        // Calls may have side effects, e.g. failing assertions in the callee, so they are never eliminated.
+        let value_has_side_effects = matches!(input.value, Expression::Call(_));
+
+        match lhs_is_used || value_has_side_effects {
+            // If the lhs is used or the value has side effects, then we return the original statement.
             true => {
                 // Set the `is_necessary` flag.
                 self.is_necessary = true;
@@ -116,9 +120,25 @@ impl StatementReconstructor for DeadCodeEliminator<'_> {
         (Block { statements, span: block.span, id: block.id }, Default::default())
     }
 
-    /// Flattening removes conditional statements from the program.
-    fn reconstruct_conditional(&mut self, _: ConditionalStatement) -> (Statement, Self::AdditionalOutput) {
-        unreachable!("`ConditionalStatement`s should not be in the AST at this phase of compilation.")
+    /// Flattening only leaves the conditional statement that guards the body of a conditionally executed finalize block.
+    /// The branches are reconstructed before the condition, since the statements are visited in reverse.
+    fn reconstruct_conditional(&mut self, input: ConditionalStatement) -> (Statement, Self::AdditionalOutput) {
+        let otherwise = input.otherwise.map(|statement| Box::new(self.reconstruct_statement(*statement).0));
+        let then = self.reconstruct_block(input.then).0;
+
+        // Set the `is_necessary` flag.
+        self.is_necessary = true;
+
+        // Visit the condition.
+        let condition = self.reconstruct_expression(input.condition).0;
+
+        // Unset the `is_necessary` flag.
+        self.is_necessary = false;
+
+        (
+            Statement::Conditional(ConditionalStatement { condition, then, otherwise, span: input.span, id: input.id }),
+            Default::default(),
+        )
     }
 
     /// Parsing guarantees that console statements are not present in the program.
@@ -44,6 +44,8 @@
 //!     return value$3;
 //! }
 //! ```
+//! Note that assignments whose value is a call are always preserved, since the callee may have side effects.
+//!
 //! Note this pass relies on the following invariants:
 //! - No shadowing for all variables, struct names, function names, etc.
 //! - Unique variable names (provided by SSA)
@@ -74,3 +76,49 @@ impl<'a> Pass for DeadCodeEliminator<'a> {
         Ok(Ast::new(program))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::TestCompiler;
+
+    use leo_span::symbol::create_session_if_not_set_then;
+
+    const PROGRAM: &str = "
+program test.aleo {
+    function helper(a: u8) -> u8 {
+        assert(a != 0u8);
+        return a;
+    }
+
+    transition main(a: u8, b: u8) -> u8 {
+        let unused_call: u8 = helper(a);
+        let unused_sum: u8 = a + b;
+        let product: u8 = a * b;
+        return product;
+    }
+}";
+
+    #[test]
+    fn test_eliminate_unused_temporaries() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+            let (ast, symbol_table, ..) = compiler.type_check(PROGRAM);
+            let ast = compiler.static_single_assignment(ast, &symbol_table);
+            let before = ast.as_repr().program_scopes.values().next().unwrap().functions[1].1.to_string();
+            assert!(before.contains("a + b"));
+
+            let ast = DeadCodeEliminator::do_pass((ast, &compiler.node_builder)).unwrap();
+            let functions = &ast.as_repr().program_scopes.values().next().unwrap().functions;
+
+            // The unused sum is eliminated, while the unused call and the returned product are preserved.
+            let main = functions[1].1.to_string();
+            assert!(!main.contains("a + b"));
+            assert!(main.contains("helper(a)"));
+            assert!(main.contains("a * b"));
+
+            // The assertion in the helper is preserved.
+            assert!(functions[0].1.to_string().contains("assert"));
+        })
+    }
+}
@@ -17,6 +17,8 @@
 use crate::Destructurer;
 
 use leo_ast::{
+    AssertStatement,
+    AssertVariant,
     AssignStatement,
     Block,
     ConditionalStatement,
@@ -37,6 +39,31 @@ use leo_ast::{
 use itertools::Itertools;
 
 impl StatementReconstructor for Destructurer<'_> {
+    /// Replaces any tuples compared by an assert statement with their tuple expressions.
+    fn reconstruct_assert(&mut self, input: AssertStatement) -> (Statement, Self::AdditionalOutput) {
+        // Note that SSA guarantees that the operands of the assert statement are either literals or identifiers.
+        let mut destructure = |expression: Expression| match expression {
+            // If the operand is an identifier that maps to a tuple, use the tuple expression.
+            Expression::Identifier(identifier) if self.tuples.contains_key(&identifier.name) => {
+                // Note that the `unwrap` is safe since the match arm checks that the entry exists in `self.tuples`.
+                Expression::Tuple(self.tuples.get(&identifier.name).unwrap().clone())
+            }
+            // Otherwise, reconstruct the original expression.
+            expression => self.reconstruct_expression(expression).0,
+        };
+
+        let variant = match input.variant {
+            AssertVariant::Assert(expression) => AssertVariant::Assert(destructure(expression)),
+            AssertVariant::AssertEq(left, right) => AssertVariant::AssertEq(destructure(left), destructure(right)),
+            AssertVariant::AssertNeq(left, right) => AssertVariant::AssertNeq(destructure(left), destructure(right)),
+        };
+
+        (
+            Statement::Assert(AssertStatement { variant, message: input.message, span: input.span, id: input.id }),
+            Default::default(),
+        )
+    }
+
     /// Flattens an assign statement, if necessary.
     /// Marks variables as structs as necessary.
     /// Note that new statements are only produced if the right hand side is a ternary expression over structs.
@@ -220,8 +247,18 @@ impl StatementReconstructor for Destructurer<'_> {
         (Block { span: block.span, statements, id: self.node_builder.next_id() }, Default::default())
     }
 
-    fn reconstruct_conditional(&mut self, _: ConditionalStatement) -> (Statement, Self::AdditionalOutput) {
-        unreachable!("`ConditionalStatement`s should not be in the AST at this phase of compilation.")
+    /// Flattening only leaves the conditional statement that guards the body of a conditionally executed finalize block.
+    fn reconstruct_conditional(&mut self, input: ConditionalStatement) -> (Statement, Self::AdditionalOutput) {
+        (
+            Statement::Conditional(ConditionalStatement {
+                condition: input.condition,
+                then: self.reconstruct_block(input.then).0,
+                otherwise: input.otherwise.map(|statement| Box::new(self.reconstruct_statement(*statement).0)),
+                span: input.span,
+                id: input.id,
+            }),
+            Default::default(),
+        )
     }
 
     fn reconstruct_console(&mut self, _: ConsoleStatement) -> (Statement, Self::AdditionalOutput) {
@@ -26,6 +26,7 @@ use leo_ast::{
     TernaryExpression,
     Type,
 };
+use leo_errors::FlattenError;
 
 impl ExpressionReconstructor for Flattener<'_> {
     type AdditionalOutput = Vec<Statement>;
@@ -82,16 +83,48 @@ impl ExpressionReconstructor for Flattener<'_> {
                     _ => unreachable!("Type checking guarantees that all expressions are typed."),
                 };
 
-                // Note that type checking guarantees that both expressions have the same same type. This is a sanity check.
+                // Note that type checking guarantees that both expressions have the same same type.
+                // However, a malformed AST would otherwise cause a panic when flattening the branches.
+                if let Some(error) = Self::check_ternary_branch_types(&first_type, &second_type, input.span) {
+                    self.handler.emit_err(error);
+                    // Recover by returning the ternary expression unmodified.
+                    return (
+                        Expression::Ternary(TernaryExpression {
+                            condition: input.condition,
+                            if_true: Box::new(Expression::Identifier(first)),
+                            if_false: Box::new(Expression::Identifier(second)),
+                            span: input.span,
+                            id: input.id,
+                        }),
+                        statements,
+                    );
+                }
+
+                // This is a sanity check.
                 assert!(first_type.eq_flat(&second_type));
 
                 match &first_type {
                     Type::Array(first_type) => self.ternary_array(first_type, &input.condition, &first, &second),
-                    Type::Identifier(first_type) => {
+                    Type::Identifier(first_type) => match self.symbol_table.lookup_struct(first_type.name) {
                         // Get the struct definitions.
-                        let first_type = self.symbol_table.lookup_struct(first_type.name).unwrap();
-                        self.ternary_struct(first_type, &input.condition, &first, &second)
-                    }
+                        Some(first_type) => self.ternary_struct(first_type, &input.condition, &first, &second),
+                        // Note that type checking guarantees that the struct is defined.
+                        // However, a malformed symbol table would otherwise cause a panic.
+                        None => {
+                            self.handler.emit_err(FlattenError::ternary_struct_not_found(first_type, input.span));
+                            // Recover by returning the ternary expression unmodified.
+                            (
+                                Expression::Ternary(TernaryExpression {
+                                    condition: input.condition,
+                                    if_true: Box::new(Expression::Identifier(first)),
+                                    if_false: Box::new(Expression::Identifier(second)),
+                                    span: input.span,
+                                    id: input.id,
+                                }),
+                                statements,
+                            )
+                        }
+                    },
                     Type::Tuple(first_type) => self.ternary_tuple(first_type, &input.condition, &first, &second),
                     _ => {
                         // Reconstruct the true case.
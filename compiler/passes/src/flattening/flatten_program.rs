@@ -16,14 +16,61 @@
 
 use crate::Flattener;
 
-use leo_ast::{Finalize, Function, ProgramReconstructor, StatementReconstructor};
+use leo_ast::{
+    Finalize,
+    Function,
+    FunctionInput,
+    Identifier,
+    Input,
+    Mode,
+    ProgramReconstructor,
+    StatementReconstructor,
+    Type,
+};
+use leo_span::sym;
 
 impl ProgramReconstructor for Flattener<'_> {
     /// Flattens a function's body and finalize block, if it exists.
+    /// If the function is annotated with `@noflatten`, its return statements are not folded.
+    /// Otherwise, the return statements of the body and the finalize block are folded unless disabled by the respective flag.
+    /// Afterwards, the finalize arguments in the returns of the body are checked against the inputs of the finalize block.
+    ///
+    /// Since Aleo instructions cannot skip a `finalize`, a function that finalizes on only some of its execution paths
+    /// always finalizes, with an additional boolean argument that is true if and only if the function would have finalized.
+    /// Note that this changes the interface of the finalize block, which receives the guard as a final, public input.
+    /// The body of the finalize block is wrapped in a conditional statement on the guard, which code generation lowers to a branch.
+    /// Hence, the body, including its mapping updates, is skipped entirely if the function would not have finalized.
     fn reconstruct_function(&mut self, function: Function) -> Function {
+        // Check whether return folding is disabled for the function.
+        self.is_noflatten = function.annotations.iter().any(|annotation| annotation.identifier.name == sym::noflatten);
+        let finalizes_conditionally = Self::finalizes_conditionally(&function.block);
+
         // First, flatten the finalize block. This allows us to initialize self.finalizes correctly.
         // Note that this is safe since the finalize block is independent of the function body.
         let finalize = function.finalize.map(|finalize| {
+            self.skip_return_folding = self.is_noflatten || !self.finalize_folding_enabled;
+            self.return_name = self.named_returns_enabled.then(|| format!("{}$finalize", function.identifier));
+
+            // If the function finalizes conditionally, add the guard as an input.
+            let mut input = finalize.input;
+            let guard = finalizes_conditionally.then(|| {
+                let guard = Identifier {
+                    name: self.assigner.unique_symbol("finalize$guard", "$"),
+                    span: Default::default(),
+                    id: self.node_builder.next_id(),
+                };
+                self.type_table.insert(guard.id, Type::Boolean);
+                input.push(Input::Internal(FunctionInput {
+                    identifier: guard,
+                    mode: Mode::None,
+                    type_: Type::Boolean,
+                    span: Default::default(),
+                    id: self.node_builder.next_id(),
+                }));
+                guard
+            });
+            self.finalize_guard = guard;
+
             // Flatten the finalize block.
             let mut block = self.reconstruct_block(finalize.block).0;
 
@@ -31,11 +78,16 @@ impl ProgramReconstructor for Flattener<'_> {
             let returns = self.clear_early_returns();
 
             // Fold the return statements into the block.
-            self.fold_returns(&mut block, returns);
+            self.fold_returns_unless_skipped(&mut block, returns);
+
+            // If the function finalizes conditionally, execute the block only if the guard holds.
+            if let Some(guard) = guard {
+                self.guard_block(&mut block, guard);
+            }
 
             Finalize {
                 identifier: finalize.identifier,
-                input: finalize.input,
+                input,
                 output: finalize.output,
                 output_type: finalize.output_type,
                 block,
@@ -45,15 +97,17 @@ impl ProgramReconstructor for Flattener<'_> {
         });
 
         // Flatten the function body.
+        self.skip_return_folding = self.is_noflatten || !self.return_folding_enabled;
+        self.return_name = self.named_returns_enabled.then(|| function.identifier.to_string());
         let mut block = self.reconstruct_block(function.block).0;
 
         // Get all of the guards and return expression.
         let returns = self.clear_early_returns();
 
         // Fold the return statements into the block.
-        self.fold_returns(&mut block, returns);
+        self.fold_returns_unless_skipped(&mut block, returns);
 
-        Function {
+        let function = Function {
             annotations: function.annotations,
             variant: function.variant,
             identifier: function.identifier,
@@ -64,6 +118,12 @@ impl ProgramReconstructor for Flattener<'_> {
             finalize,
             span: function.span,
             id: function.id,
-        }
+        };
+
+        // Check that the finalize arguments match the inputs of the finalize block.
+        self.check_finalize_arguments(&function);
+        self.finalize_guard = None;
+
+        function
     }
 }
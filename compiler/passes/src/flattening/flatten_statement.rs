@@ -17,6 +17,7 @@
 use crate::Flattener;
 
 use leo_ast::{
+    AccessExpression,
     AssertStatement,
     AssertVariant,
     AssignStatement,
@@ -28,6 +29,7 @@ use leo_ast::{
     DefinitionStatement,
     Expression,
     ExpressionReconstructor,
+    ExpressionStatement,
     IterationStatement,
     Node,
     ReturnStatement,
@@ -37,6 +39,7 @@ use leo_ast::{
     UnaryExpression,
     UnaryOperation,
 };
+use leo_errors::FlattenError;
 
 use itertools::Itertools;
 
@@ -63,6 +66,7 @@ impl StatementReconstructor for Flattener<'_> {
 
         // Flatten the arguments of the assert statement.
         let assert = AssertStatement {
+            message: input.message,
             span: input.span,
             id: input.id,
             variant: match input.variant {
@@ -89,7 +93,7 @@ impl StatementReconstructor for Flattener<'_> {
         };
 
         // Add the appropriate guards.
-        match self.construct_guard() {
+        match self.construct_statement_guard() {
             // If the condition stack is empty, we can return the flattened assert statement.
             None => (Statement::Assert(assert), statements),
             // Otherwise, we need to join the guard with the expression in the flattened assert statement.
@@ -97,6 +101,7 @@ impl StatementReconstructor for Flattener<'_> {
             // which is equivalent to `!guard || expression`.
             Some(guard) => (
                 Statement::Assert(AssertStatement {
+                    message: assert.message,
                     span: input.span,
                     id: input.id,
                     variant: AssertVariant::Assert(Expression::Binary(BinaryExpression {
@@ -204,7 +209,7 @@ impl StatementReconstructor for Flattener<'_> {
 
     // TODO: Do we want to flatten nested blocks? They do not affect code generation but it would regularize the AST structure.
     /// Flattens the statements inside a basic block.
-    /// The resulting block does not contain any conditional statements.
+    /// The resulting block does not contain any conditional statements, unless they are preserved.
     fn reconstruct_block(&mut self, block: Block) -> (Block, Self::AdditionalOutput) {
         let mut statements = Vec::with_capacity(block.statements.len());
 
@@ -219,20 +224,19 @@ impl StatementReconstructor for Flattener<'_> {
     }
 
     /// Flatten a conditional statement into a list of statements.
+    /// If conditional statements are preserved, the branches are reconstructed in place instead.
     fn reconstruct_conditional(&mut self, conditional: ConditionalStatement) -> (Statement, Self::AdditionalOutput) {
-        let mut statements = Vec::with_capacity(conditional.then.statements.len());
-
         // Add condition to the condition stack.
         self.condition_stack.push(conditional.condition.clone());
 
-        // Reconstruct the then-block and accumulate it constituent statements.
-        statements.extend(self.reconstruct_block(conditional.then).0.statements);
+        // Reconstruct the then-block.
+        let then = self.reconstruct_block(conditional.then).0;
 
         // Remove condition from the condition stack.
         self.condition_stack.pop();
 
-        // Consume the otherwise-block and flatten its constituent statements into the current block.
-        if let Some(statement) = conditional.otherwise {
+        // Reconstruct the otherwise-block.
+        let otherwise = conditional.otherwise.map(|statement| {
             // Add the negated condition to the condition stack.
             self.condition_stack.push(Expression::Unary(UnaryExpression {
                 op: UnaryOperation::Not,
@@ -241,17 +245,35 @@ impl StatementReconstructor for Flattener<'_> {
                 id: conditional.condition.id(),
             }));
 
-            // Reconstruct the otherwise-block and accumulate it constituent statements.
-            match *statement {
-                Statement::Block(block) => statements.extend(self.reconstruct_block(block).0.statements),
+            let block = match *statement {
+                Statement::Block(block) => self.reconstruct_block(block).0,
                 _ => unreachable!("SSA guarantees that the `otherwise` is always a `Block`"),
-            }
+            };
 
             // Remove the negated condition from the condition stack.
             self.condition_stack.pop();
-        };
 
-        (Statement::dummy(Default::default(), self.node_builder.next_id()), statements)
+            block
+        });
+
+        match self.preserve_conditionals {
+            true => (
+                Statement::Conditional(ConditionalStatement {
+                    condition: conditional.condition,
+                    then,
+                    otherwise: otherwise.map(|block| Box::new(Statement::Block(block))),
+                    span: conditional.span,
+                    id: conditional.id,
+                }),
+                Default::default(),
+            ),
+            // Otherwise, accumulate the constituent statements of both blocks into the current block.
+            false => {
+                let mut statements = then.statements;
+                statements.extend(otherwise.into_iter().flat_map(|block| block.statements));
+                (Statement::dummy(Default::default(), self.node_builder.next_id()), statements)
+            }
+        }
     }
 
     fn reconstruct_console(&mut self, _: ConsoleStatement) -> (Statement, Self::AdditionalOutput) {
@@ -262,16 +284,48 @@ impl StatementReconstructor for Flattener<'_> {
         unreachable!("`DefinitionStatement`s should not exist in the AST at this phase of compilation.")
     }
 
+    /// Flattens an expression statement.
+    /// If mapping update rejection is enabled, a `Mapping::set` or `Mapping::remove` inside a conditional statement is an error,
+    /// since Aleo instructions cannot update a mapping conditionally.
+    /// Note that writing back the current value when the condition is false is not sufficient,
+    /// since a key that is not in the mapping would be inserted regardless of the condition.
+    fn reconstruct_expression_statement(&mut self, input: ExpressionStatement) -> (Statement, Self::AdditionalOutput) {
+        let (expression, statements) = self.reconstruct_expression(input.expression);
+
+        if let Expression::Access(AccessExpression::AssociatedFunction(function)) = &expression {
+            if self.mapping_update_rejection_enabled
+                && Self::is_mapping_update(function)
+                && self.construct_statement_guard().is_some()
+            {
+                self.handler.emit_err(FlattenError::conditional_mapping_update(function.name, input.span));
+            }
+        }
+
+        (Statement::Expression(ExpressionStatement { expression, span: input.span, id: input.id }), statements)
+    }
+
     fn reconstruct_iteration(&mut self, _input: IterationStatement) -> (Statement, Self::AdditionalOutput) {
         unreachable!("`IterationStatement`s should not be in the AST at this phase of compilation.");
     }
 
     /// Transforms a return statement into an empty block statement.
     /// Stores the arguments to the return statement, which are later folded into a single return statement at the end of the function.
+    /// If return folding is disabled, unconditional return statements are left in place.
     fn reconstruct_return(&mut self, input: ReturnStatement) -> (Statement, Self::AdditionalOutput) {
         // Construct the associated guard.
         let guard = self.construct_guard();
 
+        if self.skip_return_folding {
+            match guard {
+                None => return (Statement::Return(input), Default::default()),
+                // Conditional returns can only be expressed by folding them into a single return.
+                Some(_) => match self.is_noflatten {
+                    true => self.handler.emit_err(FlattenError::conditional_return_in_noflatten_function(input.span)),
+                    false => self.handler.emit_err(FlattenError::conditional_return_without_return_folding(input.span)),
+                },
+            }
+        }
+
         match input.expression {
             Expression::Unit(_) | Expression::Identifier(_) => self.returns.push((guard, input)),
             _ => unreachable!("SSA guarantees that the expression is always an identifier or unit expression."),
@@ -14,18 +14,23 @@
 // You should have received a copy of the GNU General Public License
 // along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{Assigner, SymbolTable, TypeTable};
+use crate::{Assigner, FunctionSymbol, SymbolTable, TypeTable};
 
 use leo_ast::{
     AccessExpression,
     ArrayAccess,
     ArrayExpression,
     ArrayType,
+    AssertStatement,
+    AssertVariant,
+    AssociatedFunction,
     BinaryExpression,
     BinaryOperation,
     Block,
+    ConditionalStatement,
     Expression,
     ExpressionReconstructor,
+    Function,
     Identifier,
     IntegerType,
     Literal,
@@ -44,14 +49,48 @@ use leo_ast::{
     TupleExpression,
     TupleType,
     Type,
+    UnaryExpression,
+    UnaryOperation,
     UnitExpression,
 };
+use leo_errors::{emitter::Handler, FlattenError};
+use leo_span::{sym, Span};
+
+/// The options that configure the flattening pass.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FlattenerOptions {
+    /// Whether the returns in function bodies are folded into a single return at the end of the block.
+    pub return_folding_enabled: bool,
+    /// Whether the returns in finalize blocks are folded into a single return at the end of the block.
+    pub finalize_folding_enabled: bool,
+    /// Whether mapping updates inside conditional statements are rejected.
+    pub mapping_update_rejection_enabled: bool,
+    /// Whether the variables produced by folding returns are named after the function.
+    pub named_returns_enabled: bool,
+    /// Whether folded returns assert that their statically contradictory guards are false.
+    pub unreachable_assertions_enabled: bool,
+}
+
+impl Default for FlattenerOptions {
+    /// Returns the default options, in which returns are folded, and all other options are disabled.
+    fn default() -> Self {
+        Self {
+            return_folding_enabled: true,
+            finalize_folding_enabled: true,
+            mapping_update_rejection_enabled: false,
+            named_returns_enabled: false,
+            unreachable_assertions_enabled: false,
+        }
+    }
+}
 
 pub struct Flattener<'a> {
     /// The symbol table associated with the program.
     pub(crate) symbol_table: &'a SymbolTable,
     /// A mapping between node IDs and their types.
     pub(crate) type_table: &'a TypeTable,
+    /// The error handler.
+    pub(crate) handler: &'a Handler,
     /// A counter used to generate unique node IDs.
     pub(crate) node_builder: &'a NodeBuilder,
     /// A struct used to construct (unique) assignment statements.
@@ -63,16 +102,216 @@ pub struct Flattener<'a> {
     /// Note that returns are inserted in the order they are encountered during a pre-order traversal of the AST.
     /// Note that type checking guarantees that there is at most one return in a basic block.
     pub(crate) returns: Vec<(Option<Expression>, ReturnStatement)>,
+    /// Whether the current function is annotated with `@noflatten`.
+    pub(crate) is_noflatten: bool,
+    /// Whether return folding is skipped for the current block.
+    /// If so, `ReturnStatement`s are left in place instead of being folded into a single return at the end of the block.
+    pub(crate) skip_return_folding: bool,
+    /// Whether the returns in function bodies are folded into a single return at the end of the block.
+    pub(crate) return_folding_enabled: bool,
+    /// Whether the returns in finalize blocks are folded into a single return at the end of the block.
+    pub(crate) finalize_folding_enabled: bool,
+    /// Whether mapping updates inside conditional statements are rejected.
+    pub(crate) mapping_update_rejection_enabled: bool,
+    /// Whether the variables produced by folding returns are named after the function, rather than `$ret` and `finalize`.
+    pub(crate) named_returns_enabled: bool,
+    /// Whether conditional statements are left in place, rather than flattened into the enclosing block.
+    pub(crate) preserve_conditionals: bool,
+    /// Whether folded returns assert that their statically contradictory guards are false.
+    pub(crate) unreachable_assertions_enabled: bool,
+    /// The name used to prefix the variables produced by folding the returns of the current block, if enabled.
+    pub(crate) return_name: Option<String>,
+    /// The input of the current finalize block that is true if and only if the function finalizes.
+    /// This exists only if the function finalizes on some, but not all, of its execution paths.
+    pub(crate) finalize_guard: Option<Identifier>,
 }
 
 impl<'a> Flattener<'a> {
     pub(crate) fn new(
         symbol_table: &'a SymbolTable,
         type_table: &'a TypeTable,
+        handler: &'a Handler,
         node_builder: &'a NodeBuilder,
         assigner: &'a Assigner,
     ) -> Self {
-        Self { symbol_table, type_table, node_builder, assigner, condition_stack: Vec::new(), returns: Vec::new() }
+        Self {
+            symbol_table,
+            type_table,
+            handler,
+            node_builder,
+            assigner,
+            condition_stack: Vec::new(),
+            returns: Vec::new(),
+            is_noflatten: false,
+            skip_return_folding: false,
+            return_folding_enabled: true,
+            finalize_folding_enabled: true,
+            mapping_update_rejection_enabled: false,
+            named_returns_enabled: false,
+            preserve_conditionals: false,
+            unreachable_assertions_enabled: false,
+            return_name: None,
+            finalize_guard: None,
+        }
+    }
+
+    /// Sets the options that configure the pass.
+    pub fn with_options(self, options: FlattenerOptions) -> Self {
+        self.with_return_folding(options.return_folding_enabled)
+            .with_finalize_folding(options.finalize_folding_enabled)
+            .with_mapping_update_rejection(options.mapping_update_rejection_enabled)
+            .with_named_returns(options.named_returns_enabled)
+            .with_unreachable_assertions(options.unreachable_assertions_enabled)
+    }
+
+    /// Sets whether the returns in function bodies are folded into a single return at the end of the block.
+    /// If disabled, return statements are left in place, as if each function were annotated with `@noflatten`.
+    pub fn with_return_folding(mut self, enabled: bool) -> Self {
+        self.return_folding_enabled = enabled;
+        self
+    }
+
+    /// Sets whether the returns in finalize blocks are folded into a single return at the end of the block.
+    /// This is independent of the folding of returns in function bodies.
+    pub fn with_finalize_folding(mut self, enabled: bool) -> Self {
+        self.finalize_folding_enabled = enabled;
+        self
+    }
+
+    /// Sets whether mapping updates inside conditional statements are rejected.
+    /// Since Aleo instructions cannot update a mapping conditionally, such an update is an error if enabled.
+    /// If disabled, mapping updates inside conditional statements are performed on every execution path.
+    pub fn with_mapping_update_rejection(mut self, enabled: bool) -> Self {
+        self.mapping_update_rejection_enabled = enabled;
+        self
+    }
+
+    /// Sets whether the variables produced by folding returns are named after the function.
+    /// If enabled, the folded return of `main` is named `main$out`, rather than `$ret`,
+    /// and the folded returns and arguments of its finalize block are prefixed with `main$finalize`.
+    pub fn with_named_returns(mut self, enabled: bool) -> Self {
+        self.named_returns_enabled = enabled;
+        self
+    }
+
+    /// Sets whether conditional statements are left in place, rather than flattened into the enclosing block.
+    /// If enabled, the statements of each branch stay inside the branch, so assertions and mapping updates
+    /// are no longer guarded by the conditions of the enclosing conditional statements.
+    /// Returns are still folded into a single return at the end of the block, and ternary expressions
+    /// over composite data types are still expanded.
+    /// Note that later passes, such as code generation, do not support conditional statements.
+    pub fn with_preserved_conditionals(mut self, enabled: bool) -> Self {
+        self.preserve_conditionals = enabled;
+        self
+    }
+
+    /// Sets whether folded returns assert that their statically contradictory guards are false.
+    /// If enabled, folding a return whose guard can never be true, e.g. `!c && c`, produces `assert(!(!c && c))`,
+    /// so that reaching the return at runtime fails, rather than silently selecting a dead branch of the ternary.
+    /// This is a debugging aid, which does not change the value of the folded return.
+    pub fn with_unreachable_assertions(mut self, enabled: bool) -> Self {
+        self.unreachable_assertions_enabled = enabled;
+        self
+    }
+
+    /// Returns `true` if some, but not all, of the returns in `block` call `finalize`.
+    pub(crate) fn finalizes_conditionally(block: &Block) -> bool {
+        // Records whether each return in the statement calls `finalize`.
+        fn collect_finalizes(statement: &Statement, finalizes: &mut Vec<bool>) {
+            match statement {
+                Statement::Return(return_) => finalizes.push(return_.finalize_arguments.is_some()),
+                Statement::Block(block) => block.statements.iter().for_each(|s| collect_finalizes(s, finalizes)),
+                Statement::Conditional(conditional) => {
+                    conditional.then.statements.iter().for_each(|s| collect_finalizes(s, finalizes));
+                    if let Some(otherwise) = &conditional.otherwise {
+                        collect_finalizes(otherwise, finalizes);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut finalizes = Vec::new();
+        block.statements.iter().for_each(|statement| collect_finalizes(statement, &mut finalizes));
+        finalizes.contains(&true) && finalizes.contains(&false)
+    }
+
+    /// Checks that the finalize arguments in the returns at the end of `function` match the inputs of its finalize block,
+    /// as recorded in the symbol table. This catches mismatches introduced by folding before code generation.
+    /// If the finalize block is guarded, the guard is expected as an additional, final argument.
+    pub(crate) fn check_finalize_arguments(&self, function: &Function) {
+        let finalize = match self.symbol_table.lookup_fn_symbol(function.identifier.name) {
+            Some(FunctionSymbol { finalize: Some(finalize), .. }) => finalize,
+            _ => return,
+        };
+        let mut input_types = finalize.input.iter().map(|input| input.type_()).collect::<Vec<_>>();
+        if self.finalize_guard.is_some() {
+            input_types.push(Type::Boolean);
+        }
+
+        for statement in function.block.statements.iter() {
+            let (arguments, span) = match statement {
+                Statement::Return(ReturnStatement { finalize_arguments: Some(arguments), span, .. }) => {
+                    // Synthesized returns may not have a span, in which case the function is reported instead.
+                    (arguments, if span.is_dummy() { function.span } else { *span })
+                }
+                _ => continue,
+            };
+
+            if arguments.len() != input_types.len() {
+                self.handler.emit_err(FlattenError::finalize_argument_count_mismatch(
+                    function.identifier,
+                    input_types.len(),
+                    arguments.len(),
+                    span,
+                ));
+                continue;
+            }
+
+            for (position, (argument, input_type)) in arguments.iter().zip(input_types.iter()).enumerate() {
+                // Note that arguments without a type cannot be checked.
+                if let Some(type_) = self.type_table.get(&argument.id()) {
+                    if !type_.eq_flat(input_type) {
+                        self.handler.emit_err(FlattenError::finalize_argument_type_mismatch(
+                            function.identifier,
+                            position + 1,
+                            input_type,
+                            type_,
+                            span,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns an error if the branches of a ternary expression have types that cannot be flattened together.
+    pub(crate) fn check_ternary_branch_types(
+        first_type: &Type,
+        second_type: &Type,
+        span: Span,
+    ) -> Option<FlattenError> {
+        match (first_type, second_type) {
+            (Type::Tuple(first), Type::Tuple(second)) if first.length() != second.length() => {
+                Some(FlattenError::ternary_tuple_arity_mismatch(first.length(), second.length(), span))
+            }
+            (Type::Identifier(first), Type::Identifier(second)) if first.name != second.name => {
+                Some(FlattenError::ternary_struct_mismatch(first, second, span))
+            }
+            _ => None,
+        }
+    }
+
+    /// Folds the returns into the block, unless return folding is disabled and the block already ends in a return.
+    pub(crate) fn fold_returns_unless_skipped(
+        &mut self,
+        block: &mut Block,
+        returns: Vec<(Option<Expression>, ReturnStatement)>,
+    ) {
+        match self.skip_return_folding && matches!(block.statements.last(), Some(Statement::Return(_))) {
+            true => {}
+            false => self.fold_returns(block, returns),
+        }
     }
 
     /// Clears the state associated with `ReturnStatements`, returning the ones that were previously stored.
@@ -80,6 +319,37 @@ impl<'a> Flattener<'a> {
         core::mem::take(&mut self.returns)
     }
 
+    /// Constructs the guard of a statement that is not a return, such as an assertion or a mapping update.
+    /// If conditional statements are preserved, the conditions are already enforced by the conditional statements.
+    pub(crate) fn construct_statement_guard(&mut self) -> Option<Expression> {
+        match self.preserve_conditionals {
+            true => None,
+            false => self.construct_guard(),
+        }
+    }
+
+    /// Wraps the statements of `block` in a conditional statement on `guard`, so that they only execute if it holds.
+    /// A trailing return statement is left outside of the conditional statement, so that the block still ends in it.
+    pub(crate) fn guard_block(&mut self, block: &mut Block, guard: Identifier) {
+        let return_ = match block.statements.last() {
+            Some(Statement::Return(_)) => block.statements.pop(),
+            _ => None,
+        };
+        let then = Block {
+            statements: core::mem::take(&mut block.statements),
+            span: Default::default(),
+            id: self.node_builder.next_id(),
+        };
+        block.statements.push(Statement::Conditional(ConditionalStatement {
+            condition: Expression::Identifier(guard),
+            then,
+            otherwise: None,
+            span: Default::default(),
+            id: self.node_builder.next_id(),
+        }));
+        block.statements.extend(return_);
+    }
+
     /// Constructs a guard from the current state of the condition stack.
     pub(crate) fn construct_guard(&mut self) -> Option<Expression> {
         match self.condition_stack.is_empty() {
@@ -106,7 +376,58 @@ impl<'a> Flattener<'a> {
         }
     }
 
+    /// Returns `true` if the guard can never be true, i.e. it is a conjunction that contains `false`,
+    /// or that contains both a condition and its negation, e.g. `!c && c`.
+    pub(crate) fn is_contradictory(guard: &Expression) -> bool {
+        // Collects the operands of a chain of conjunctions.
+        fn collect_conjuncts<'b>(expression: &'b Expression, conjuncts: &mut Vec<&'b Expression>) {
+            match expression {
+                Expression::Binary(BinaryExpression { op: BinaryOperation::And, left, right, .. }) => {
+                    collect_conjuncts(left, conjuncts);
+                    collect_conjuncts(right, conjuncts);
+                }
+                _ => conjuncts.push(expression),
+            }
+        }
+
+        let mut conjuncts = Vec::new();
+        collect_conjuncts(guard, &mut conjuncts);
+        conjuncts.iter().any(|conjunct| match conjunct {
+            Expression::Literal(Literal::Boolean(false, ..)) => true,
+            // Note that the conditions are identifiers after SSA, so it suffices to compare their names.
+            Expression::Unary(UnaryExpression { op: UnaryOperation::Not, receiver, .. }) => {
+                conjuncts.iter().any(|other| match (&**receiver, other) {
+                    (Expression::Identifier(first), Expression::Identifier(second)) => first.name == second.name,
+                    _ => false,
+                })
+            }
+            _ => false,
+        })
+    }
+
+    /// Constructs an assertion that the guard is false, i.e. `assert(!guard)`.
+    pub(crate) fn unreachable_assertion(&mut self, guard: Expression) -> Statement {
+        Statement::Assert(AssertStatement {
+            variant: AssertVariant::Assert(Expression::Unary(UnaryExpression {
+                op: UnaryOperation::Not,
+                receiver: Box::new(guard),
+                span: Default::default(),
+                id: {
+                    // Create a new node ID for the unary expression.
+                    let id = self.node_builder.next_id();
+                    // Set the type of the node ID.
+                    self.type_table.insert(id, Type::Boolean);
+                    id
+                },
+            })),
+            message: None,
+            span: Default::default(),
+            id: self.node_builder.next_id(),
+        })
+    }
+
     /// Fold guards and expressions into a single expression.
+    /// Guards that are statically `true` or `false` are resolved directly, rather than producing a ternary expression.
     /// Note that this function assumes that at least one guard is present.
     pub(crate) fn fold_guards(
         &mut self,
@@ -124,6 +445,20 @@ impl<'a> Flattener<'a> {
                 // Produce a chain of ternary expressions and assignments for the guards.
                 let mut statements = Vec::with_capacity(guards.len());
 
+                // If enabled, assert that the statically contradictory guards are false.
+                // Note that guards that are the literal `false` are resolved directly, so they need no assertion.
+                if self.unreachable_assertions_enabled {
+                    let contradictory = guards
+                        .iter()
+                        .filter_map(|(guard, _)| guard.as_ref())
+                        .filter(|guard| !matches!(guard, Expression::Literal(_)) && Self::is_contradictory(guard))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    for guard in contradictory {
+                        statements.push(self.unreachable_assertion(guard));
+                    }
+                }
+
                 // Helper to construct and store ternary assignments. e.g `$ret$0 = $var$0 ? $var$1 : $var$2`
                 let mut construct_ternary_assignment =
                     |guard: Expression, if_true: Expression, if_false: Expression| {
@@ -166,6 +501,11 @@ impl<'a> Flattener<'a> {
 
                 let expression = guards.into_iter().rev().fold(last_expression, |acc, (guard, expr)| match guard {
                     None => unreachable!("All expressions except for the last one must have a guard."),
+                    // If the guard is statically `true`, then the expression is always selected.
+                    // Statements producing the expressions have already been emitted, so their order is preserved.
+                    Some(Expression::Literal(Literal::Boolean(true, ..))) => expr,
+                    // If the guard is statically `false`, then the expression is never selected.
+                    Some(Expression::Literal(Literal::Boolean(false, ..))) => acc,
                     // Note that type checking guarantees that all expressions have the same type.
                     Some(guard) => construct_ternary_assignment(guard, expr, acc),
                 });
@@ -175,6 +515,12 @@ impl<'a> Flattener<'a> {
         }
     }
 
+    /// Returns whether the associated function updates a mapping, i.e. is a `Mapping::set` or a `Mapping::remove`.
+    pub(crate) fn is_mapping_update(function: &AssociatedFunction) -> bool {
+        matches!(function.ty, Type::Identifier(Identifier { name: sym::Mapping, .. }))
+            && matches!(function.name.name, sym::set | sym::remove)
+    }
+
     /// A wrapper around `assigner.unique_simple_assign_statement` that updates `self.structs`.
     pub(crate) fn unique_simple_assign_statement(&mut self, expr: Expression) -> (Identifier, Statement) {
         // Create a new variable for the expression.
@@ -187,6 +533,13 @@ impl<'a> Flattener<'a> {
         (place, statement)
     }
 
+    /// Constructs a boolean literal and records its type.
+    pub(crate) fn boolean_literal(&mut self, value: bool) -> Expression {
+        let id = self.node_builder.next_id();
+        self.type_table.insert(id, Type::Boolean);
+        Expression::Literal(Literal::Boolean(value, Default::default(), id))
+    }
+
     /// A wrapper around `assigner.simple_assign_statement` that tracks the type of the lhs.
     pub(crate) fn simple_assign_statement(&mut self, lhs: Identifier, rhs: Expression) -> Statement {
         // Update the type table.
@@ -200,23 +553,31 @@ impl<'a> Flattener<'a> {
     }
 
     /// Folds a list of return statements into a single return statement and adds the produced statements to the block.
+    /// If the finalize block is guarded, the returns that do not call `finalize` only contribute to the guard argument,
+    /// which is true if and only if the function finalizes.
     pub(crate) fn fold_returns(&mut self, block: &mut Block, returns: Vec<(Option<Expression>, ReturnStatement)>) {
         // If the list of returns is not empty, then fold them into a single return statement.
         if !returns.is_empty() {
             let mut return_expressions = Vec::with_capacity(returns.len());
 
             // Construct a vector for each argument position.
-            // Note that the indexing is safe since we check that `returns` is not empty.
-            let (has_finalize, number_of_finalize_arguments) = match &returns[0].1.finalize_arguments {
-                None => (false, 0),
-                Some(args) => (true, args.len()),
-            };
+            // Note that the number of arguments is taken from the first return that calls `finalize`, if any.
+            let (has_finalize, number_of_finalize_arguments) =
+                match returns.iter().find_map(|(_, return_statement)| return_statement.finalize_arguments.as_ref()) {
+                    None => (false, 0),
+                    Some(args) => (true, args.len()),
+                };
             let mut finalize_arguments: Vec<Vec<(Option<Expression>, Expression)>> =
                 vec![Vec::with_capacity(returns.len()); number_of_finalize_arguments];
+            let mut finalize_guards = Vec::new();
 
             // Aggregate the return expressions and finalize arguments and their respective guards.
             for (guard, return_statement) in returns {
                 return_expressions.push((guard.clone(), return_statement.expression));
+                if has_finalize && self.finalize_guard.is_some() {
+                    let finalizes = return_statement.finalize_arguments.is_some();
+                    finalize_guards.push((guard.clone(), self.boolean_literal(finalizes)));
+                }
                 if let Some(arguments) = return_statement.finalize_arguments {
                     for (i, argument) in arguments.into_iter().enumerate() {
                         // Note that the indexing is safe since we initialize `finalize_arguments` with the correct length.
@@ -225,33 +586,47 @@ impl<'a> Flattener<'a> {
                 }
             }
 
+            // Get the prefixes of the folded return expression and finalize arguments.
+            let (return_prefix, finalize_prefix) = match &self.return_name {
+                Some(name) => (format!("{name}$out"), format!("{name}$finalize$")),
+                None => ("$ret".to_string(), "finalize$".to_string()),
+            };
+
             // Fold the return expressions into a single expression.
-            let (expression, stmts) = self.fold_guards("$ret", return_expressions);
+            let (expression, stmts) = self.fold_guards(&return_prefix, return_expressions);
 
             // Add all of the accumulated statements to the end of the block.
             block.statements.extend(stmts);
 
             // For each position in the finalize call, fold the corresponding arguments into a single expression.
-            let finalize_arguments = match has_finalize {
+            let mut finalize_arguments = match has_finalize {
                 false => None,
                 true => Some(
                     finalize_arguments
                         .into_iter()
                         .enumerate()
                         .map(|(i, arguments)| {
-                            let (expression, stmts) = self.fold_guards(&format!("finalize${i}$"), arguments);
+                            let (expression, stmts) = self.fold_guards(&format!("{finalize_prefix}{i}$"), arguments);
                             block.statements.extend(stmts);
                             expression
                         })
-                        .collect(),
+                        .collect::<Vec<_>>(),
                 ),
             };
 
+            // If the finalize block is guarded, fold whether each return calls `finalize` into the final argument.
+            if let Some(arguments) = finalize_arguments.as_mut().filter(|_| !finalize_guards.is_empty()) {
+                let (expression, stmts) = self.fold_guards(&format!("{finalize_prefix}guard$"), finalize_guards);
+                block.statements.extend(stmts);
+                arguments.push(expression);
+            }
+
             // Add the `ReturnStatement` to the end of the block.
+            // Since the return combines all of the returns in the block, it is given the span of the block.
             block.statements.push(Statement::Return(ReturnStatement {
                 expression,
                 finalize_arguments,
-                span: Default::default(),
+                span: block.span,
                 id: self.node_builder.next_id(),
             }));
         }
@@ -263,7 +638,7 @@ impl<'a> Flattener<'a> {
                     Expression::Unit(UnitExpression { span: Default::default(), id })
                 },
                 finalize_arguments: None,
-                span: Default::default(),
+                span: block.span,
                 id: self.node_builder.next_id(),
             }));
         }
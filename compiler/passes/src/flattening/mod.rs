@@ -17,6 +17,11 @@
 //! The flattening pass traverses the AST after the SSA pass and converts into a sequential code.
 //! The pass flattens `ConditionalStatement`s into a sequence of `AssignStatement`s.
 //! The pass rewrites `ReturnStatement`s into `AssignStatement`s and consolidates the returned values as a single `ReturnStatement` at the end of the function.
+//! This consolidation can be disabled for function bodies and finalize blocks independently, in which case unconditional `ReturnStatement`s are left in place.
+//! Optionally, the pass leaves `ConditionalStatement`s in place, in which case only returns are consolidated and nothing is guarded by the branch conditions.
+//! Optionally, the pass rejects mapping updates inside conditional statements, since Aleo instructions cannot update a mapping conditionally.
+//! If a function finalizes on only some of its execution paths, it always finalizes with an additional boolean argument, which the finalize block receives as a final, public input.
+//! This changes the interface of the finalize block. Its body is wrapped in the only `ConditionalStatement` left in place, on the new input, which code generation lowers to a branch.
 //! The pass rewrites ternary expressions over composite data types, into ternary expressions over the individual fields of the composite data type, followed by an expression constructing the composite data type.
 //!
 //! Consider the following Leo code, output by the SSA pass.
@@ -62,16 +67,792 @@ pub use flattener::*;
 use crate::{Assigner, Pass, SymbolTable, TypeTable};
 
 use leo_ast::{Ast, NodeBuilder, ProgramReconstructor};
-use leo_errors::Result;
+use leo_errors::{emitter::Handler, Result};
 
 impl<'a> Pass for Flattener<'a> {
-    type Input = (Ast, &'a SymbolTable, &'a TypeTable, &'a NodeBuilder, &'a Assigner);
+    type Input = (Ast, &'a Handler, &'a SymbolTable, &'a TypeTable, &'a NodeBuilder, &'a Assigner, FlattenerOptions);
     type Output = Result<Ast>;
 
-    fn do_pass((ast, st, tt, node_builder, assigner): Self::Input) -> Self::Output {
-        let mut reconstructor = Flattener::new(st, tt, node_builder, assigner);
+    fn do_pass((ast, handler, st, tt, node_builder, assigner, options): Self::Input) -> Self::Output {
+        let mut reconstructor = Flattener::new(st, tt, handler, node_builder, assigner).with_options(options);
         let program = reconstructor.reconstruct_program(ast.into_repr());
+        handler.last_err().map_err(|e| *e)?;
 
         Ok(Ast::new(program))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_helpers::TestCompiler, StaticSingleAssigner, StaticSingleAssignerOptions};
+
+    use leo_ast::{
+        AssertStatement,
+        AssertVariant,
+        BinaryOperation,
+        Block,
+        Expression,
+        ExpressionReconstructor,
+        Identifier,
+        IntegerType,
+        Literal,
+        Program,
+        ReturnStatement,
+        Statement,
+        TernaryExpression,
+        TupleType,
+        Type,
+    };
+    use leo_span::{symbol::create_session_if_not_set_then, Span, Symbol};
+
+    #[test]
+    fn test_ternary_tuple_arity_mismatch() {
+        create_session_if_not_set_then(|_| {
+            let (handler, buf) = Handler::new_with_buf();
+            let symbol_table = SymbolTable::default();
+            let type_table = TypeTable::default();
+            let node_builder = NodeBuilder::default();
+            let assigner = Assigner::default();
+
+            // Constructs a typed identifier expression.
+            let identifier = |name: &str, type_: Type| {
+                let identifier = Identifier::new(Symbol::intern(name), node_builder.next_id());
+                type_table.insert(identifier.id, type_);
+                Expression::Identifier(identifier)
+            };
+
+            // Construct a ternary expression whose branches have types `(u8, u8)` and `(u8,)`.
+            let u8 = Type::Integer(IntegerType::U8);
+            let ternary = TernaryExpression {
+                condition: Box::new(identifier("cond", Type::Boolean)),
+                if_true: Box::new(identifier("first", Type::Tuple(TupleType::new(vec![u8.clone(), u8.clone()])))),
+                if_false: Box::new(identifier("second", Type::Tuple(TupleType::new(vec![u8])))),
+                span: Default::default(),
+                id: node_builder.next_id(),
+            };
+
+            let mut flattener = Flattener::new(&symbol_table, &type_table, &handler, &node_builder, &assigner);
+            let (expression, statements) = flattener.reconstruct_ternary(ternary);
+
+            // The ternary expression is returned unmodified and the error is reported.
+            assert!(matches!(expression, Expression::Ternary(_)));
+            assert!(statements.is_empty());
+            assert_eq!(handler.err_count(), 1);
+            assert!(buf.extract_errs().last_entry().unwrap().to_string().contains("tuples of different lengths"));
+        })
+    }
+
+    #[test]
+    fn test_ternary_struct_mismatch() {
+        create_session_if_not_set_then(|_| {
+            let (handler, buf) = Handler::new_with_buf();
+            let symbol_table = SymbolTable::default();
+            let type_table = TypeTable::default();
+            let node_builder = NodeBuilder::default();
+            let assigner = Assigner::default();
+
+            // Constructs a typed identifier expression.
+            let identifier = |name: &str, type_: Type| {
+                let identifier = Identifier::new(Symbol::intern(name), node_builder.next_id());
+                type_table.insert(identifier.id, type_);
+                Expression::Identifier(identifier)
+            };
+            let struct_type = |name: &str| Type::Identifier(Identifier::new(Symbol::intern(name), Default::default()));
+
+            // Construct a ternary expression whose branches have types `Foo` and `Bar`.
+            let ternary = TernaryExpression {
+                condition: Box::new(identifier("cond", Type::Boolean)),
+                if_true: Box::new(identifier("first", struct_type("Foo"))),
+                if_false: Box::new(identifier("second", struct_type("Bar"))),
+                span: Default::default(),
+                id: node_builder.next_id(),
+            };
+
+            let mut flattener = Flattener::new(&symbol_table, &type_table, &handler, &node_builder, &assigner);
+            let (expression, statements) = flattener.reconstruct_ternary(ternary);
+
+            // The ternary expression is returned unmodified and the error names both structs.
+            assert!(matches!(expression, Expression::Ternary(_)));
+            assert!(statements.is_empty());
+            assert_eq!(handler.err_count(), 1);
+            let error = buf.extract_errs().last_entry().unwrap().to_string();
+            assert!(error.contains("different structs, `Foo` and `Bar`"));
+        })
+    }
+
+    #[test]
+    fn test_ternary_struct_not_found() {
+        create_session_if_not_set_then(|_| {
+            let (handler, buf) = Handler::new_with_buf();
+            let symbol_table = SymbolTable::default();
+            let type_table = TypeTable::default();
+            let node_builder = NodeBuilder::default();
+            let assigner = Assigner::default();
+
+            // Constructs a typed identifier expression.
+            let identifier = |name: &str, type_: Type| {
+                let identifier = Identifier::new(Symbol::intern(name), node_builder.next_id());
+                type_table.insert(identifier.id, type_);
+                Expression::Identifier(identifier)
+            };
+            let struct_type = Type::Identifier(Identifier::new(Symbol::intern("Foo"), Default::default()));
+
+            // Construct a ternary expression whose branches have type `Foo`, which is not in the symbol table.
+            let ternary = TernaryExpression {
+                condition: Box::new(identifier("cond", Type::Boolean)),
+                if_true: Box::new(identifier("first", struct_type.clone())),
+                if_false: Box::new(identifier("second", struct_type)),
+                span: Default::default(),
+                id: node_builder.next_id(),
+            };
+
+            let mut flattener = Flattener::new(&symbol_table, &type_table, &handler, &node_builder, &assigner);
+            let (expression, statements) = flattener.reconstruct_ternary(ternary);
+
+            // The ternary expression is returned unmodified and the error names the missing struct.
+            assert!(matches!(expression, Expression::Ternary(_)));
+            assert!(statements.is_empty());
+            assert_eq!(handler.err_count(), 1);
+            let error = buf.extract_errs().last_entry().unwrap().to_string();
+            assert!(error.contains("structs of type `Foo`, which is not defined"), "{error}");
+        })
+    }
+
+    #[test]
+    fn test_fold_constant_guards() {
+        create_session_if_not_set_then(|_| {
+            let handler = Handler::default();
+            let symbol_table = SymbolTable::default();
+            let type_table = TypeTable::default();
+            let node_builder = NodeBuilder::default();
+            let assigner = Assigner::default();
+
+            // Constructs a typed identifier expression.
+            let identifier = |name: &str, type_: Type| {
+                let identifier = Identifier::new(Symbol::intern(name), node_builder.next_id());
+                type_table.insert(identifier.id, type_);
+                Expression::Identifier(identifier)
+            };
+            let guard = |value: bool| Some(Expression::Literal(Literal::Boolean(value, Default::default(), 0)));
+            let u8 = Type::Integer(IntegerType::U8);
+            let name = |expression: &Expression| match expression {
+                Expression::Identifier(identifier) => identifier.name.to_string(),
+                _ => panic!("Expected an identifier, found `{expression}`."),
+            };
+
+            let mut flattener = Flattener::new(&symbol_table, &type_table, &handler, &node_builder, &assigner);
+
+            // A statically-true guard selects its expression.
+            let guards = vec![(guard(true), identifier("first", u8.clone())), (None, identifier("second", u8.clone()))];
+            let (expression, statements) = flattener.fold_guards("$ret", guards);
+            assert_eq!(name(&expression), "first");
+            assert!(statements.is_empty());
+
+            // A statically-false guard drops its expression.
+            let guards =
+                vec![(guard(false), identifier("first", u8.clone())), (None, identifier("second", u8.clone()))];
+            let (expression, statements) = flattener.fold_guards("$ret", guards);
+            assert_eq!(name(&expression), "second");
+            assert!(statements.is_empty());
+
+            // Only the dynamic guard produces a ternary expression.
+            let guards = vec![
+                (Some(identifier("cond", Type::Boolean)), identifier("first", u8.clone())),
+                (guard(false), identifier("second", u8.clone())),
+                (None, identifier("third", u8)),
+            ];
+            let (_, statements) = flattener.fold_guards("$ret", guards);
+            assert_eq!(statements.len(), 1);
+            match &statements[0] {
+                Statement::Assign(assign) => assert_eq!(assign.value.to_string(), "(cond ? first : third)"),
+                statement => panic!("Expected an assignment, found `{statement}`."),
+            }
+            assert_eq!(handler.err_count(), 0);
+        })
+    }
+
+    #[test]
+    fn test_ternary_array() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+
+            let program = "
+program test.aleo {
+    transition main(flag: bool, a: u8, b: u8, c: u8, d: u8) -> u8 {
+        let e: [u8; 2] = flag ? [a, b] : [c, d];
+        return e[0u32] + e[1u32];
+    }
+}";
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+            let ast = compiler.static_single_assignment(ast, &symbol_table);
+            let ast = compiler.flatten(ast, &symbol_table);
+            let function = ast.as_repr().program_scopes.values().next().unwrap().functions[0].1.to_string();
+
+            // The ternary expression is lowered into one ternary expression per element.
+            assert_eq!(function.matches("(flag ? ").count(), 2);
+            assert_eq!(function.matches(".0u32").count(), 3);
+            assert_eq!(function.matches(".1u32").count(), 3);
+        })
+    }
+
+    #[test]
+    fn test_ternary_nested_structs() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    struct Inner {
+        x: u8,
+        y: u8,
+    }
+
+    struct Outer {
+        inner: Inner,
+        z: u8,
+    }
+
+    transition main(c: bool, d: bool, a: Outer, b: Outer) -> Outer {
+        let o: Outer = a;
+        if c {
+            o = b;
+            if d {
+                o = a;
+            }
+        }
+        return o;
+    }
+}";
+            let (program, handler) = flatten(program, true, true);
+            assert_eq!(handler.err_count(), 0);
+            let function = program.program_scopes.values().next().unwrap().functions[0].1.to_string();
+            let assignments = function
+                .lines()
+                .filter_map(|line| line.trim().strip_suffix(';')?.split_once(" = "))
+                .collect::<Vec<_>>();
+            let value_of = |name: &str| assignments.iter().find(|(place, _)| *place == name).map(|(_, value)| *value);
+
+            // Both levels of conditionals produce ternary expressions, each of which selects a scalar member.
+            let ternaries = assignments.iter().filter(|(_, value)| value.starts_with('(')).collect::<Vec<_>>();
+            assert!(ternaries.iter().any(|(_, value)| value.starts_with("(c ? ")), "{function}");
+            assert!(ternaries.iter().any(|(_, value)| value.starts_with("(d ? ")), "{function}");
+            for (_, value) in ternaries {
+                let (_, branches) = value.trim_matches(|c| c == '(' || c == ')').split_once(" ? ").unwrap();
+                for branch in branches.split(" : ") {
+                    let access = value_of(branch).unwrap_or_else(|| panic!("`{branch}` is not assigned: {function}"));
+                    assert!([".x", ".y", ".z"].iter().any(|member| access.ends_with(member)), "{function}");
+                }
+            }
+
+            // The nested member is rebuilt from the ternary expressions over its own members.
+            let outer = assignments.iter().rev().find(|(_, value)| value.starts_with("{inner: ")).unwrap().1;
+            let inner = outer.trim_start_matches("{inner: ").split(", ").next().unwrap();
+            assert!(value_of(inner).unwrap().starts_with("{x: "), "{function}");
+        })
+    }
+
+    #[test]
+    fn test_associated_constants() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition constant() -> group {
+        return group::GEN;
+    }
+
+    transition select(c: bool, a: group) -> group {
+        return c ? group::GEN : a;
+    }
+}";
+            // Associated constants in returns and ternary branches are flattened like any other variable.
+            let (program, handler) = flatten(program, true, true);
+            assert_eq!(handler.err_count(), 0);
+            let functions = &program.program_scopes.values().next().unwrap().functions;
+            for (_, function) in functions {
+                assert!(matches!(function.block.statements.last(), Some(Statement::Return(..))), "{function}");
+            }
+            assert!(functions[1].1.to_string().contains("(c ? "), "{}", functions[1].1);
+        })
+    }
+
+    #[test]
+    fn test_constant_propagation() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition constant() -> u8 {
+        let a: u8 = 5u8;
+        return a;
+    }
+
+    transition select(c: bool) -> u8 {
+        let a: u8 = 1u8;
+        let b: u8 = 2u8;
+        return c ? a : b;
+    }
+
+    transition same(c: bool) -> u8 {
+        let a: u8 = 1u8;
+        return c ? a : a;
+    }
+}";
+            let compiler = TestCompiler::default();
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+            let (ast, _) = StaticSingleAssigner::do_pass((
+                ast,
+                &compiler.handler,
+                &compiler.node_builder,
+                &compiler.assigner,
+                &symbol_table,
+                &compiler.type_table,
+                StaticSingleAssignerOptions { constant_propagation_enabled: true, ..Default::default() },
+            ))
+            .unwrap();
+            let ast = compiler.flatten(ast, &symbol_table);
+            assert_eq!(compiler.handler.err_count(), 0);
+
+            // Propagated literals that are returned or selected by a ternary expression are flattened as variables.
+            let functions = &ast.as_repr().program_scopes.values().next().unwrap().functions;
+            for (_, function) in functions {
+                match function.block.statements.last() {
+                    Some(Statement::Return(ReturnStatement { expression: Expression::Identifier(_), .. })) => {}
+                    _ => panic!("Expected the function to return a variable: {function}"),
+                }
+            }
+            assert!(functions[1].1.to_string().contains("(c ? $var$"), "{}", functions[1].1);
+            assert!(!functions[2].1.to_string().contains("(c ? "), "{}", functions[2].1);
+        })
+    }
+
+    /// Runs the passes up to and including flattening, with the given return folding flags.
+    fn flatten(program: &str, return_folding_enabled: bool, finalize_folding_enabled: bool) -> (Program, Handler) {
+        flatten_with(program, |flattener| {
+            flattener.with_return_folding(return_folding_enabled).with_finalize_folding(finalize_folding_enabled)
+        })
+    }
+
+    /// Runs the passes up to and including flattening, with the flattener configured by `configure`.
+    fn flatten_with(program: &str, configure: impl FnOnce(Flattener) -> Flattener) -> (Program, Handler) {
+        let compiler = TestCompiler::default();
+        let (ast, symbol_table, ..) = compiler.type_check(program);
+        let ast = compiler.static_single_assignment(ast, &symbol_table);
+        let program = configure(compiler.flattener(&symbol_table)).reconstruct_program(ast.into_repr());
+        (program, compiler.handler)
+    }
+
+    #[test]
+    fn test_preserve_conditionals() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(flag: bool, a: u8, b: u8) -> u8 {
+        if flag {
+            assert_eq(a, b);
+            return a;
+        } else {
+            return b;
+        }
+    }
+}";
+            let (program, handler) = flatten_with(program, |flattener| flattener.with_preserved_conditionals(true));
+            assert_eq!(handler.err_count(), 0);
+            let function = &program.program_scopes.values().next().unwrap().functions[0].1;
+            let statements = &function.block.statements;
+
+            // The conditional statement survives, and the assertion inside it is not guarded by its condition.
+            let conditional = statements
+                .iter()
+                .find_map(|statement| match statement {
+                    Statement::Conditional(conditional) => Some(conditional),
+                    _ => None,
+                })
+                .expect("Expected the conditional statement to be preserved.");
+            assert!(conditional.otherwise.is_some());
+            assert!(conditional.then.statements.iter().any(|statement| matches!(
+                statement,
+                Statement::Assert(AssertStatement { variant: AssertVariant::AssertEq(..), .. })
+            )));
+
+            // The returns are still consolidated into a single return at the end of the block.
+            let returns = |statements: &[Statement]| {
+                statements.iter().filter(|statement| matches!(statement, Statement::Return(..))).count()
+            };
+            assert_eq!(returns(statements), 1);
+            assert!(matches!(statements.last(), Some(Statement::Return(..))));
+            assert_eq!(returns(&conditional.then.statements), 0);
+        })
+    }
+
+    #[test]
+    fn test_unreachable_assertions() {
+        create_session_if_not_set_then(|_| {
+            let source = "
+program test.aleo {
+    transition main(c: bool, a: u8, b: u8) -> u8 {
+        if c {
+            return a;
+        } else if c {
+            return b;
+        }
+        return a + b;
+    }
+}";
+            // Returns the assertions in the body of the flattened function and the index of the first ternary assignment.
+            let collect_assertions = |program: Program| {
+                let function = &program.program_scopes.values().next().unwrap().functions[0].1;
+                let statements = &function.block.statements;
+                let first_ternary = statements.iter().position(|statement| match statement {
+                    Statement::Assign(assign) => matches!(assign.value, Expression::Ternary(_)),
+                    _ => false,
+                });
+                let assertions = statements
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, statement)| match statement {
+                        Statement::Assert(AssertStatement { variant: AssertVariant::Assert(expression), .. }) => {
+                            Some((i, expression.clone()))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                (assertions, first_ternary.unwrap())
+            };
+
+            // Without the option, the folded returns are not asserted.
+            let (program, handler) = flatten(source, true, true);
+            assert_eq!(handler.err_count(), 0);
+            assert!(collect_assertions(program).0.is_empty());
+
+            let (program, handler) = flatten_with(source, |flattener| flattener.with_unreachable_assertions(true));
+            assert_eq!(handler.err_count(), 0);
+            let (assertions, first_ternary) = collect_assertions(program);
+
+            // Only the guard of `return b`, i.e. `!c && c`, is contradictory.
+            assert_eq!(assertions.len(), 1);
+            let (position, expression) = &assertions[0];
+            let guard = match expression {
+                Expression::Unary(unary) => &unary.receiver,
+                expression => panic!("Expected a negated guard, found `{expression}`."),
+            };
+            assert!(matches!(&**guard, Expression::Binary(binary) if binary.op == BinaryOperation::And));
+            assert!(Flattener::is_contradictory(guard));
+
+            // The assertion precedes the ternary expressions that fold the returns.
+            assert!(*position < first_ternary);
+        })
+    }
+
+    #[test]
+    fn test_disable_return_folding() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    mapping values: u8 => u8;
+
+    transition main(a: u8, b: u8) -> u8 {
+        return a + b then finalize(a, b);
+    }
+
+    finalize main(a: u8, b: u8) {
+        Mapping::set(values, a, b);
+        return;
+    }
+}";
+            // Returns whether the returns at the end of the function body and the finalize block span the entire block.
+            let spans_block = |return_folding_enabled: bool, finalize_folding_enabled: bool| {
+                let (program, handler) = flatten(program, return_folding_enabled, finalize_folding_enabled);
+                assert_eq!(handler.err_count(), 0);
+                let function = &program.program_scopes.values().next().unwrap().functions[0].1;
+                let spans_block = |block: &Block| match block.statements.last() {
+                    Some(Statement::Return(return_)) => return_.span == block.span,
+                    _ => panic!("Expected the block to end in a return statement."),
+                };
+                (spans_block(&function.block), spans_block(&function.finalize.as_ref().unwrap().block))
+            };
+
+            // Folded returns span the block, while returns that are left in place keep their original span.
+            assert_eq!(spans_block(true, true), (true, true));
+            assert_eq!(spans_block(false, true), (false, true));
+            assert_eq!(spans_block(true, false), (true, false));
+        })
+    }
+
+    #[test]
+    fn test_folded_return_span() {
+        create_session_if_not_set_then(|s| {
+            let compiler = TestCompiler::default();
+
+            let program = "
+program test.aleo {
+    transition main(flag: bool, a: u8) -> u8 {
+        if (flag) {
+            return a;
+        }
+        return a + a;
+    }
+}";
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+            let ast = compiler.static_single_assignment(ast, &symbol_table);
+            let program = compiler.flattener(&symbol_table).reconstruct_program(ast.into_repr());
+
+            // The folded return spans the body of the function in the source.
+            let function = &program.program_scopes.values().next().unwrap().functions[0].1;
+            let span = match function.block.statements.last() {
+                Some(Statement::Return(return_)) => return_.span,
+                _ => panic!("Expected the function to end in a return statement."),
+            };
+            assert_ne!(span, Span::default());
+            let contents = s.source_map.contents_of_span(span).unwrap();
+            assert!(contents.starts_with('{') && contents.ends_with('}'), "{contents}");
+            assert!(contents.contains("return a + a;"), "{contents}");
+        })
+    }
+
+    #[test]
+    fn test_conditional_return_without_return_folding() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(flag: bool, a: u8) -> u8 {
+        if (flag) {
+            return a;
+        }
+        return a + a;
+    }
+}";
+            // Conditional returns can only be expressed by folding them.
+            let (_, handler) = flatten(program, false, true);
+            assert_eq!(handler.err_count(), 1);
+            let (_, handler) = flatten(program, true, false);
+            assert_eq!(handler.err_count(), 0);
+        })
+    }
+
+    #[test]
+    fn test_conditional_finalize() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    mapping counts: u8 => u8;
+
+    transition main(flag: bool, a: u8) {
+        if (flag) {
+            return;
+        }
+        return then finalize(a);
+    }
+
+    finalize main(a: u8) {
+        assert_neq(a, 0u8);
+        Mapping::set(counts, a, a);
+    }
+}";
+            let (program, handler) = flatten(program, true, true);
+            // The mapping update is skipped along with the rest of the finalize block, so it is not rejected.
+            assert_eq!(handler.err_count(), 0);
+            let function = &program.program_scopes.values().next().unwrap().functions[0].1;
+
+            // Returns the value assigned to the given variable in the block.
+            let assigned_value = |block: &Block, place: &Expression| {
+                block
+                    .statements
+                    .iter()
+                    .find_map(|statement| match statement {
+                        Statement::Assign(assign) if assign.place == *place => Some(assign.value.to_string()),
+                        _ => None,
+                    })
+                    .unwrap()
+            };
+
+            // The function always finalizes, passing whether it would have finalized as the final argument.
+            let arguments = match function.block.statements.last() {
+                Some(Statement::Return(ReturnStatement { finalize_arguments: Some(arguments), .. })) => arguments,
+                _ => panic!("Expected the function to end in a return that finalizes."),
+            };
+            assert_eq!(arguments.len(), 2);
+            assert_eq!(arguments[0].to_string(), "a");
+            assert_eq!(assigned_value(&function.block, &arguments[1]), "(flag ? false : true)");
+
+            // The finalize block receives the guard as its final input, which changes its interface.
+            let finalize = function.finalize.as_ref().unwrap();
+            assert_eq!(finalize.input.len(), 2);
+            assert_eq!(finalize.input[1].type_(), Type::Boolean);
+
+            // The body of the finalize block is executed only if the guard holds, so its statements are unchanged.
+            match finalize.block.statements.as_slice() {
+                [Statement::Conditional(conditional)] => {
+                    assert_eq!(conditional.condition.to_string(), finalize.input[1].identifier().to_string());
+                    assert!(conditional.otherwise.is_none());
+                    assert_eq!(conditional.then.statements.len(), 2);
+                    assert!(matches!(conditional.then.statements[0], Statement::Assert(_)));
+                    assert!(matches!(conditional.then.statements[1], Statement::Expression(_)));
+                }
+                _ => panic!("Expected the finalize block to consist of a conditional statement on the guard."),
+            }
+
+            // A function that finalizes on every path is unchanged.
+            let (program, _) = flatten(
+                "
+program test.aleo {
+    transition main(a: u8) {
+        return then finalize(a);
+    }
+
+    finalize main(a: u8) {
+        assert_neq(a, 0u8);
+    }
+}",
+                true,
+                true,
+            );
+            let function = &program.program_scopes.values().next().unwrap().functions[0].1;
+            assert_eq!(function.finalize.as_ref().unwrap().input.len(), 1);
+        })
+    }
+
+    #[test]
+    fn test_mapping_update_rejection() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    mapping counts: u8 => u8;
+
+    transition main(flag: bool, a: u8) {
+        return then finalize(flag, a);
+    }
+
+    finalize main(flag: bool, a: u8) {
+        if (flag) {
+            let count: u8 = Mapping::get_or_use(counts, a, 0u8);
+            Mapping::set(counts, a, count + 1u8);
+        } else {
+            Mapping::remove(counts, a);
+        }
+    }
+}";
+            // Without mapping update rejection, the mapping is updated on every execution path.
+            let (_, handler) = flatten_with(program, |flattener| flattener.with_mapping_update_rejection(false));
+            assert_eq!(handler.err_count(), 0);
+
+            // With mapping update rejection, both updates are rejected, since Aleo instructions cannot update a mapping conditionally.
+            // Note that writing back the current value if `flag` is false is not sufficient,
+            // since `a` would still be inserted into the mapping if it is not present.
+            let (_, handler) = flatten_with(program, |flattener| flattener.with_mapping_update_rejection(true));
+            assert_eq!(handler.err_count(), 2);
+        })
+    }
+
+    #[test]
+    fn test_named_returns() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+
+            let program = "
+program test.aleo {
+    mapping values: u8 => u8;
+
+    transition main(flag: bool, a: u8, b: u8) -> u8 {
+        if (flag) {
+            return a then finalize(a);
+        }
+        return b then finalize(b);
+    }
+
+    finalize main(a: u8) {
+        Mapping::set(values, a, a);
+    }
+}";
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+            let ast = compiler.static_single_assignment(ast, &symbol_table);
+
+            // Returns the names of the variables assigned in the function body.
+            let assigned_names = |named_returns_enabled: bool| {
+                let program = compiler
+                    .flattener(&symbol_table)
+                    .with_named_returns(named_returns_enabled)
+                    .reconstruct_program(ast.as_repr().clone());
+                let function = &program.program_scopes.values().next().unwrap().functions[0].1;
+                function
+                    .block
+                    .statements
+                    .iter()
+                    .filter_map(|statement| match statement {
+                        Statement::Assign(assign) => Some(assign.place.to_string()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            // By default, the folded return and finalize argument use opaque prefixes.
+            let names = assigned_names(false);
+            assert!(names.iter().any(|name| name.starts_with("$ret$")), "{names:?}");
+            assert!(names.iter().any(|name| name.starts_with("finalize$0$")), "{names:?}");
+
+            // Otherwise, they are named after the function.
+            let names = assigned_names(true);
+            assert!(names.iter().any(|name| name.starts_with("main$out$")), "{names:?}");
+            assert!(names.iter().any(|name| name.starts_with("main$finalize$0$")), "{names:?}");
+            assert!(!names.iter().any(|name| name.starts_with("$ret") || name.starts_with("finalize$")));
+            assert_eq!(compiler.handler.err_count(), 0);
+        })
+    }
+
+    #[test]
+    fn test_finalize_arguments() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+            let TestCompiler { handler, buf, node_builder, type_table, .. } = &compiler;
+
+            let program = "
+program test.aleo {
+    mapping values: u8 => u8;
+
+    transition main(a: u8, b: u8) -> u8 {
+        return a + b then finalize(a, b);
+    }
+
+    finalize main(a: u8, b: u8) {
+        Mapping::set(values, a, b);
+    }
+}";
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+            let ast = compiler.static_single_assignment(ast, &symbol_table);
+
+            // The arguments of a well-typed program match the inputs of the finalize block.
+            let mut flattener = compiler.flattener(&symbol_table);
+            let program = flattener.reconstruct_program(ast.into_repr());
+            assert_eq!(handler.err_count(), 0);
+
+            // Returns the function with the finalize arguments of its final return replaced by `update`.
+            let function = program.program_scopes.values().next().unwrap().functions[0].1.clone();
+            let with_arguments = |update: &dyn Fn(&mut Vec<Expression>)| {
+                let mut function = function.clone();
+                match function.block.statements.last_mut() {
+                    Some(Statement::Return(ReturnStatement { finalize_arguments: Some(arguments), .. })) => {
+                        update(arguments)
+                    }
+                    _ => panic!("Expected the function to end in a return with finalize arguments."),
+                }
+                function
+            };
+
+            // Too few arguments are reported.
+            flattener.check_finalize_arguments(&with_arguments(&|arguments| {
+                arguments.pop();
+            }));
+            assert_eq!(handler.err_count(), 1);
+            let error = buf.extract_errs().last_entry().unwrap().to_string();
+            assert!(error.contains("The finalize block of `main` expects 2 argument(s), but 1 were passed to it."));
+
+            // An argument of the wrong type is reported.
+            flattener.check_finalize_arguments(&with_arguments(&|arguments| {
+                let identifier = Identifier::new(Symbol::intern("flag"), node_builder.next_id());
+                type_table.insert(identifier.id, Type::Boolean);
+                arguments[1] = Expression::Identifier(identifier);
+            }));
+            assert_eq!(handler.err_count(), 2);
+            let error = buf.extract_errs().last_entry().unwrap().to_string();
+            assert!(error.contains("Argument 2 to the finalize block of `main` has type `boolean`"), "{error}");
+            assert!(error.contains("but the input has type `u8`."), "{error}");
+        })
+    }
+}
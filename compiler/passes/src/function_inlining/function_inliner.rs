@@ -16,9 +16,11 @@
 
 use crate::{Assigner, AssignmentRenamer, CallGraph, TypeTable};
 
-use leo_ast::{Function, NodeBuilder};
+use leo_ast::{Function, NodeBuilder, Variant};
 use leo_span::Symbol;
 
+use indexmap::IndexSet;
+
 pub struct FunctionInliner<'a> {
     /// A counter used to create unique NodeIDs.
     pub(crate) node_builder: &'a NodeBuilder,
@@ -30,6 +32,11 @@ pub struct FunctionInliner<'a> {
     pub(crate) type_table: &'a TypeTable,
     /// A map of reconstructed functions in the current program scope.
     pub(crate) reconstructed_functions: Vec<(Symbol, Function)>,
+    /// The maximum number of statements in a standard function that is inlined at its call sites.
+    /// If zero, only `inline` functions are inlined.
+    pub(crate) trivial_inlining_threshold: usize,
+    /// The standard functions in the current program scope that are inlined at their call sites.
+    pub(crate) trivial_functions: IndexSet<Symbol>,
 }
 
 impl<'a> FunctionInliner<'a> {
@@ -46,6 +53,24 @@ impl<'a> FunctionInliner<'a> {
             assignment_renamer: AssignmentRenamer::new(assigner),
             reconstructed_functions: Default::default(),
             type_table,
+            trivial_inlining_threshold: 0,
+            trivial_functions: IndexSet::new(),
         }
     }
+
+    /// Sets the maximum number of statements in a standard function that is inlined at its call sites.
+    /// Such functions are removed from the program, since they are no longer called. If zero, only `inline` functions are inlined.
+    pub fn with_trivial_inlining_threshold(mut self, threshold: usize) -> Self {
+        self.trivial_inlining_threshold = threshold;
+        self
+    }
+
+    /// Returns `true` if `function` is a standard function that is small enough to be inlined at its call sites.
+    /// Functions with a finalize block and recursive functions are never inlined.
+    pub(crate) fn is_trivial(&self, function: &Function) -> bool {
+        function.variant == Variant::Standard
+            && function.finalize.is_none()
+            && function.block.statements.len() <= self.trivial_inlining_threshold
+            && !self.call_graph.has_path(function.identifier.name, function.identifier.name)
+    }
 }
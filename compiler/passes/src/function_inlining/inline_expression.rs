@@ -52,9 +52,15 @@ impl ExpressionReconstructor for FunctionInliner<'_> {
         let (_, callee) = self.reconstructed_functions.iter().find(|(symbol, _)| *symbol == function_name).unwrap();
 
         // Inline the callee function, if required, otherwise, return the call expression.
-        match callee.variant {
-            Variant::Transition | Variant::Standard => (Expression::Call(input), Default::default()),
-            Variant::Inline => {
+        // Note that standard functions are inlined if they are trivial.
+        let is_inlined = match callee.variant {
+            Variant::Inline => true,
+            Variant::Standard => self.trivial_functions.contains(&function_name),
+            Variant::Transition => false,
+        };
+        match is_inlined {
+            false => (Expression::Call(input), Default::default()),
+            true => {
                 // Construct a mapping from input variables of the callee function to arguments passed to the callee.
                 let parameter_to_argument = callee
                     .input
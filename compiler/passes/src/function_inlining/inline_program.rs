@@ -38,6 +38,10 @@ impl ProgramReconstructor for FunctionInliner<'_> {
             if let Some(function) = function_map.remove(function_name) {
                 // Reconstruct the function.
                 let reconstructed_function = self.reconstruct_function(function);
+                // If the function is trivial, it is inlined at its call sites, which are reconstructed later in the post-order.
+                if self.is_trivial(&reconstructed_function) {
+                    self.trivial_functions.insert(*function_name);
+                }
                 // Add the reconstructed function to the mapping.
                 self.reconstructed_functions.push((*function_name, reconstructed_function));
             }
@@ -45,8 +49,13 @@ impl ProgramReconstructor for FunctionInliner<'_> {
         // This is a sanity check to ensure that functions in the program scope have been processed.
         assert!(function_map.is_empty(), "All functions in the program scope should have been processed.");
 
-        // Note that this intentionally clears `self.reconstructed_functions` for the next program scope.
-        let functions = core::mem::take(&mut self.reconstructed_functions).into_iter().collect();
+        // Note that this intentionally clears `self.reconstructed_functions` and `self.trivial_functions` for the next program scope.
+        // Trivial functions are removed, since all of their call sites have been inlined.
+        let trivial_functions = core::mem::take(&mut self.trivial_functions);
+        let functions = core::mem::take(&mut self.reconstructed_functions)
+            .into_iter()
+            .filter(|(name, _)| !trivial_functions.contains(name))
+            .collect();
 
         ProgramScope {
             program_id: input.program_id,
@@ -69,9 +69,18 @@ impl StatementReconstructor for FunctionInliner<'_> {
         (Block { span: block.span, statements, id: block.id }, Default::default())
     }
 
-    /// Flattening removes conditional statements from the program.
-    fn reconstruct_conditional(&mut self, _: ConditionalStatement) -> (Statement, Self::AdditionalOutput) {
-        unreachable!("`ConditionalStatement`s should not be in the AST at this phase of compilation.")
+    /// Flattening only leaves the conditional statement that guards the body of a conditionally executed finalize block.
+    fn reconstruct_conditional(&mut self, input: ConditionalStatement) -> (Statement, Self::AdditionalOutput) {
+        (
+            Statement::Conditional(ConditionalStatement {
+                condition: input.condition,
+                then: self.reconstruct_block(input.then).0,
+                otherwise: input.otherwise.map(|statement| Box::new(self.reconstruct_statement(*statement).0)),
+                span: input.span,
+                id: input.id,
+            }),
+            Default::default(),
+        )
     }
 
     /// Parsing guarantees that console statements are not present in the program.
@@ -51,6 +51,9 @@
 //!     return value$3;
 //! }
 //! ```
+//!
+//! Optionally, standard functions whose bodies contain at most a given number of statements are also inlined,
+//! unless they are recursive, and are removed from the program scope.
 
 pub mod assignment_renamer;
 pub use assignment_renamer::*;
@@ -70,13 +73,162 @@ use leo_ast::{Ast, NodeBuilder, ProgramReconstructor};
 use leo_errors::Result;
 
 impl<'a> Pass for FunctionInliner<'a> {
-    type Input = (Ast, &'a NodeBuilder, &'a CallGraph, &'a Assigner, &'a TypeTable);
+    type Input = (Ast, &'a NodeBuilder, &'a CallGraph, &'a Assigner, &'a TypeTable, usize);
     type Output = Result<Ast>;
 
-    fn do_pass((ast, node_builder, call_graph, assigner, tt): Self::Input) -> Self::Output {
-        let mut reconstructor = FunctionInliner::new(node_builder, call_graph, assigner, tt);
+    fn do_pass((ast, node_builder, call_graph, assigner, tt, trivial_inlining_threshold): Self::Input) -> Self::Output {
+        let mut reconstructor = FunctionInliner::new(node_builder, call_graph, assigner, tt)
+            .with_trivial_inlining_threshold(trivial_inlining_threshold);
         let program = reconstructor.reconstruct_program(ast.into_repr());
 
         Ok(Ast::new(program))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::TestCompiler;
+
+    use leo_ast::{Program, Statement};
+    use leo_span::symbol::create_session_if_not_set_then;
+
+    use indexmap::IndexSet;
+
+    const PROGRAM: &str = "
+program test.aleo {
+    function double(x: u32) -> u32 {
+        return x * 2u32;
+    }
+
+    function large(x: u32) -> u32 {
+        let a: u32 = x + 1u32;
+        let b: u32 = a * a;
+        let c: u32 = b - x;
+        return c / 2u32;
+    }
+
+    transition main(x: u32) -> u32 {
+        return double(x) + large(x);
+    }
+}";
+
+    /// Runs the passes up to and including inlining, and returns the names and bodies of the remaining functions.
+    fn inline(trivial_inlining_threshold: usize) -> Vec<(String, String)> {
+        inline_program(PROGRAM, trivial_inlining_threshold)
+            .program_scopes
+            .values()
+            .next()
+            .unwrap()
+            .functions
+            .iter()
+            .map(|(name, function)| (name.to_string(), function.to_string()))
+            .collect()
+    }
+
+    /// Runs the passes up to and including inlining on the given program.
+    fn inline_program(program: &str, trivial_inlining_threshold: usize) -> Program {
+        let compiler = TestCompiler::default();
+        let (ast, symbol_table, _, call_graph) = compiler.type_check(program);
+        let ast = compiler.static_single_assignment(ast, &symbol_table);
+        let ast = compiler.flatten(ast, &symbol_table);
+        let ast = FunctionInliner::do_pass((
+            ast,
+            &compiler.node_builder,
+            &call_graph,
+            &compiler.assigner,
+            &compiler.type_table,
+            trivial_inlining_threshold,
+        ))
+        .unwrap();
+        ast.into_repr()
+    }
+
+    #[test]
+    fn test_trivial_inlining() {
+        create_session_if_not_set_then(|_| {
+            // By default, standard functions are not inlined.
+            let functions = inline(0);
+            let names = functions.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+            assert_eq!(names, ["double", "large", "main"]);
+            assert!(functions[2].1.contains("double(x)"));
+
+            // The trivial helper is inlined and removed, while the large one is called.
+            let functions = inline(2);
+            let names = functions.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+            assert_eq!(names, ["large", "main"]);
+            let main = &functions[1].1;
+            assert!(!main.contains("double("), "{main}");
+            assert!(main.contains("x * 2u32"), "{main}");
+            assert!(main.contains("large(x)"), "{main}");
+        })
+    }
+
+    #[test]
+    fn test_trivial_inlining_arguments() {
+        create_session_if_not_set_then(|_| {
+            let program = inline_program(
+                "
+program test.aleo {
+    function scale(x: u32, y: u32) -> u32 {
+        let z: u32 = x * y;
+        return z + x;
+    }
+
+    transition main(a: u32, b: u32) -> u32 {
+        let z: u32 = scale(a, b);
+        return scale(z, a) + z;
+    }
+}",
+                4,
+            );
+            let functions = &program.program_scopes.values().next().unwrap().functions;
+            assert_eq!(functions.len(), 1);
+            let main = &functions[0].1;
+            let body = main.to_string();
+            assert!(!body.contains("scale("), "{body}");
+
+            // Each inlined local is renamed, so that no variable is assigned more than once.
+            let assigned = main
+                .block
+                .statements
+                .iter()
+                .filter_map(|statement| match statement {
+                    Statement::Assign(assign) => Some(assign.place.to_string()),
+                    Statement::Definition(definition) => Some(definition.place.to_string()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(assigned.len(), assigned.iter().collect::<IndexSet<_>>().len(), "{body}");
+
+            // The parameters are replaced by the arguments of each call.
+            assert!(body.contains("a * b"), "{body}");
+            assert!(!body.contains('x') && !body.contains('y'), "{body}");
+        })
+    }
+
+    #[test]
+    fn test_recursive_functions_are_not_trivial() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+
+            // Type checking rejects recursive programs, so the cycle is added to the call graph directly.
+            let ast = compiler.parse(PROGRAM);
+            let (_, double) = &ast.as_repr().program_scopes.values().next().unwrap().functions[0];
+            let name = double.identifier.name;
+
+            let call_graph = CallGraph::new(IndexSet::from([name]));
+            let inliner =
+                FunctionInliner::new(&compiler.node_builder, &call_graph, &compiler.assigner, &compiler.type_table)
+                    .with_trivial_inlining_threshold(2);
+            assert!(inliner.is_trivial(double));
+
+            let mut call_graph = CallGraph::new(IndexSet::from([name]));
+            call_graph.add_edge(name, name);
+            let inliner =
+                FunctionInliner::new(&compiler.node_builder, &call_graph, &compiler.assigner, &compiler.type_table)
+                    .with_trivial_inlining_threshold(2);
+            assert!(!inliner.is_trivial(double));
+        })
+    }
+}
@@ -23,6 +23,9 @@ pub use code_generation::*;
 pub mod common;
 pub use common::*;
 
+pub mod common_subexpression_elimination;
+pub use common_subexpression_elimination::*;
+
 pub mod dead_code_elimination;
 pub use dead_code_elimination::*;
 
@@ -41,11 +44,23 @@ pub use self::loop_unrolling::*;
 pub mod pass;
 pub use self::pass::*;
 
+pub mod single_assignment_verification;
+pub use single_assignment_verification::*;
+
 pub mod static_single_assignment;
 pub use static_single_assignment::*;
 
 pub mod symbol_table_creation;
 pub use symbol_table_creation::*;
 
+pub mod temporary_elimination;
+pub use temporary_elimination::*;
+
+#[cfg(test)]
+pub(crate) mod test_helpers;
+
 pub mod type_checking;
 pub use type_checking::*;
+
+pub mod unused_input_detection;
+pub use unused_input_detection::*;
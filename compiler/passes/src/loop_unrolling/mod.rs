@@ -35,14 +35,67 @@ use leo_ast::{Ast, NodeBuilder, ProgramReconstructor};
 use leo_errors::{emitter::Handler, Result};
 
 impl<'a> Pass for Unroller<'a> {
-    type Input = (Ast, &'a Handler, &'a NodeBuilder, SymbolTable, &'a TypeTable);
+    type Input = (Ast, &'a Handler, &'a NodeBuilder, SymbolTable, &'a TypeTable, usize);
     type Output = Result<(Ast, SymbolTable)>;
 
-    fn do_pass((ast, handler, node_builder, st, tt): Self::Input) -> Self::Output {
-        let mut reconstructor = Self::new(st, tt, handler, node_builder);
+    fn do_pass((ast, handler, node_builder, st, tt, max_iterations): Self::Input) -> Self::Output {
+        let mut reconstructor = Self::new(st, tt, handler, node_builder).with_max_iterations(max_iterations);
         let program = reconstructor.reconstruct_program(ast.into_repr());
         handler.last_err().map_err(|e| *e)?;
 
         Ok((Ast::new(program), reconstructor.symbol_table.take()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::TestCompiler;
+
+    use leo_span::symbol::create_session_if_not_set_then;
+
+    /// Unrolls the loop in a program that sums the numbers from zero to `bound`, with at most `max_iterations`.
+    fn unroll(bound: u32, max_iterations: usize) -> (Result<(Ast, SymbolTable)>, TestCompiler) {
+        let compiler = TestCompiler::default();
+        let program = format!(
+            "
+program test.aleo {{
+    transition main(a: u32) -> u32 {{
+        let sum: u32 = a;
+        for i: u32 in 0u32..{bound}u32 {{
+            sum += i;
+        }}
+        return sum;
+    }}
+}}"
+        );
+        let (ast, symbol_table, ..) = compiler.type_check(&program);
+        let TestCompiler { handler, node_builder, type_table, .. } = &compiler;
+        let result = Unroller::do_pass((ast, handler, node_builder, symbol_table, type_table, max_iterations));
+        (result, compiler)
+    }
+
+    #[test]
+    fn test_max_iterations() {
+        create_session_if_not_set_then(|_| {
+            // Loops with at most the maximum number of iterations are unrolled.
+            let (ast, _) = unroll(4, 4).0.unwrap();
+            let function = &ast.as_repr().program_scopes.values().next().unwrap().functions[0].1;
+            assert_eq!(function.block.to_string().matches("sum = sum + ").count(), 4, "{function}");
+
+            // If the maximum is zero, there is no maximum.
+            assert!(unroll(1000, 0).0.is_ok());
+
+            // By default, loops with more than `DEFAULT_MAX_LOOP_ITERATIONS` iterations are reported.
+            let bound = DEFAULT_MAX_LOOP_ITERATIONS as u32;
+            assert!(unroll(bound, DEFAULT_MAX_LOOP_ITERATIONS).0.is_ok());
+            assert!(unroll(bound + 1, DEFAULT_MAX_LOOP_ITERATIONS).0.is_err());
+
+            // Loops with more iterations are reported with their bounds.
+            let (result, compiler) = unroll(1000000, 1000);
+            assert!(result.is_err());
+            let error = compiler.errors().pop().unwrap();
+            assert!(error.contains("The loop from `0` to `1000000` has more than 1000 iterations"), "{error}");
+        })
+    }
+}
@@ -54,8 +54,14 @@ pub struct Unroller<'a> {
     pub(crate) node_builder: &'a NodeBuilder,
     /// Are we in the midst of unrolling a loop?
     pub(crate) is_unrolling: bool,
+    /// The maximum number of iterations of a loop that is unrolled. If zero, there is no maximum.
+    pub(crate) max_iterations: usize,
 }
 
+/// The default maximum number of iterations of a loop that is unrolled.
+/// Each iteration produces a copy of the loop body, so larger loops produce programs that are too large to be deployed.
+pub const DEFAULT_MAX_LOOP_ITERATIONS: usize = 1 << 16;
+
 impl<'a> Unroller<'a> {
     pub(crate) fn new(
         symbol_table: SymbolTable,
@@ -71,6 +77,41 @@ impl<'a> Unroller<'a> {
             handler,
             node_builder,
             is_unrolling: false,
+            max_iterations: DEFAULT_MAX_LOOP_ITERATIONS,
+        }
+    }
+
+    /// Sets the maximum number of iterations of a loop that is unrolled, which defaults to `DEFAULT_MAX_LOOP_ITERATIONS`.
+    /// If zero, there is no maximum.
+    /// Loops with more iterations are reported by the unroller before they are unrolled, since each iteration produces a copy of the loop body.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Checks that the loop from `start` to `stop` has at most the maximum number of iterations, if there is one.
+    /// Otherwise, emits an error and returns `false`.
+    pub(crate) fn check_iteration_limit<I: LoopBound>(&self, input: &IterationStatement, start: I, stop: I) -> bool {
+        if self.max_iterations == 0 {
+            return true;
+        }
+
+        let clusivity = match input.inclusive {
+            true => Clusivity::Inclusive,
+            false => Clusivity::Exclusive,
+        };
+        // Note that the iterations are counted lazily, since the number of iterations may not fit in `I`.
+        match RangeIterator::new(start, stop, clusivity).nth(self.max_iterations) {
+            None => true,
+            Some(_) => {
+                self.emit_err(LoopUnrollerError::loop_iterations_exceed_limit(
+                    start,
+                    stop,
+                    self.max_iterations,
+                    input.span,
+                ));
+                false
+            }
         }
     }
 
@@ -142,6 +183,11 @@ impl<'a> Unroller<'a> {
             Err(s) => return s,
         };
 
+        // Check that the loop does not have too many iterations to unroll.
+        if !self.check_iteration_limit(&input, start, stop) {
+            return Statement::dummy(input.span, self.node_builder.next_id());
+        }
+
         // Get the index of the current scope.
         let scope_index = self.current_scope_index();
 
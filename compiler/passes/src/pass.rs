@@ -14,6 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
 
+use leo_ast::Ast;
+use leo_errors::{emitter::Handler, Result};
+
 /// A compiler pass consuming `Self::Input` and returning `Self::Output`.
 pub trait Pass {
     type Input;
@@ -22,3 +25,133 @@ pub trait Pass {
     /// Runs the compiler pass.
     fn do_pass(input: Self::Input) -> Self::Output;
 }
+
+/// Runs a sequence of passes on an `Ast`, making the order of the passes explicit.
+/// The pipeline stops at the first pass that fails or emits an error to the handler, and the remaining passes are skipped.
+pub struct Pipeline<'a> {
+    /// The handler that the passes emit their errors to.
+    handler: &'a Handler,
+    /// The AST produced by the last pass, or the error that stopped the pipeline.
+    ast: Result<Ast>,
+}
+
+impl<'a> Pipeline<'a> {
+    /// Initializes a new `Pipeline` that runs on `ast`.
+    pub fn new(ast: Ast, handler: &'a Handler) -> Self {
+        Self { handler, ast: Ok(ast) }
+    }
+
+    /// Runs the pass `P`, unless a previous pass failed.
+    /// The input of the pass is constructed from the current AST by `input`,
+    /// and the next AST is extracted from the output of the pass by `output`.
+    pub fn run<P: Pass>(
+        mut self,
+        input: impl FnOnce(Ast) -> P::Input,
+        output: impl FnOnce(P::Output) -> Result<Ast>,
+    ) -> Self {
+        let handler = self.handler;
+        self.ast = self.ast.and_then(|ast| {
+            let ast = output(P::do_pass(input(ast)))?;
+            // Note that a pass may emit errors without failing.
+            handler.last_err().map_err(|e| *e)?;
+            Ok(ast)
+        });
+        self
+    }
+
+    /// Runs the pass `P`, which produces an AST, unless a previous pass failed.
+    pub fn then<P: Pass<Output = Result<Ast>>>(self, input: impl FnOnce(Ast) -> P::Input) -> Self {
+        self.run::<P>(input, |output| output)
+    }
+
+    /// Runs `f` on the current AST, unless a previous pass failed, e.g. to verify the AST or to write it to a file.
+    /// The pipeline stops if `f` fails.
+    pub fn inspect(mut self, f: impl FnOnce(&Ast) -> Result<()>) -> Self {
+        if let Ok(ast) = &self.ast {
+            if let Err(error) = f(ast) {
+                self.ast = Err(error);
+            }
+        }
+        self
+    }
+
+    /// Returns the AST produced by the last pass, or the error that stopped the pipeline.
+    pub fn finish(self) -> Result<Ast> {
+        self.ast
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_helpers::TestCompiler, Destructurer, Flattener, FlattenerOptions, StaticSingleAssigner};
+
+    use leo_span::symbol::create_session_if_not_set_then;
+
+    use std::cell::Cell;
+
+    const PROGRAM: &str = "
+program test.aleo {
+    transition main(flag: bool, a: u8) -> u8 {
+        if (flag) {
+            return a;
+        }
+        return a + a;
+    }
+}";
+
+    #[test]
+    fn test_pipeline() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+            let TestCompiler { handler, node_builder, type_table, assigner, .. } = &compiler;
+            let (ast, symbol_table, ..) = compiler.type_check(PROGRAM);
+
+            // Run SSA followed by flattening.
+            let ast = Pipeline::new(ast, handler)
+                .run::<StaticSingleAssigner>(
+                    |ast| (ast, handler, node_builder, assigner, &symbol_table, type_table, Default::default()),
+                    |output| output.map(|(ast, _)| ast),
+                )
+                .then::<Flattener>(|ast| {
+                    (ast, handler, &symbol_table, type_table, node_builder, assigner, Default::default())
+                })
+                .finish()
+                .unwrap();
+
+            // The returns are folded into a single return.
+            let function = ast.as_repr().program_scopes.values().next().unwrap().functions[0].1.to_string();
+            assert_eq!(function.matches("return").count(), 1, "{function}");
+            assert!(function.contains("$ret"), "{function}");
+        })
+    }
+
+    #[test]
+    fn test_pipeline_stops_at_first_error() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+            let TestCompiler { handler, node_builder, type_table, assigner, .. } = &compiler;
+            let (ast, symbol_table, ..) = compiler.type_check(PROGRAM);
+
+            // The conditional return cannot be flattened without return folding, so destructuring is skipped.
+            let options = FlattenerOptions { return_folding_enabled: false, ..Default::default() };
+            let destructured = Cell::new(false);
+            let result = Pipeline::new(ast, handler)
+                .run::<StaticSingleAssigner>(
+                    |ast| (ast, handler, node_builder, assigner, &symbol_table, type_table, Default::default()),
+                    |output| output.map(|(ast, _)| ast),
+                )
+                .then::<Flattener>(|ast| (ast, handler, &symbol_table, type_table, node_builder, assigner, options))
+                .then::<Destructurer>(|ast| {
+                    destructured.set(true);
+                    (ast, type_table, node_builder, assigner)
+                })
+                .finish();
+            assert!(result.is_err());
+            assert!(!destructured.get());
+            let errors = compiler.errors();
+            assert_eq!(errors.len(), 1);
+            assert!(errors[0].contains("Cannot return from within a conditional statement"), "{errors:?}");
+        })
+    }
+}
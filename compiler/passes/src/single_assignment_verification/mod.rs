@@ -0,0 +1,107 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! The Single Assignment Verification pass checks that the output of the Static Single Assignment pass
+//! assigns each variable at most once within a function body.
+//! Since this invariant is guaranteed by construction, a violation indicates a bug in the compiler.
+//! The pass is intended for debugging and is only run if enabled in the build options.
+//!
+//! Consider the following Leo code, which does not satisfy the invariant.
+//! ```leo
+//! function main(a: u8, b: u8) -> u8 {
+//!     $var$0 = a + b;
+//!     $var$0 = a * b;
+//!     return $var$0;
+//! }
+//! ```
+//!
+//! The single assignment verification pass reports an error for the second assignment to `$var$0`.
+
+pub mod verifier;
+pub use verifier::*;
+
+use crate::Pass;
+
+use leo_ast::{Ast, ProgramVisitor};
+use leo_errors::{emitter::Handler, Result};
+
+impl<'a> Pass for SingleAssignmentVerifier<'a> {
+    type Input = (&'a Ast, &'a Handler);
+    type Output = Result<()>;
+
+    fn do_pass((ast, handler): Self::Input) -> Self::Output {
+        let mut visitor = SingleAssignmentVerifier::new(handler);
+        visitor.visit_program(ast.as_repr());
+        handler.last_err().map_err(|e| *e)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::TestCompiler;
+
+    use leo_ast::Statement;
+    use leo_span::symbol::create_session_if_not_set_then;
+
+    const PROGRAM: &str = "
+program test.aleo {
+    transition main(flag: bool, a: u8, b: u8) -> u8 {
+        let c: u8 = a + b;
+        if (flag) {
+            c = c * b;
+        }
+        return c;
+    }
+}";
+
+    /// Parses `PROGRAM` and applies the static single assignment pass.
+    fn static_single_assignment(compiler: &TestCompiler) -> Ast {
+        let (ast, symbol_table, ..) = compiler.type_check(PROGRAM);
+        compiler.static_single_assignment(ast, &symbol_table)
+    }
+
+    #[test]
+    fn test_verify_single_assignment() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+            let ast = static_single_assignment(&compiler);
+
+            assert!(SingleAssignmentVerifier::do_pass((&ast, &compiler.handler)).is_ok());
+        })
+    }
+
+    #[test]
+    fn test_verify_double_assignment() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+            let TestCompiler { handler, buf, .. } = &compiler;
+            let mut program = static_single_assignment(&compiler).into_repr();
+
+            // Duplicate the first assignment in the function body.
+            let statements = &mut program.program_scopes.values_mut().next().unwrap().functions[0].1.block.statements;
+            let assignment =
+                statements.iter().find(|statement| matches!(statement, Statement::Assign(_))).cloned().unwrap();
+            statements.insert(0, assignment);
+
+            assert!(SingleAssignmentVerifier::do_pass((&Ast::new(program), handler)).is_err());
+            assert_eq!(handler.err_count(), 1);
+            assert!(buf.extract_errs().last_entry().unwrap().to_string().contains("is assigned more than once"));
+        })
+    }
+}
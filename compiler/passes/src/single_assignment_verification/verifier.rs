@@ -0,0 +1,78 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+use leo_ast::{AssignStatement, Expression, ExpressionVisitor, Function, ProgramVisitor, StatementVisitor};
+use leo_errors::{emitter::Handler, CompilerError};
+use leo_span::{Span, Symbol};
+
+use indexmap::IndexMap;
+
+/// A compiler pass that checks that each variable is assigned at most once in a function body.
+pub struct SingleAssignmentVerifier<'a> {
+    /// The error handler.
+    handler: &'a Handler,
+    /// A mapping from the variables assigned in the current function body to the spans of their assignments.
+    assignments: IndexMap<Symbol, Span>,
+}
+
+impl<'a> SingleAssignmentVerifier<'a> {
+    /// Initializes a new `SingleAssignmentVerifier`.
+    pub fn new(handler: &'a Handler) -> Self {
+        Self { handler, assignments: Default::default() }
+    }
+
+    /// Records an assignment to `name`, emitting an error if `name` has already been assigned.
+    fn record_assignment(&mut self, name: Symbol, span: Span) {
+        if let Some(previous_span) = self.assignments.insert(name, span) {
+            self.handler.emit_err(CompilerError::variable_assigned_more_than_once_in_ssa(name, previous_span, span));
+        }
+    }
+}
+
+impl<'a> ExpressionVisitor<'a> for SingleAssignmentVerifier<'a> {
+    type AdditionalInput = ();
+    type Output = ();
+}
+
+impl<'a> StatementVisitor<'a> for SingleAssignmentVerifier<'a> {
+    fn visit_assign(&mut self, input: &'a AssignStatement) {
+        match &input.place {
+            Expression::Identifier(identifier) => self.record_assignment(identifier.name, input.span),
+            Expression::Tuple(tuple) => tuple.elements.iter().for_each(|element| match element {
+                Expression::Identifier(identifier) => self.record_assignment(identifier.name, input.span),
+                _ => unreachable!(
+                    "The previous compiler passes guarantee the tuple elements on the lhs are identifiers."
+                ),
+            }),
+            _ => unreachable!(
+                "The previous compiler passes guarantee that `place` is either an identifier or tuple of identifiers."
+            ),
+        }
+    }
+}
+
+impl<'a> ProgramVisitor<'a> for SingleAssignmentVerifier<'a> {
+    fn visit_function(&mut self, input: &'a Function) {
+        // Note that the function body and its finalize block are verified independently.
+        self.assignments.clear();
+        self.visit_block(&input.block);
+
+        if let Some(finalize) = &input.finalize {
+            self.assignments.clear();
+            self.visit_block(&finalize.block);
+        }
+    }
+}
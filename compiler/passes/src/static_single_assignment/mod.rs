@@ -49,6 +49,13 @@
 //! }
 //! ```
 //! Note that the redundant assignments have no effect on the bytecode generated by the compiler.
+//!
+//! If constant propagation is enabled, uses of variables that are known to equal a literal are replaced with the literal.
+//! For example, `let a = 5u8; let b = a + c;` is rewritten into `$var$0 = 5u8; a$1 = $var$0; $var$2 = 5u8 + c; b$3 = $var$2;`.
+//! Ternary expressions whose branches are the same variable or literal, e.g. `flag ? a : a`, are also replaced with the branch.
+//!
+//! If assert lowering is enabled, assertions of the form `assert(a != b)` or `assert(!(a == b))` are rewritten into `assert_neq(a, b)`.
+//! This avoids computing an intermediate boolean, since `assert_neq` is lowered directly to `assert.neq`.
 
 mod rename_expression;
 
@@ -62,16 +69,1153 @@ pub use static_single_assigner::*;
 use crate::{Assigner, Pass, SymbolTable, TypeTable};
 
 use leo_ast::{Ast, NodeBuilder, ProgramConsumer};
-use leo_errors::Result;
+use leo_errors::{emitter::Handler, Result};
+use leo_span::Symbol;
+
+use indexmap::IndexMap;
 
 impl<'a> Pass for StaticSingleAssigner<'a> {
-    type Input = (Ast, &'a NodeBuilder, &'a Assigner, &'a SymbolTable, &'a TypeTable);
-    type Output = Result<Ast>;
+    type Input =
+        (Ast, &'a Handler, &'a NodeBuilder, &'a Assigner, &'a SymbolTable, &'a TypeTable, StaticSingleAssignerOptions);
+    type Output = Result<(Ast, IndexMap<Symbol, Symbol>)>;
 
-    fn do_pass((ast, node_builder, assigner, symbol_table, type_table): Self::Input) -> Self::Output {
-        let mut consumer = StaticSingleAssigner::new(node_builder, symbol_table, type_table, assigner);
+    fn do_pass((ast, handler, node_builder, assigner, symbol_table, type_table, options): Self::Input) -> Self::Output {
+        // Calls to imported transitions are qualified with the programs they are imported from.
+        let call_targets = StaticSingleAssigner::import_call_targets(ast.as_repr());
+        let mut consumer =
+            StaticSingleAssigner::new(handler, node_builder, symbol_table, type_table, assigner, options)
+                .with_call_targets(call_targets);
         let program = consumer.consume_program(ast.into_repr());
+        handler.last_err().map_err(|e| *e)?;
+
+        Ok((Ast::new(program), consumer.circuits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::TestCompiler;
+
+    use indexmap::IndexMap;
+    use leo_ast::{
+        AccessExpression,
+        AssignStatement,
+        Block,
+        ConditionalStatement,
+        DeclarationType,
+        DefinitionStatement,
+        ErrExpression,
+        Expression,
+        ExpressionConsumer,
+        Function,
+        Identifier,
+        IntegerType,
+        Literal,
+        Node,
+        Statement,
+        StatementConsumer,
+        TernaryExpression,
+        TupleAccess,
+        TupleExpression,
+        TupleType,
+        Type,
+    };
+    use leo_span::{symbol::create_session_if_not_set_then, Symbol};
+
+    const PROGRAM: &str = "
+program test.aleo {
+    struct Point {
+        x: u8,
+        y: u8,
+    }
+
+    function helper(a: u8) -> u8 {
+        return a + 1u8;
+    }
+
+    transition main(flag: bool, a: u8, b: u8) -> Point {
+        let c: u8 = helper(a);
+        if (flag) {
+            c = c + b;
+        }
+        return Point { y: b, x: c };
+    }
+}";
+
+    #[test]
+    fn test_reconstruct_single_function() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+            let (ast, symbol_table, ..) = compiler.type_check(PROGRAM);
+            let functions = ast.as_repr().program_scopes.values().next().unwrap().functions.clone();
+
+            // Apply SSA to the whole program.
+            let program = compiler.static_single_assignment(ast, &symbol_table).into_repr();
+            let expected = &program.program_scopes.values().next().unwrap().functions;
+
+            // Apply SSA to each function individually, with a fresh assigner, so that the same names are produced.
+            let assigner = Assigner::default();
+            let mut consumer = StaticSingleAssigner::new(
+                &compiler.handler,
+                &compiler.node_builder,
+                &symbol_table,
+                &compiler.type_table,
+                &assigner,
+                Default::default(),
+            );
+            for ((name, function), (expected_name, expected_function)) in functions.into_iter().zip(expected.iter()) {
+                assert_eq!(name, *expected_name);
+                let function = consumer.reconstruct_single_function(function);
+                assert_eq!(function.to_string(), expected_function.to_string());
+            }
+        })
+    }
+
+    #[test]
+    fn test_ast_json_round_trip() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+            let (ast, symbol_table, ..) = compiler.type_check(PROGRAM);
+            let ast = compiler.static_single_assignment(ast, &symbol_table);
+
+            // The dumped AST is read back into an identical AST.
+            let path = std::env::temp_dir().join(format!("leo-ssa-ast-dump-{}.json", std::process::id()));
+            leo_ast::dump_ast_json(&ast, &path).unwrap();
+            let reloaded = Ast::from_json_file(path.clone()).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(reloaded, ast);
+        })
+    }
+
+    #[test]
+    fn test_call_targets() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+            let (ast, symbol_table, ..) = compiler.type_check(PROGRAM);
+            let functions = ast.as_repr().program_scopes.values().next().unwrap().functions.clone();
+            let (_, main) = functions.into_iter().find(|(name, _)| *name == Symbol::intern("main")).unwrap();
+
+            // Returns the program that qualifies the call in the function, if any.
+            let external = |function: Function| {
+                function
+                    .block
+                    .statements
+                    .into_iter()
+                    .find_map(|statement| match statement {
+                        Statement::Assign(assign) => match assign.value {
+                            Expression::Call(call) => Some(call.external.map(|external| external.to_string())),
+                            _ => None,
+                        },
+                        _ => None,
+                    })
+                    .expect("Expected the function to contain a call.")
+            };
+
+            // Without a mapping, the call is unchanged.
+            let mut consumer = compiler.static_single_assigner(&symbol_table);
+            assert_eq!(external(consumer.reconstruct_single_function(main.clone())), None);
+
+            // With a mapping, the call is qualified with the program it is imported from, i.e. it becomes `token.aleo/helper`.
+            let call_targets = IndexMap::from([(Symbol::intern("helper"), Symbol::intern("token"))]);
+            let mut consumer = compiler.static_single_assigner(&symbol_table).with_call_targets(call_targets);
+            assert_eq!(external(consumer.reconstruct_single_function(main)), Some("token".to_string()));
+        })
+    }
+
+    #[test]
+    fn test_recover_from_failed_functions() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+
+            let program = "
+program test.aleo {
+    function first(a: u8) -> u8 {
+        return a + 1u8;
+    }
+
+    function second(a: u8) -> u8 {
+        return a + 2u8;
+    }
+
+    transition main(a: u8) -> u8 {
+        let b: u8 = a * 2u8;
+        return b + a;
+    }
+}";
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+
+            // Replace the returned expressions of `first` and `second` with expressions the pass cannot handle.
+            let mut program = ast.into_repr();
+            let scope = program.program_scopes.values_mut().next().unwrap();
+            for (_, function) in scope.functions.iter_mut().take(2) {
+                match function.block.statements.last_mut() {
+                    Some(Statement::Return(return_)) => {
+                        return_.expression = Expression::Err(ErrExpression {
+                            span: Default::default(),
+                            id: compiler.node_builder.next_id(),
+                        })
+                    }
+                    _ => panic!("Expected the function to end in a return statement."),
+                }
+            }
+
+            let mut consumer = compiler.static_single_assigner(&symbol_table);
+            let program = consumer.consume_program(program);
+
+            // Both failures are reported.
+            assert_eq!(compiler.handler.err_count(), 2);
+            let errors = compiler.errors();
+            assert!(errors[0].contains("failed to convert `first` to static single assignment form"), "{errors:?}");
+            assert!(errors[1].contains("failed to convert `second` to static single assignment form"), "{errors:?}");
+
+            // The expressions the pass cannot handle are left in place, while the remaining functions are still processed.
+            let functions = &program.program_scopes.values().next().unwrap().functions;
+            assert!(functions[0].1.to_string().contains("return error"));
+            assert!(functions[1].1.to_string().contains("return error"));
+            assert!(functions[2].1.to_string().contains("$var$"));
+        })
+    }
+
+    #[test]
+    fn test_temporary_spans() {
+        create_session_if_not_set_then(|s| {
+            let compiler = TestCompiler::default();
+
+            let program = "
+program test.aleo {
+    transition main(a: u8, b: u8) -> u8 {
+        return a + b * 2u8;
+    }
+}";
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+
+            let mut consumer = compiler.static_single_assigner(&symbol_table);
+            consumer.consume_program(ast.into_repr());
+
+            // Each temporary maps to the source of the expression it was introduced for.
+            let temporaries = consumer.temporary_spans();
+            assert!(temporaries.iter().all(|(name, _)| name.to_string().starts_with("$var$")));
+            let sources =
+                temporaries.iter().map(|(_, span)| s.source_map.contents_of_span(*span).unwrap()).collect::<Vec<_>>();
+            assert_eq!(sources, ["2u8", "b * 2u8", "a + b * 2u8"]);
+
+            // The locations of the expressions are reported as lines and columns, as for emitted instructions.
+            let locations = consumer.temporary_locations();
+            assert_eq!(
+                locations.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+                temporaries.iter().map(|(name, _)| *name).collect::<Vec<_>>()
+            );
+            assert_eq!(locations.iter().map(|(_, location)| *location).collect::<Vec<_>>(), [
+                Some((4, 24)),
+                Some((4, 20)),
+                Some((4, 16))
+            ]);
+        })
+    }
+
+    #[test]
+    fn test_synthesized_statement_spans() {
+        create_session_if_not_set_then(|s| {
+            let compiler = TestCompiler::default();
+
+            let program = "
+program test.aleo {
+    transition main(a: u8, b: u8, c: bool) -> u8 {
+        let d: u8 = a;
+        if c {
+            d = a + b;
+        }
+        return d;
+    }
+}";
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+
+            let mut consumer = compiler.static_single_assigner(&symbol_table);
+            let program = consumer.consume_program(ast.into_repr());
+            let function = &program.program_scopes.values().next().unwrap().functions[0].1;
+
+            // Collect the assignments, including those in the then-block, with the source of their spans.
+            let mut assignments = Vec::new();
+            for statement in function.block.statements.iter() {
+                match statement {
+                    Statement::Assign(assign) => assignments.push(assign.clone()),
+                    Statement::Conditional(conditional) => {
+                        assignments.extend(conditional.then.statements.iter().filter_map(|statement| match statement {
+                            Statement::Assign(assign) => Some(assign.clone()),
+                            _ => None,
+                        }))
+                    }
+                    _ => {}
+                }
+            }
+            let sources = assignments
+                .iter()
+                .map(|assign| (assign.to_string(), s.source_map.contents_of_span(assign.span).unwrap_or_default()))
+                .collect::<Vec<_>>();
+
+            // Every assignment points at the source it was produced for, rather than an empty span.
+            assert!(assignments.iter().all(|assign| !assign.span.is_dummy()), "{sources:?}");
+            let source_of = |predicate: &dyn Fn(&AssignStatement) -> bool| {
+                let assign = assignments.iter().find(|assign| predicate(assign)).unwrap();
+                s.source_map.contents_of_span(assign.span).unwrap()
+            };
+            assert!(source_of(&|assign| assign.to_string().ends_with(" = a;")).starts_with("let d: u8 = a"));
+            assert_eq!(source_of(&|assign| matches!(assign.value, Expression::Binary(_))), "a + b");
+            assert!(source_of(&|assign| assign.to_string().starts_with("d$") && assign.to_string().contains("$var$"))
+                .starts_with("d = a + b"));
+
+            // The phi function points at the conditional statement that introduced it.
+            assert!(source_of(&|assign| matches!(assign.value, Expression::Ternary(_))).starts_with("if c {"));
+        })
+    }
+
+    #[test]
+    fn test_circuit_bindings() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+
+            let program = "
+program test.aleo {
+    struct Point {
+        x: u8,
+        y: u8,
+    }
+
+    transition main(a: u8) -> u8 {
+        let p: Point = Point { x: a, y: a };
+        let b: u8 = p.x + a;
+        return b;
+    }
+}";
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+
+            let mut consumer = compiler.static_single_assigner(&symbol_table);
+            consumer.consume_program(ast.as_repr().clone());
+
+            // Only the variables holding the struct are bound, i.e. the temporary and the renamed `p`.
+            let bindings = consumer
+                .circuit_bindings()
+                .iter()
+                .map(|(variable, circuit)| (variable.to_string(), circuit.to_string()))
+                .collect::<Vec<_>>();
+            assert_eq!(bindings.len(), 2, "{bindings:?}");
+            assert!(bindings[0].0.starts_with("$var$"));
+            assert!(bindings[1].0.starts_with("p$"));
+            assert!(bindings.iter().all(|(_, circuit)| circuit == "Point"));
+
+            // The pass outputs the same bindings, when run with a fresh assigner, so that the same names are produced.
+            let assigner = Assigner::default();
+            let (_, circuits) = StaticSingleAssigner::do_pass((
+                ast,
+                &compiler.handler,
+                &compiler.node_builder,
+                &assigner,
+                &symbol_table,
+                &compiler.type_table,
+                Default::default(),
+            ))
+            .unwrap();
+            let names = circuits.keys().map(|variable| variable.to_string()).collect::<Vec<_>>();
+            assert_eq!(names, bindings.into_iter().map(|(variable, _)| variable).collect::<Vec<_>>());
+        })
+    }
+
+    #[test]
+    fn test_unit_returns() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+
+            // A function with an early `return;` that otherwise falls through.
+            let program = "
+program test.aleo {
+    transition main(flag: bool, a: u8) {
+        if (flag) {
+            return;
+        }
+        assert_eq(a, 1u8);
+    }
+}";
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+
+            // The unit return does not produce any temporaries.
+            let ast = compiler.static_single_assignment(ast, &symbol_table);
+            let function = ast.as_repr().program_scopes.values().next().unwrap().functions[0].1.to_string();
+            assert!(function.contains("return ()"));
+            assert!(!function.contains('$'));
+
+            // The returns are consolidated into a single unit return, without any return temporaries.
+            let ast = compiler.flatten(ast, &symbol_table);
+            let function = ast.as_repr().program_scopes.values().next().unwrap().functions[0].1.to_string();
+            assert_eq!(function.matches("return").count(), 1);
+            assert!(function.contains("return ()"));
+            assert!(!function.contains("$ret"));
+        })
+    }
+
+    #[test]
+    fn test_struct_init_member_order() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+
+            let program = "
+program test.aleo {
+    struct Point {
+        x: u8,
+        y: u8,
+    }
+
+    transition main(flag: bool, a: u8, b: u8) -> Point {
+        let p: Point = Point { y: b, x: a };
+        let q: Point = Point { x: b, y: a };
+        return flag ? p : q;
+    }
+}";
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+            let ast = compiler.static_single_assignment(ast, &symbol_table);
+            let ast = compiler.flatten(ast, &symbol_table);
+            let function = ast.as_repr().program_scopes.values().next().unwrap().functions[0].1.to_string();
+            let assignments = function
+                .lines()
+                .filter_map(|line| line.trim().strip_suffix(';')?.split_once(" = "))
+                .collect::<IndexMap<_, _>>();
+
+            // The members of both struct expressions are in declaration order.
+            assert!(assignments.values().any(|value| *value == "{x: a, y: b}"));
+            assert!(assignments.values().any(|value| *value == "{x: b, y: a}"));
+
+            // Each member of the result of the ternary expression selects the same member of both branches.
+            let result = assignments.values().rev().find(|value| value.starts_with('{')).unwrap();
+            let members = result.trim_matches(|c| c == '{' || c == '}').split(", ").collect::<Vec<_>>();
+            for (member, name) in members.into_iter().zip(["x", "y"]) {
+                let (member_name, variable) = member.split_once(": ").unwrap();
+                assert_eq!(member_name, name);
+                let (_, branches) =
+                    assignments[variable].trim_matches(|c| c == '(' || c == ')').split_once(" ? ").unwrap();
+                for branch in branches.split(" : ") {
+                    assert!(assignments[branch].ends_with(&format!(".{name}")));
+                }
+            }
+        })
+    }
+
+    /// The options that enable constant propagation.
+    const CONSTANT_PROPAGATION: StaticSingleAssignerOptions =
+        StaticSingleAssignerOptions { constant_propagation_enabled: true, assert_lowering_enabled: false };
+
+    /// The options that enable assert lowering.
+    const ASSERT_LOWERING: StaticSingleAssignerOptions =
+        StaticSingleAssignerOptions { constant_propagation_enabled: false, assert_lowering_enabled: true };
+
+    /// Parses the given program and applies SSA, returning the first function.
+    fn static_single_assignment(program: &str, options: StaticSingleAssignerOptions) -> String {
+        let compiler = TestCompiler::default();
+        let (ast, symbol_table, ..) = compiler.type_check(program);
+        let (ast, _) = StaticSingleAssigner::do_pass((
+            ast,
+            &compiler.handler,
+            &compiler.node_builder,
+            &compiler.assigner,
+            &symbol_table,
+            &compiler.type_table,
+            options,
+        ))
+        .unwrap();
+        ast.as_repr().program_scopes.values().next().unwrap().functions[0].1.to_string()
+    }
+
+    #[test]
+    fn test_constant_propagation() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(c: u8) -> u8 {
+        let a: u8 = 5u8;
+        let b: u8 = a;
+        return b + c;
+    }
+}";
+            // The constant is propagated through the chain of assignments.
+            assert!(static_single_assignment(program, CONSTANT_PROPAGATION).contains("5u8 + c"));
+            // The constant is not propagated if constant propagation is disabled.
+            assert!(!static_single_assignment(program, Default::default()).contains("5u8 + c"));
+        })
+    }
+
+    #[test]
+    fn test_constant_propagation_across_conditionals() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(flag: bool, c: u8) -> u8 {
+        let a: u8 = 1u8;
+        if (flag) {
+            a = 2u8;
+            let b: u8 = a * c;
+        }
+        return a + c;
+    }
+}";
+            let function = static_single_assignment(program, CONSTANT_PROPAGATION);
+            // The constant is known within the branch.
+            assert!(function.contains("2u8 * c"));
+            // After the branches are merged, the value of `a` is no longer known.
+            assert!(!function.contains("1u8 + c"));
+            assert!(!function.contains("2u8 + c"));
+            assert!(function.contains("flag ? "));
+        })
+    }
+
+    #[test]
+    fn test_ternary_literal_branches() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(flag: bool, x: u8) -> u8 {
+        let zero: u8 = 0u8;
+        return flag ? x : zero;
+    }
+}";
+            // A propagated literal of the same type as the other branch is assigned to a variable, rather than cast.
+            let function = static_single_assignment(program, CONSTANT_PROPAGATION);
+            assert!(function.contains(" = 0u8;"), "{function}");
+            assert!(function.contains("flag ? x : $var$"), "{function}");
+            assert!(!function.contains(" as "), "{function}");
+
+            // A literal branch of a different type, e.g. one left by constant folding, is re-typed to the other type.
+            let (handler, buf) = Handler::new_with_buf();
+            let node_builder = NodeBuilder::default();
+            let symbol_table = SymbolTable::default();
+            let type_table = TypeTable::default();
+            let assigner = Assigner::default();
+
+            let identifier = |name: &str, type_: Type| {
+                let identifier = Identifier::new(Symbol::intern(name), node_builder.next_id());
+                type_table.insert(identifier.id, type_);
+                Expression::Identifier(identifier)
+            };
+            let ternary = |value: &str| {
+                let literal =
+                    Literal::Integer(IntegerType::U16, value.to_string(), Default::default(), node_builder.next_id());
+                type_table.insert(literal.id(), Type::Integer(IntegerType::U16));
+                let ternary = TernaryExpression {
+                    condition: Box::new(identifier("flag", Type::Boolean)),
+                    if_true: Box::new(identifier("x", Type::Integer(IntegerType::U8))),
+                    if_false: Box::new(Expression::Literal(literal)),
+                    span: Default::default(),
+                    id: node_builder.next_id(),
+                };
+                type_table.insert(ternary.id, Type::Integer(IntegerType::U8));
+                ternary
+            };
+
+            let mut consumer = StaticSingleAssigner::new(
+                &handler,
+                &node_builder,
+                &symbol_table,
+                &type_table,
+                &assigner,
+                Default::default(),
+            );
+            let (_, statements) = consumer.consume_ternary(ternary("0"));
+            let statements = statements.iter().map(|statement| statement.to_string()).collect::<Vec<_>>();
+            assert_eq!(statements.len(), 2, "{statements:?}");
+            assert!(statements[0].ends_with(" = 0u8;"), "{statements:?}");
+            assert!(!statements.iter().any(|statement| statement.contains(" as ")), "{statements:?}");
+
+            // The ternary selects the re-typed literal.
+            let (literal, _) = statements[0].split_once(" = ").unwrap();
+            assert!(statements[1].ends_with(&format!(" = (flag ? x : {literal});")), "{statements:?}");
+            assert_eq!(handler.err_count(), 0);
+
+            // A literal that is out of the range of the other type is reported.
+            consumer.consume_ternary(ternary("300"));
+            assert_eq!(handler.err_count(), 1);
+            let error = buf.extract_errs().last_entry().unwrap().to_string();
+            assert!(error.contains("The literal `300u16` must have the type `u8`"), "{error}");
+        })
+    }
+
+    #[test]
+    fn test_assert_lowering() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(a: u8, b: u8) {
+        assert(a != b);
+        assert(!(a == b));
+        assert(!(a < b));
+    }
+}";
+            // Assertions of inequalities are rewritten into `assert_neq` statements.
+            let function = static_single_assignment(program, ASSERT_LOWERING);
+            assert_eq!(function.matches("assert_neq(a, b);").count(), 2);
+            assert_eq!(function.matches("assert(").count(), 1);
+
+            // The assertions are unchanged if assert lowering is disabled.
+            let function = static_single_assignment(program, Default::default());
+            assert!(!function.contains("assert_neq"));
+            assert_eq!(function.matches("assert(").count(), 3);
+        })
+    }
+
+    #[test]
+    fn test_array_access() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(arr: [u8; 4], i: u32) -> u8 {
+        let j: u32 = i;
+        j = j + 1u32;
+        return arr[j + 1u32] + arr[1u32];
+    }
+}";
+            let function = static_single_assignment(program, Default::default());
+            let assignments = function
+                .lines()
+                .filter_map(|line| line.trim().strip_suffix(';')?.split_once(" = "))
+                .collect::<Vec<_>>();
+
+            // The index is consumed, so it refers to the latest version of `j`.
+            let (j, _) = assignments.iter().rev().find(|(place, _)| place.starts_with("j$")).unwrap();
+            let index = assignments.iter().find_map(|(_, value)| value.strip_prefix("arr.$var$")).unwrap();
+            let (_, value) = assignments.iter().find(|(place, _)| *place == format!("$var${index}")).unwrap();
+            assert!(value.starts_with(&format!("{j} + ")));
+            // Literal indices are preserved.
+            assert!(assignments.iter().any(|(_, value)| *value == "arr.1u32"));
+        })
+    }
+
+    #[test]
+    fn test_compound_assignments() {
+        create_session_if_not_set_then(|_| {
+            // Each compound operator, the type of its operands, and the binary operator it desugars to.
+            let cases = [
+                ("+=", "u8", "+"),
+                ("-=", "u8", "-"),
+                ("*=", "u8", "*"),
+                ("/=", "u8", "/"),
+                ("%=", "u8", "%"),
+                ("**=", "u8", "**"),
+                ("<<=", "u8", "<<"),
+                (">>=", "u8", ">>"),
+                ("&=", "u8", "&"),
+                ("|=", "u8", "|"),
+                ("^=", "u8", "^"),
+                // The bitwise operators also apply to booleans, with the same binary operators.
+                ("&=", "bool", "&"),
+                ("|=", "bool", "|"),
+                ("^=", "bool", "^"),
+                ("&&=", "bool", "&&"),
+                ("||=", "bool", "||"),
+            ];
+            for (compound, type_, operator) in cases {
+                let program = format!(
+                    "
+program test.aleo {{
+    transition main(a: {type_}, b: {type_}) -> {type_} {{
+        let c: {type_} = a;
+        c {compound} b;
+        return c;
+    }}
+}}"
+                );
+                let function = static_single_assignment(&program, Default::default());
+
+                // The right-hand side of the compound assignment is a binary expression over the previous version of `c`.
+                let binary = function
+                    .lines()
+                    .filter_map(|line| line.trim().strip_suffix(';')?.split_once(" = "))
+                    .find_map(|(_, value)| match value.split(' ').collect::<Vec<_>>()[..] {
+                        [left, operator, "b"] if left.starts_with("c$") => Some(operator.to_string()),
+                        _ => None,
+                    });
+                assert_eq!(binary.as_deref(), Some(operator), "`{compound}` on `{type_}`:\n{function}");
+            }
+        })
+    }
+
+    #[test]
+    fn test_identical_ternary_branches() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(flag: bool, a: u8) -> u8 {
+        let b: u8 = flag ? a : a;
+        let c: u8 = a == 1u8 ? 2u8 : 2u8;
+        let d: u8 = flag ? a : 1u8;
+        return b + c + d;
+    }
+}";
+            // Ternary expressions with identical branches are replaced with the branch.
+            let function = static_single_assignment(program, CONSTANT_PROPAGATION);
+            assert!(!function.contains("flag ? a : a"), "{function}");
+            assert!(!function.contains("? 2u8 : 2u8"), "{function}");
+            // The condition is still evaluated, and ternary expressions with different branches are preserved.
+            assert!(function.contains("a == 1u8"), "{function}");
+            assert!(function.contains("flag ? a : $var$"), "{function}");
+            assert!(!function.contains("flag ? a : 1u8"), "{function}");
+
+            // The ternary expressions are unchanged if constant propagation is disabled.
+            let function = static_single_assignment(program, Default::default());
+            assert!(function.contains("flag ? a : a"), "{function}");
+            assert!(function.contains("? 2u8 : 2u8"), "{function}");
+        })
+    }
+
+    #[test]
+    fn test_tuple_definitions() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(x: u8, y: u8) -> u8 {
+        let (a, b): (u8, u8) = (x, y);
+        let (c, d): (u8, u8) = swap(a, b);
+        return a + b + c + d;
+    }
+
+    function swap(a: u8, b: u8) -> (u8, u8) {
+        return (b, a);
+    }
+}";
+            let function = static_single_assignment(program, Default::default());
+            let lines = function.lines().map(str::trim).collect::<Vec<_>>();
+
+            // Each name bound to a tuple expression is assigned its element, without an intermediate tuple.
+            assert!(lines.iter().any(|line| line.starts_with("a$") && line.ends_with(" = x;")), "{function}");
+            assert!(lines.iter().any(|line| line.starts_with("b$") && line.ends_with(" = y;")), "{function}");
+            assert!(!function.contains("(x,y)"), "{function}");
+
+            // The names bound to the outputs of a call are assigned by a single tuple assignment.
+            assert!(lines.iter().any(|line| line.starts_with("(c$") && line.contains(",d$")), "{function}");
+
+            // Nested tuples and mismatched lengths are reported, rather than causing a panic.
+            let (handler, buf) = Handler::new_with_buf();
+            let node_builder = NodeBuilder::default();
+            let symbol_table = SymbolTable::default();
+            let type_table = TypeTable::default();
+            let assigner = Assigner::default();
+            let mut consumer = StaticSingleAssigner::new(
+                &handler,
+                &node_builder,
+                &symbol_table,
+                &type_table,
+                &assigner,
+                Default::default(),
+            );
+
+            let identifier =
+                |name: &str| Expression::Identifier(Identifier::new(Symbol::intern(name), node_builder.next_id()));
+            let tuple = |elements: Vec<Expression>| {
+                Expression::Tuple(TupleExpression { elements, span: Default::default(), id: node_builder.next_id() })
+            };
+            let u8 = Type::Integer(IntegerType::U8);
+            let tuple_type = Type::Tuple(TupleType::new(vec![u8.clone(), u8.clone()]));
+
+            // The value `v` is a variable of type `(u8, u8)`.
+            let v = Symbol::intern("v");
+            consumer.rename_table.update(v, v, node_builder.next_id());
+            let value = || {
+                let value = Identifier::new(v, node_builder.next_id());
+                type_table.insert(value.id, tuple_type.clone());
+                Expression::Identifier(value)
+            };
+            let definition = |place: Expression, value: Expression| DefinitionStatement {
+                declaration_type: DeclarationType::Let,
+                place,
+                type_: tuple_type.clone(),
+                value,
+                span: Default::default(),
+                id: node_builder.next_id(),
+            };
+
+            // let (a, (b, c)) = v;
+            let place = tuple(vec![identifier("a"), tuple(vec![identifier("b"), identifier("c")])]);
+            assert!(consumer.consume_definition(definition(place, value())).is_empty());
+            // let (a, b, c) = v;
+            let place = tuple(vec![identifier("a"), identifier("b"), identifier("c")]);
+            assert!(consumer.consume_definition(definition(place, value())).is_empty());
+
+            assert_eq!(handler.err_count(), 2);
+            let errors = buf.extract_errs().into_inner().into_iter().map(|error| error.to_string()).collect::<Vec<_>>();
+            assert!(errors[0].contains("can only contain identifiers"), "{errors:?}");
+            assert!(errors[1].contains("Expected a tuple with 2 elements, found one with 3 elements"), "{errors:?}");
+        })
+    }
+
+    #[test]
+    fn test_ternary_tuple_length_mismatch() {
+        create_session_if_not_set_then(|_| {
+            // Note that type checking rejects tuples of different lengths, so the AST is constructed directly.
+            let (handler, buf) = Handler::new_with_buf();
+            let node_builder = NodeBuilder::default();
+            let symbol_table = SymbolTable::default();
+            let type_table = TypeTable::default();
+            let assigner = Assigner::default();
+            let mut consumer = StaticSingleAssigner::new(
+                &handler,
+                &node_builder,
+                &symbol_table,
+                &type_table,
+                &assigner,
+                Default::default(),
+            );
+
+            for name in ["flag", "x", "y", "z"] {
+                let name = Symbol::intern(name);
+                consumer.rename_table.update(name, name, node_builder.next_id());
+            }
+            let identifier =
+                |name: &str| Expression::Identifier(Identifier::new(Symbol::intern(name), node_builder.next_id()));
+            let tuple = |elements: Vec<Expression>| {
+                Expression::Tuple(TupleExpression { elements, span: Default::default(), id: node_builder.next_id() })
+            };
+
+            // flag ? (x, y) : (x, y, z)
+            let ternary = Expression::Ternary(TernaryExpression {
+                condition: Box::new(identifier("flag")),
+                if_true: Box::new(tuple(vec![identifier("x"), identifier("y")])),
+                if_false: Box::new(tuple(vec![identifier("x"), identifier("y"), identifier("z")])),
+                span: Default::default(),
+                id: node_builder.next_id(),
+            });
+            let (expression, _) = consumer.consume_expression(ternary);
+            assert!(!matches!(expression, Expression::Ternary(_)), "{expression}");
+
+            assert_eq!(handler.err_count(), 1);
+            let errors = buf.extract_errs().into_inner().into_iter().map(|error| error.to_string()).collect::<Vec<_>>();
+            assert!(errors[0].contains("tuples of different lengths, 2 and 3"), "{errors:?}");
+        })
+    }
+
+    #[test]
+    fn test_shadowed_parameter() {
+        create_session_if_not_set_then(|_| {
+            // Note that type checking rejects shadowing, so the AST is constructed directly.
+            let handler = Handler::default();
+            let node_builder = NodeBuilder::default();
+            let symbol_table = SymbolTable::default();
+            let type_table = TypeTable::default();
+            let assigner = Assigner::default();
+            let mut consumer = StaticSingleAssigner::new(
+                &handler,
+                &node_builder,
+                &symbol_table,
+                &type_table,
+                &assigner,
+                Default::default(),
+            );
+
+            // Constructs a typed identifier.
+            let identifier = |name: &str, type_: Type| {
+                let identifier = Identifier::new(Symbol::intern(name), node_builder.next_id());
+                type_table.insert(identifier.id, type_);
+                identifier
+            };
+            let u8 = Type::Integer(IntegerType::U8);
+
+            // The parameters `flag`, `x`, and `y`.
+            for (name, type_) in [("flag", Type::Boolean), ("x", u8.clone()), ("y", u8.clone())] {
+                let parameter = identifier(name, type_);
+                consumer.rename_table.update(parameter.name, parameter.name, parameter.id);
+            }
+
+            // if flag {
+            //     let x: u8 = y;
+            //     y = x;
+            // }
+            let then = Block {
+                statements: vec![
+                    Statement::Definition(DefinitionStatement {
+                        declaration_type: DeclarationType::Let,
+                        place: Expression::Identifier(identifier("x", u8.clone())),
+                        type_: u8.clone(),
+                        value: Expression::Identifier(identifier("y", u8.clone())),
+                        span: Default::default(),
+                        id: node_builder.next_id(),
+                    }),
+                    Statement::Assign(Box::new(AssignStatement {
+                        place: Expression::Identifier(identifier("y", u8.clone())),
+                        value: Expression::Identifier(identifier("x", u8.clone())),
+                        span: Default::default(),
+                        id: node_builder.next_id(),
+                    })),
+                ],
+                span: Default::default(),
+                id: node_builder.next_id(),
+            };
+            let statements = consumer.consume_conditional(ConditionalStatement {
+                condition: Expression::Identifier(identifier("flag", Type::Boolean)),
+                then,
+                otherwise: None,
+                span: Default::default(),
+                id: node_builder.next_id(),
+            });
+            let statements = statements.iter().map(|statement| statement.to_string()).collect::<Vec<_>>();
+
+            // Only the assignment to `y` is merged, while the shadowing `x` goes out of scope with the block.
+            let phis = statements.iter().filter(|statement| statement.contains("(flag ? ")).collect::<Vec<_>>();
+            assert_eq!(phis.len(), 1, "{statements:?}");
+            assert!(phis[0].contains("(flag ? y$"), "{statements:?}");
+            assert!(phis[0].ends_with(" : y);"), "{statements:?}");
+            let (y, _) = statements.last().unwrap().split_once(" = ").unwrap();
+            assert!(y.starts_with("y$"), "{statements:?}");
+
+            // A subsequent use of `x` refers to the parameter, rather than the shadowing variable.
+            let ternary = TernaryExpression {
+                condition: Box::new(Expression::Identifier(identifier("flag", Type::Boolean))),
+                if_true: Box::new(Expression::Identifier(identifier("x", u8.clone()))),
+                if_false: Box::new(Expression::Identifier(identifier("y", u8.clone()))),
+                span: Default::default(),
+                id: node_builder.next_id(),
+            };
+            type_table.insert(ternary.id, u8);
+            let (_, statements) = consumer.consume_ternary(ternary);
+            assert!(statements[0].to_string().ends_with(&format!(" = (flag ? x : {y});")), "{statements:?}");
+        })
+    }
+
+    #[test]
+    fn test_constant_tuple_access() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition main(x: u8, y: u8) -> u8 {
+        return (x, y).1;
+    }
+}";
+            // The access is folded into the selected element, without assigning the tuple.
+            let function = static_single_assignment(program, Default::default());
+            assert!(function.contains("return y;"), "{function}");
+            assert!(!function.contains(".1"), "{function}");
+
+            // An index that is out of bounds is reported, rather than folded.
+            let (handler, buf) = Handler::new_with_buf();
+            let node_builder = NodeBuilder::default();
+            let symbol_table = SymbolTable::default();
+            let type_table = TypeTable::default();
+            let assigner = Assigner::default();
+
+            let identifier = |name: &str| {
+                let identifier = Identifier::new(Symbol::intern(name), node_builder.next_id());
+                type_table.insert(identifier.id, Type::Integer(IntegerType::U8));
+                Expression::Identifier(identifier)
+            };
+            let tuple = TupleExpression {
+                elements: vec![identifier("x"), identifier("y")],
+                span: Default::default(),
+                id: node_builder.next_id(),
+            };
+            let u8 = Type::Integer(IntegerType::U8);
+            type_table.insert(tuple.id, Type::Tuple(TupleType::new(vec![u8.clone(), u8.clone()])));
+            let access = TupleAccess {
+                tuple: Box::new(Expression::Tuple(tuple)),
+                index: 2usize.into(),
+                span: Default::default(),
+                id: node_builder.next_id(),
+            };
+            type_table.insert(access.id, u8);
+
+            let mut consumer = StaticSingleAssigner::new(
+                &handler,
+                &node_builder,
+                &symbol_table,
+                &type_table,
+                &assigner,
+                Default::default(),
+            );
+            for name in [Symbol::intern("x"), Symbol::intern("y")] {
+                consumer.rename_table.update(name, name, node_builder.next_id());
+            }
+            consumer.consume_access(AccessExpression::Tuple(access));
+            assert_eq!(handler.err_count(), 1);
+            let error = buf.extract_errs().last_entry().unwrap().to_string();
+            assert!(error.contains("Tuple index `2` out of range"), "{error}");
+        })
+    }
+
+    #[test]
+    fn test_undefined_variables() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+
+            let program = "
+program test.aleo {
+    mapping values: u8 => u8;
+
+    transition first(a: u8) -> u8 {
+        return a;
+    }
+
+    transition second(a: u8) -> u8 {
+        return a;
+    }
+
+    transition third(a: u8) {
+        return then finalize(a);
+    }
+
+    finalize third(a: u8) {
+        let b: u8 = Mapping::get_or_use(values, a, 0u8);
+        Mapping::set(values, a, b);
+    }
+}";
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+
+            // Since type checking rejects undefined variables, the returned variables are renamed to `y` afterwards.
+            let mut program = ast.into_repr();
+            let scope = program.program_scopes.values_mut().next().unwrap();
+            for (_, function) in scope.functions.iter_mut().take(2) {
+                match function.block.statements.last_mut() {
+                    Some(Statement::Return(return_)) => match &mut return_.expression {
+                        Expression::Identifier(identifier) => identifier.name = Symbol::intern("y"),
+                        _ => panic!("Expected the function to return a variable."),
+                    },
+                    _ => panic!("Expected the function to end in a return statement."),
+                }
+            }
+
+            let result = StaticSingleAssigner::do_pass((
+                Ast::new(program),
+                &compiler.handler,
+                &compiler.node_builder,
+                &compiler.assigner,
+                &symbol_table,
+                &compiler.type_table,
+                Default::default(),
+            ));
+            assert!(result.is_err());
+
+            // Each use is reported, while the mapping in the finalize block is not.
+            assert_eq!(compiler.handler.err_count(), 2);
+            let errors = compiler.errors();
+            assert!(errors.iter().all(|error| error.contains("variable `y` is not defined")), "{errors:?}");
+        })
+    }
+
+    #[test]
+    fn test_associated_constants() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    transition binary(a: group) -> group {
+        return a + group::GEN;
+    }
+
+    transition call() -> group {
+        return helper(group::GEN);
+    }
+
+    transition constant() -> group {
+        return group::GEN;
+    }
+
+    transition select(c: bool, a: group) -> group {
+        return c ? group::GEN : a;
+    }
+
+    function helper(x: group) -> group {
+        return x;
+    }
+}";
+            let compiler = TestCompiler::default();
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+            let ast = compiler.static_single_assignment(ast, &symbol_table);
+            assert_eq!(compiler.handler.err_count(), 0);
+            let functions = &ast.as_repr().program_scopes.values().next().unwrap().functions;
+            let function = |index: usize| functions[index].1.to_string();
+
+            // The constant is used directly as an operand of binary expressions and calls.
+            for (index, operation) in [(0, " = a + group::GEN;"), (1, " = helper(group::GEN);")] {
+                assert!(function(index).contains(operation), "{}", function(index));
+                assert!(!function(index).contains(" = group::GEN;"), "{}", function(index));
+            }
+
+            // Elsewhere, the constant is assigned to a variable, since flattening requires returned values and
+            // the branches of ternary expressions to be variables.
+            assert!(function(2).contains(" = group::GEN;"), "{}", function(2));
+            assert!(!function(2).contains("return group::GEN"), "{}", function(2));
+            assert!(function(3).contains(" = group::GEN;"), "{}", function(3));
+            assert!(!function(3).contains("? group::GEN"), "{}", function(3));
+        })
+    }
+
+    #[test]
+    fn test_member_access_chains() {
+        create_session_if_not_set_then(|s| {
+            let program = "
+program test.aleo {
+    struct Inner {
+        c: u8,
+        d: u8,
+    }
+
+    struct Outer {
+        b: Inner,
+        e: u8,
+    }
+
+    transition main(x: u8, y: u8) -> u8 {
+        return Outer { b: Inner { c: x, d: y }, e: y }.b.c;
+    }
+}";
+            // With constant propagation, the chain collapses into the accessed member.
+            let function = static_single_assignment(program, CONSTANT_PROPAGATION);
+            assert!(function.contains("return x;"), "{function}");
+            assert!(!function.contains(".b"), "{function}");
+
+            // Otherwise, each access is lowered into a temporary, which maps to the source of the access.
+            let compiler = TestCompiler::default();
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+
+            let mut consumer = compiler.static_single_assigner(&symbol_table);
+            consumer.consume_program(ast.into_repr());
+            let sources = consumer
+                .temporary_spans()
+                .iter()
+                .map(|(_, span)| s.source_map.contents_of_span(*span).unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                sources[sources.len() - 2..],
+                ["Outer { b: Inner { c: x, d: y }, e: y }.b", "Outer { b: Inner { c: x, d: y }, e: y }.b.c"]
+            );
+        })
+    }
+
+    #[test]
+    fn test_short_circuit_side_effects() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    mapping flags: address => bool;
+
+    function is_small(a: u8) -> bool {
+        assert(a < 100u8);
+        return a < 10u8;
+    }
+
+    transition main(flag: bool, a: u8, b: u8) -> bool {
+        let c: bool = flag && a == b;
+        let d: bool = flag || is_small(a);
+        return then finalize(self.caller, c && d);
+    }
+
+    finalize main(owner: address, flag: bool) {
+        let present: bool = flag && Mapping::contains(flags, owner);
+        let set: bool = flag && Mapping::get(flags, owner);
+        assert(present || set);
+    }
+}";
+            let compiler = TestCompiler::default();
+            let (ast, symbol_table, ..) = compiler.type_check(program);
+            let mut consumer = compiler.static_single_assigner(&symbol_table);
+            consumer.consume_program(ast.into_repr());
 
-        Ok(Ast::new(program))
+            // The rhs is always evaluated, so only a call and a read that fails if the key is not present are rejected.
+            assert_eq!(compiler.handler.err_count(), 2);
+            let errors = compiler.errors();
+            assert!(errors[0].contains("The right operand of `||` has side effects"), "{errors:?}");
+            assert!(errors[1].contains("The right operand of `&&` has side effects"), "{errors:?}");
+        })
     }
 }
@@ -22,13 +22,16 @@ use leo_ast::{
     ArrayExpression,
     AssociatedFunction,
     BinaryExpression,
+    BinaryOperation,
     CallExpression,
     CastExpression,
+    ErrExpression,
     Expression,
     ExpressionConsumer,
     Identifier,
     Literal,
     MemberAccess,
+    Node,
     Statement,
     Struct,
     StructExpression,
@@ -36,9 +39,12 @@ use leo_ast::{
     TernaryExpression,
     TupleAccess,
     TupleExpression,
+    Type,
     UnaryExpression,
+    UnaryOperation,
     UnitExpression,
 };
+use leo_errors::{CompilerError, TypeCheckerError};
 use leo_span::{sym, Symbol};
 
 use indexmap::IndexMap;
@@ -79,6 +85,39 @@ impl ExpressionConsumer for StaticSingleAssigner<'_> {
                     }
                 }
 
+                // The coordinates of a group element, e.g. `g.x`, are rewritten into the corresponding unary operation,
+                // which code generation lowers to a `cast` instruction.
+                if let Some(Type::Group) = self.type_table.get(&member.inner.id()) {
+                    let op = match member.name.name {
+                        sym::x => UnaryOperation::ToXCoordinate,
+                        sym::y => UnaryOperation::ToYCoordinate,
+                        // Type checking guarantees that groups only have `x` and `y` members, so others are only reported for unchecked ASTs.
+                        name => {
+                            self.emit_internal_err(format!("groups do not have a member `{name}`"), member.span);
+                            return (Expression::Access(AccessExpression::Member(member)), Vec::new());
+                        }
+                    };
+                    return self.consume_unary(UnaryExpression {
+                        op,
+                        receiver: member.inner,
+                        span: member.span,
+                        id: member.id,
+                    });
+                }
+
+                // If constant propagation is enabled, accesses into struct expressions are folded into the accessed member.
+                let member = match self.options.constant_propagation_enabled {
+                    true => match self.fold_member_access(member) {
+                        Ok((expr, mut statements)) => {
+                            let (expr, mut stmts) = self.consume_expression(expr);
+                            statements.append(&mut stmts);
+                            return (expr, statements);
+                        }
+                        Err(member) => member,
+                    },
+                    false => member,
+                };
+
                 let (expr, statements) = self.consume_expression(*member.inner);
                 (
                     AccessExpression::Member(MemberAccess {
@@ -90,32 +129,69 @@ impl ExpressionConsumer for StaticSingleAssigner<'_> {
                     statements,
                 )
             }
-            AccessExpression::Tuple(tuple) => {
-                let (expr, statements) = self.consume_expression(*tuple.tuple);
-                (
-                    AccessExpression::Tuple(TupleAccess {
-                        tuple: Box::new(expr),
-                        index: tuple.index,
-                        span: tuple.span,
-                        id: tuple.id,
-                    }),
-                    statements,
-                )
-            }
+            AccessExpression::Tuple(tuple) => match *tuple.tuple {
+                // A constant index into a tuple expression, e.g. `(a, b).0`, is folded into the selected element.
+                // Note that every element is still consumed, so that the statements they produce, e.g. calls, are kept.
+                Expression::Tuple(inner) if tuple.index.value() < inner.elements.len() => {
+                    let mut statements = Vec::new();
+                    let mut elements = inner
+                        .elements
+                        .into_iter()
+                        .map(|element| {
+                            let (element, mut stmts) = self.consume_expression(element);
+                            statements.append(&mut stmts);
+                            element
+                        })
+                        .collect::<Vec<_>>();
+                    return (elements.swap_remove(tuple.index.value()), statements);
+                }
+                expr => {
+                    // Type checking guarantees that the index is in bounds, so this is only reported for unchecked ASTs.
+                    if let Expression::Tuple(inner) = &expr {
+                        let length = inner.elements.len();
+                        self.handler.emit_err(TypeCheckerError::tuple_out_of_range(&tuple.index, length, tuple.span));
+                    }
+                    let (expr, statements) = self.consume_expression(expr);
+                    (
+                        AccessExpression::Tuple(TupleAccess {
+                            tuple: Box::new(expr),
+                            index: tuple.index,
+                            span: tuple.span,
+                            id: tuple.id,
+                        }),
+                        statements,
+                    )
+                }
+            },
             AccessExpression::Array(input) => {
-                let (array, statements) = self.consume_expression(*input.array);
+                let (array, mut statements) = self.consume_expression(*input.array);
+
+                // Literal indices are preserved, since code generation requires array indices to be literals.
+                // Any other index is consumed, so that it refers to the renamed variables.
+                let index = match *input.index {
+                    index @ Expression::Literal(_) => index,
+                    index => {
+                        let (index, mut stmts) = self.consume_expression(index);
+                        statements.append(&mut stmts);
+                        index
+                    }
+                };
 
                 (
                     AccessExpression::Array(ArrayAccess {
                         array: Box::new(array),
-                        index: input.index,
+                        index: Box::new(index),
                         span: input.span,
                         id: input.id,
                     }),
                     statements,
                 )
             }
-            expr => (expr, Vec::new()),
+            // Associated constants, e.g. `group::GEN`, are already values, so they are not renamed.
+            // Note that they are only used directly as operands by `consume_operand`.
+            AccessExpression::AssociatedConstant(constant) => {
+                (AccessExpression::AssociatedConstant(constant), Vec::new())
+            }
         };
         let (place, statement) = self.unique_simple_assign_statement(Expression::Access(expr));
         statements.push(statement);
@@ -152,9 +228,19 @@ impl ExpressionConsumer for StaticSingleAssigner<'_> {
     /// Consumes a binary expression, accumulating any statements that are generated.
     fn consume_binary(&mut self, input: BinaryExpression) -> Self::Output {
         // Reconstruct the lhs of the binary expression.
-        let (left_expression, mut statements) = self.consume_expression(*input.left);
+        let (left_expression, mut statements) = self.consume_operand(*input.left);
         // Reconstruct the rhs of the binary expression.
-        let (right_expression, mut right_statements) = self.consume_expression(*input.right);
+        let right_span = input.right.span();
+        let (right_expression, mut right_statements) = self.consume_operand(*input.right);
+
+        // The rhs of a logical operator is always evaluated, since Aleo instructions cannot evaluate it conditionally.
+        // Hence, side effects in the rhs are rejected, rather than executed when the lhs determines the result.
+        if matches!(input.op, BinaryOperation::And | BinaryOperation::Or)
+            && right_statements.iter().any(Self::has_side_effects)
+        {
+            self.handler.emit_err(CompilerError::short_circuit_side_effects(input.op, right_span));
+        }
+
         // Accumulate any statements produced.
         statements.append(&mut right_statements);
 
@@ -180,19 +266,27 @@ impl ExpressionConsumer for StaticSingleAssigner<'_> {
             .arguments
             .into_iter()
             .map(|argument| {
-                let (argument, mut stmts) = self.consume_expression(argument);
+                let (argument, mut stmts) = self.consume_operand(argument);
                 statements.append(&mut stmts);
                 argument
             })
             .collect();
 
+        // If the function is imported from another program, then qualify the call with the program name.
+        let external = match (input.external, input.function.as_ref()) {
+            (None, Expression::Identifier(function)) => self.call_targets.get(&function.name).map(|program| {
+                Box::new(Expression::Identifier(Identifier::new(*program, self.node_builder.next_id())))
+            }),
+            (external, _) => external,
+        };
+
         // Construct and accumulate a new assignment statement for the call expression.
         let (place, statement) = self.unique_simple_assign_statement(Expression::Call(CallExpression {
             // Note that we do not rename the function name.
             function: input.function,
             // Consume the arguments.
             arguments,
-            external: input.external,
+            external,
             span: input.span,
             id: input.id,
         }));
@@ -219,6 +313,8 @@ impl ExpressionConsumer for StaticSingleAssigner<'_> {
     }
 
     /// Consumes a struct initialization expression with renamed variables, accumulating any statements that are generated.
+    /// The members are evaluated in source order, but are reordered to match the declaration order of the struct,
+    /// which is the order used by the ternary expressions over structs produced by flattening.
     fn consume_struct_init(&mut self, input: StructExpression) -> Self::Output {
         let mut statements = Vec::new();
 
@@ -251,8 +347,14 @@ impl ExpressionConsumer for StaticSingleAssigner<'_> {
         // Reorder the members to match that of the struct definition.
 
         // Lookup the struct definition.
-        // Note that type checking guarantees that the correct struct definition exists.
-        let struct_definition: &Struct = self.symbol_table.lookup_struct(input.name.name).unwrap();
+        // Note that type checking guarantees that the correct struct definition exists, so a missing one is only reported for unchecked ASTs.
+        let struct_definition: &Struct = match self.symbol_table.lookup_struct(input.name.name) {
+            Some(struct_definition) => struct_definition,
+            None => {
+                self.emit_internal_err(format!("the struct `{}` is not defined", input.name), input.span);
+                return (Expression::Struct(StructExpression { members, ..input }), statements);
+            }
+        };
 
         // Initialize the list of reordered members.
         let mut reordered_members = Vec::with_capacity(members.len());
@@ -291,6 +393,13 @@ impl ExpressionConsumer for StaticSingleAssigner<'_> {
         (Expression::Identifier(place), statements)
     }
 
+    /// Reports an error expression, which should not be in the AST at this phase of compilation.
+    /// The expression is returned unchanged, so that the remaining statements are still processed.
+    fn consume_err(&mut self, input: ErrExpression) -> Self::Output {
+        self.emit_internal_err("error expressions should not be in the AST at this phase of compilation", input.span);
+        (Expression::Err(input), Vec::new())
+    }
+
     /// Produces a new `Identifier` with a unique name.
     fn consume_identifier(&mut self, identifier: Identifier) -> Self::Output {
         let name = match self.is_lhs {
@@ -301,10 +410,25 @@ impl ExpressionConsumer for StaticSingleAssigner<'_> {
                 new_name
             }
             // Otherwise, we look up the previous name in the `RenameTable`.
-            // Note that we do not panic if the identifier is not found in the rename table.
-            // Variables that do not exist in the rename table are ones that have been introduced during the SSA pass.
-            // These variables are never re-assigned, and will never have an entry in the rename-table.
-            false => *self.rename_table.lookup(identifier.name).unwrap_or(&identifier.name),
+            // If the identifier is not found, it keeps its name, and an error is reported unless the variable
+            // is one that does not need an entry in the rename table, e.g. a mapping.
+            // Note that the pass continues, so that every use of an undefined variable is reported.
+            false => {
+                let name = match self.rename_table.lookup(identifier.name) {
+                    Some(name) => *name,
+                    None => {
+                        if !self.is_defined_outside_rename_table(identifier.name) {
+                            self.handler.emit_err(CompilerError::undefined_variable(identifier.name, identifier.span));
+                        }
+                        identifier.name
+                    }
+                };
+                // If the variable is known to equal a literal, then use the literal instead.
+                if let Some(literal) = self.lookup_constant(name, identifier.span) {
+                    return (literal, Default::default());
+                }
+                name
+            }
         };
 
         (Expression::Identifier(Identifier { name, span: identifier.span, id: identifier.id }), Default::default())
@@ -319,17 +443,48 @@ impl ExpressionConsumer for StaticSingleAssigner<'_> {
 
     /// Consumes a ternary expression, accumulating any statements that are generated.
     fn consume_ternary(&mut self, input: TernaryExpression) -> Self::Output {
+        // Type checking ensures that both branches have the same type, so a length mismatch is reported.
+        // The ternary expression is replaced by its first branch, so that the pass can continue.
+        if let (Some(if_true_length), Some(if_false_length)) =
+            (self.tuple_length(&input.if_true), self.tuple_length(&input.if_false))
+        {
+            if if_true_length != if_false_length {
+                self.handler.emit_err(CompilerError::ternary_tuple_length_mismatch(
+                    if_true_length,
+                    if_false_length,
+                    input.if_true.span() + input.if_false.span(),
+                ));
+                return self.consume_expression(*input.if_true);
+            }
+        }
+
+        // A literal branch of a different type than the other branch is re-typed to the type of the other branch.
+        // Note that this is done before the branches are consumed, since consuming a literal assigns it to a variable.
+        let if_true = self.retype_literal_branch(*input.if_true, &input.if_false);
+        let if_false = self.retype_literal_branch(*input.if_false, &if_true);
+
         // Reconstruct the condition of the ternary expression.
         let (cond_expr, mut statements) = self.consume_expression(*input.condition);
         // Reconstruct the if-true case of the ternary expression.
-        let (if_true_expr, mut if_true_statements) = self.consume_expression(*input.if_true);
+        let (if_true_expr, mut if_true_statements) = self.consume_expression(if_true);
         // Reconstruct the if-false case of the ternary expression.
-        let (if_false_expr, mut if_false_statements) = self.consume_expression(*input.if_false);
+        let (if_false_expr, mut if_false_statements) = self.consume_expression(if_false);
 
         // Accumulate any statements produced.
         statements.append(&mut if_true_statements);
         statements.append(&mut if_false_statements);
 
+        // If constant propagation is enabled and both branches are the same value, the ternary expression is redundant.
+        // Note that the statements produced for the condition and branches are kept, since they may have side effects, e.g. calls.
+        // Those without side effects are removed by dead code elimination, if their results are unused.
+        if self.options.constant_propagation_enabled && Self::is_same_value(&if_true_expr, &if_false_expr) {
+            return (if_true_expr, statements);
+        }
+
+        // Note that flattening requires the branches to be variables, so propagated literals are assigned to variables.
+        let if_true_expr = self.assign_literal(if_true_expr, &mut statements);
+        let if_false_expr = self.assign_literal(if_false_expr, &mut statements);
+
         // Construct and accumulate a unique assignment statement storing the result of the ternary expression.
         let (place, statement) = self.unique_simple_assign_statement(Expression::Ternary(TernaryExpression {
             condition: Box::new(cond_expr),
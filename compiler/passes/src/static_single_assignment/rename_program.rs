@@ -64,6 +64,9 @@ impl FunctionConsumer for StaticSingleAssigner<'_> {
 
     /// Reconstructs the `Function`s in the `Program`, while allocating the appropriate `RenameTable`s.
     fn consume_function(&mut self, function: Function) -> Self::Output {
+        // Record the function, so that errors can be attributed to it.
+        self.function = Some(function.identifier.name);
+
         // Allocate a `RenameTable` for the function.
         self.push();
 
@@ -41,17 +41,28 @@ use leo_ast::{
     TupleExpression,
     Type,
 };
+use leo_errors::TypeCheckerError;
 use leo_span::Symbol;
 
 use indexmap::IndexSet;
-use itertools::Itertools;
 
 impl StatementConsumer for StaticSingleAssigner<'_> {
     type Output = Vec<Statement>;
 
     /// Consumes the expressions in an `AssertStatement`, returning the list of simplified statements.
     fn consume_assert(&mut self, input: AssertStatement) -> Self::Output {
-        let (variant, mut statements) = match input.variant {
+        // If enabled, rewrite assertions of inequalities into `assert_neq` statements.
+        let variant = match input.variant {
+            AssertVariant::Assert(expr) if self.options.assert_lowering_enabled => {
+                match Self::inequality_operands(expr) {
+                    Ok((left, right)) => AssertVariant::AssertNeq(left, right),
+                    Err(expr) => AssertVariant::Assert(expr),
+                }
+            }
+            variant => variant,
+        };
+
+        let (variant, mut statements) = match variant {
             AssertVariant::Assert(expr) => {
                 let (expr, statements) = self.consume_expression(expr);
                 (AssertVariant::Assert(expr), statements)
@@ -79,26 +90,39 @@ impl StatementConsumer for StaticSingleAssigner<'_> {
         };
 
         // Add the assert statement to the list of produced statements.
-        statements.push(Statement::Assert(AssertStatement { variant, span: input.span, id: input.id }));
+        statements.push(Statement::Assert(AssertStatement {
+            variant,
+            message: input.message,
+            span: input.span,
+            id: input.id,
+        }));
 
         statements
     }
 
     /// Consume all `AssignStatement`s, renaming as necessary.
+    /// Note that the parser desugars compound assignments, e.g. `x &= y` into `x = x & y`, so they need no special handling.
     fn consume_assign(&mut self, assign: AssignStatement) -> Self::Output {
         // First consume the right-hand-side of the assignment.
         let (value, mut statements) = self.consume_expression(assign.value);
 
         // Then assign a new unique name to the left-hand-side of the assignment.
         // Note that this order is necessary to ensure that the right-hand-side uses the correct name when consuming a complex assignment.
+        // Type checking guarantees that the left-hand-side is an identifier, so other places are only reported for unchecked ASTs.
         self.is_lhs = true;
         let place = match self.consume_expression(assign.place).0 {
             Expression::Identifier(identifier) => identifier,
-            _ => panic!("Type checking guarantees that the left-hand-side of an assignment is an identifier."),
+            place => {
+                self.is_lhs = false;
+                self.emit_internal_err("the left-hand side of an assignment is not an identifier", place.span());
+                return statements;
+            }
         };
         self.is_lhs = false;
 
-        statements.push(self.simple_assign_statement(place, value));
+        let mut statement = self.simple_assign_statement(place, value);
+        statement.set_span(assign.span);
+        statements.push(statement);
 
         statements
     }
@@ -136,19 +160,27 @@ impl StatementConsumer for StaticSingleAssigner<'_> {
         self.push();
 
         // Consume the otherwise-block and flatten its constituent statements into the current block.
-        let otherwise = conditional.otherwise.map(|otherwise| Box::new(Statement::Block(match *otherwise {
-            Statement::Block(block) => Block {
-                span: block.span,
-                id: block.id,
-                statements: self.consume_block(block),
-            },
-            Statement::Conditional(conditional) => Block {
-                span: conditional.span,
-                id: conditional.id,
-                statements: self.consume_conditional(conditional),
-            },
-            _ => unreachable!("Type checking guarantees that the otherwise-block of a conditional statement is a block or another conditional statement."),
-        })));
+        // Type checking guarantees that the otherwise-block is a block or another conditional statement,
+        // so other statements are only reported for unchecked ASTs, and replaced with an empty block.
+        let otherwise = conditional.otherwise.map(|otherwise| {
+            Box::new(Statement::Block(match *otherwise {
+                Statement::Block(block) => {
+                    Block { span: block.span, id: block.id, statements: self.consume_block(block) }
+                }
+                Statement::Conditional(conditional) => Block {
+                    span: conditional.span,
+                    id: conditional.id,
+                    statements: self.consume_conditional(conditional),
+                },
+                otherwise => {
+                    self.emit_internal_err(
+                        "the otherwise-block of a conditional statement is not a block",
+                        otherwise.span(),
+                    );
+                    Block { span: otherwise.span(), id: otherwise.id(), statements: Vec::new() }
+                }
+            }))
+        });
 
         // Remove the `RenameTable` for the otherwise-block.
         let else_table = self.pop();
@@ -163,8 +195,10 @@ impl StatementConsumer for StaticSingleAssigner<'_> {
         }));
 
         // Compute the write set for the variables written in the then-block or otherwise-block.
-        let if_write_set: IndexSet<&Symbol> = IndexSet::from_iter(if_table.local_names());
-        let else_write_set: IndexSet<&Symbol> = IndexSet::from_iter(else_table.local_names());
+        // Note that variables defined in a block are excluded, so that a variable shadowing one of the same name
+        // in an enclosing scope, e.g. a parameter, does not overwrite it when the blocks are merged.
+        let if_write_set: IndexSet<&Symbol> = IndexSet::from_iter(if_table.local_assignments());
+        let else_write_set: IndexSet<&Symbol> = IndexSet::from_iter(else_table.local_assignments());
         let write_set = if_write_set.union(&else_write_set);
 
         // For each variable in the write set, instantiate and add a phi function to the list of produced statements.
@@ -172,37 +206,48 @@ impl StatementConsumer for StaticSingleAssigner<'_> {
             // Note that phi functions only need to be instantiated if the variable exists before the `ConditionalStatement`.
             if self.rename_table.lookup(**symbol).is_some() {
                 // Helper to lookup an and create an argument for the phi function.
+                // Note that the popped tables do not have a parent, so names that a branch does not rename are resolved in the enclosing scope.
                 let create_phi_argument = |table: &RenameTable, symbol: Symbol| {
-                    let name =
-                        *table.lookup(symbol).unwrap_or_else(|| panic!("Symbol {symbol} should exist in the program."));
-                    let id = *table
-                        .lookup_id(&name)
-                        .unwrap_or_else(|| panic!("Symbol {name} should exist in the rename table."));
-                    Box::new(Expression::Identifier(Identifier { name, span: Default::default(), id }))
+                    let name = *table.lookup(symbol).or_else(|| self.rename_table.lookup(symbol))?;
+                    let id = *table.lookup_id(&name).or_else(|| self.rename_table.lookup_id(&name))?;
+                    Some(Box::new(Expression::Identifier(Identifier { name, span: Default::default(), id })))
+                };
+
+                // Create the arguments for the phi function.
+                // Note that the variable exists before the `ConditionalStatement`, so both arguments exist unless the AST is malformed.
+                let (if_true, if_false) =
+                    match (create_phi_argument(&if_table, **symbol), create_phi_argument(&else_table, **symbol)) {
+                        (Some(if_true), Some(if_false)) => (if_true, if_false),
+                        _ => {
+                            self.emit_internal_err(format!("`{symbol}` is not in the rename table"), conditional.span);
+                            continue;
+                        }
+                    };
+
+                // Get the type of the variable.
+                let type_ = match self.type_table.get(&if_true.id()) {
+                    Some(type_) => type_,
+                    None => {
+                        self.emit_internal_err(format!("`{symbol}` does not have a type"), conditional.span);
+                        continue;
+                    }
                 };
 
                 // Create a new name for the variable written to in the `ConditionalStatement`.
                 let new_name = self.assigner.unique_symbol(symbol, "$");
 
-                // Create the arguments for the phi function.
-                let if_true = create_phi_argument(&if_table, **symbol);
-                let if_false = create_phi_argument(&else_table, **symbol);
-
                 // Create a new node ID for the the phi function.
                 let id = self.node_builder.next_id();
                 // Update the type of the node ID.
-                let type_ = match self.type_table.get(&if_true.id()) {
-                    Some(type_) => type_,
-                    None => unreachable!("Type checking guarantees that all expressions have a type."),
-                };
                 self.type_table.insert(id, type_);
 
                 // Construct a ternary expression for the phi function.
+                // Note that the phi function is given the span of the conditional statement that introduced it.
                 let (value, stmts) = self.consume_ternary(TernaryExpression {
                     condition: Box::new(condition.clone()),
                     if_true,
                     if_false,
-                    span: Default::default(),
+                    span: conditional.span,
                     id,
                 });
 
@@ -212,7 +257,8 @@ impl StatementConsumer for StaticSingleAssigner<'_> {
                 let id = match self.rename_table.lookup_id(symbol) {
                     Some(id) => *id,
                     None => {
-                        unreachable!("The ID for the symbol `{}` should already exist in the rename table.", symbol)
+                        self.emit_internal_err(format!("`{symbol}` does not have an ID"), conditional.span);
+                        continue;
                     }
                 };
 
@@ -220,7 +266,7 @@ impl StatementConsumer for StaticSingleAssigner<'_> {
                 self.rename_table.update(*(*symbol), new_name, id);
 
                 // Create a new `AssignStatement` for the phi function.
-                let identifier = Identifier { name: new_name, span: Default::default(), id };
+                let identifier = Identifier { name: new_name, span: conditional.span, id };
                 let assignment = self.simple_assign_statement(identifier, value);
 
                 // Store the generated phi function.
@@ -231,87 +277,127 @@ impl StatementConsumer for StaticSingleAssigner<'_> {
         statements
     }
 
-    /// Parsing guarantees that console statements are not present in the program.
-    fn consume_console(&mut self, _: ConsoleStatement) -> Self::Output {
-        unreachable!("Parsing guarantees that console statements are not present in the program.")
+    /// Parsing guarantees that console statements are not present in the program, so they are only reported for unchecked ASTs.
+    fn consume_console(&mut self, input: ConsoleStatement) -> Self::Output {
+        self.emit_internal_err("console statements should not be in the program", input.span);
+        Vec::new()
     }
 
-    fn consume_const(&mut self, _: ConstDeclaration) -> Self::Output {
-        unreachable!("Loop unrolling pass removes all constant declaration statements in the program.")
+    /// The loop unrolling pass removes all constant declarations, so they are only reported if it was skipped.
+    fn consume_const(&mut self, input: ConstDeclaration) -> Self::Output {
+        self.emit_internal_err("constant declarations should be removed by loop unrolling", input.span);
+        Vec::new()
     }
 
     /// Consumes the `DefinitionStatement` into an `AssignStatement`, renaming the left-hand-side as appropriate.
     fn consume_definition(&mut self, definition: DefinitionStatement) -> Self::Output {
         // First consume the right-hand-side of the definition.
-        let (value, mut statements) = self.consume_expression(definition.value);
+        // A tuple expression bound to a tuple of names of the same length, e.g. `let (a, b) = (x, y);`, is consumed
+        // element-wise, so that each name can be bound to its element directly, rather than through an intermediate tuple.
+        let (value, mut statements) = match (&definition.place, definition.value) {
+            (Expression::Tuple(place), Expression::Tuple(value)) if place.elements.len() == value.elements.len() => {
+                let mut statements = Vec::new();
+                let elements = value
+                    .elements
+                    .into_iter()
+                    .map(|element| {
+                        let (element, mut stmts) = self.consume_expression(element);
+                        statements.append(&mut stmts);
+                        element
+                    })
+                    .collect();
+                (Expression::Tuple(TupleExpression { elements, span: value.span, id: value.id }), statements)
+            }
+            (_, value) => self.consume_expression(value),
+        };
 
         // Then assign a new unique name to the left-hand-side of the definition.
         // Note that this order is necessary to ensure that the right-hand-side uses the correct name when consuming a complex assignment.
         self.is_lhs = true;
         match definition.place {
             Expression::Identifier(identifier) => {
-                // Add the identifier to the rename table.
-                self.rename_table.update(identifier.name, identifier.name, identifier.id);
-                // Rename the identifier.
-                let identifier = match self.consume_identifier(identifier).0 {
-                    Expression::Identifier(identifier) => identifier,
-                    _ => unreachable!("`self.consume_identifier` will always return an `Identifier`."),
-                };
-                // Create a new assignment statement.
-                statements.push(self.simple_assign_statement(identifier, value));
+                let identifier = self.rename_definition(identifier);
+                // Create a new assignment statement, which is given the span of the definition.
+                let mut statement = self.simple_assign_statement(identifier, value);
+                statement.set_span(definition.span);
+                statements.push(statement);
             }
             Expression::Tuple(tuple) => {
-                let elements: Vec<Expression> = tuple.elements.into_iter().map(|element| {
+                // Rename each element of the lhs.
+                // Type checking guarantees that the elements are identifiers, so other elements, e.g. nested tuples,
+                // are only reported for unchecked ASTs.
+                let length = tuple.elements.len();
+                let mut elements = Vec::with_capacity(length);
+                for element in tuple.elements {
                     match element {
-                        Expression::Identifier(identifier) => {
-                            // Add the identifier to the rename table.
-                            self.rename_table.update(identifier.name, identifier.name, identifier.id);
-                            // Rename the identifier.
-                            let identifier = match self.consume_identifier(identifier).0 {
-                                Expression::Identifier(identifier) => identifier,
-                                _ => unreachable!("`self.consume_identifier` will always return an `Identifier`."),
-                            };
-                            // Return the renamed identifier.
-                            Expression::Identifier(identifier)
-                        }
-                        _ => unreachable!("Type checking guarantees that the tuple elements on the lhs of a `DefinitionStatement` are always be identifiers."),
+                        Expression::Identifier(identifier) => elements.push(self.rename_definition(identifier)),
+                        element => self
+                            .handler
+                            .emit_err(TypeCheckerError::lhs_tuple_element_must_be_an_identifier(element.span())),
                     }
-                }).collect();
-
-                // Get the type of `value`.
-                let tuple_type_ = match self.type_table.get(&value.id()) {
-                    Some(Type::Tuple(type_)) => type_,
-                    _ => unreachable!("Type checking guarantees that this expression is a tuple."),
-                };
-
-                // Update the type of each element in the tuple.
-                for (element, type_) in elements.iter().zip_eq(tuple_type_.elements()) {
-                    self.type_table.insert(element.id(), type_.clone());
                 }
 
-                // Construct the lhs of the assignment.
-                let place = Expression::Tuple(TupleExpression {
-                    elements,
-                    span: Default::default(),
-                    id: self.node_builder.next_id(),
-                });
-
-                // Update the type of the lhs.
-                self.type_table.insert(place.id(), Type::Tuple(tuple_type_));
-
-                // Create the assignment statement.
-                let assignment = Statement::Assign(Box::new(AssignStatement {
-                    place,
-                    value,
-                    span: definition.span,
-                    id: definition.id,
-                }));
-
-                statements.push(assignment);
+                match value {
+                    // If any element could not be renamed, then no assignment is produced.
+                    _ if elements.len() != length => {}
+                    // If the value is a tuple expression, then each name is bound to the corresponding element.
+                    Expression::Tuple(value) => {
+                        for (identifier, element) in elements.into_iter().zip(value.elements) {
+                            let mut statement = self.simple_assign_statement(identifier, element);
+                            statement.set_span(definition.span);
+                            statements.push(statement);
+                        }
+                    }
+                    // Otherwise, e.g. if the value is a call with multiple outputs, the names are bound by a single
+                    // assignment to a tuple, which code generation lowers into the destination registers of the call.
+                    value => {
+                        // Get the type of `value`.
+                        let tuple_type_ = match self.type_table.get(&value.id()) {
+                            Some(Type::Tuple(type_)) => type_,
+                            _ => {
+                                self.is_lhs = false;
+                                self.emit_internal_err(format!("`{value}` is not a tuple"), value.span());
+                                return statements;
+                            }
+                        };
+
+                        // Type checking guarantees that the number of names matches the length of the tuple,
+                        // so a mismatch is only reported for unchecked ASTs.
+                        if elements.len() != tuple_type_.length() {
+                            self.handler.emit_err(TypeCheckerError::incorrect_num_tuple_elements(
+                                elements.len(),
+                                tuple_type_.length(),
+                                definition.span,
+                            ));
+                        } else {
+                            // Update the type of each element in the tuple.
+                            for (element, type_) in elements.iter().zip(tuple_type_.elements()) {
+                                self.type_table.insert(element.id, type_.clone());
+                            }
+
+                            // Construct the lhs of the assignment.
+                            let place = Expression::Tuple(TupleExpression {
+                                elements: elements.into_iter().map(Expression::Identifier).collect(),
+                                span: Default::default(),
+                                id: self.node_builder.next_id(),
+                            });
+
+                            // Update the type of the lhs.
+                            self.type_table.insert(place.id(), Type::Tuple(tuple_type_));
+
+                            // Create the assignment statement.
+                            statements.push(Statement::Assign(Box::new(AssignStatement {
+                                place,
+                                value,
+                                span: definition.span,
+                                id: definition.id,
+                            })));
+                        }
+                    }
+                }
             }
-            _ => unreachable!(
-                "Type checking guarantees that the left-hand-side of a `DefinitionStatement` is an identifier or tuple."
-            ),
+            place => self
+                .emit_internal_err("the left-hand side of a definition is not an identifier or a tuple", place.span()),
         }
         self.is_lhs = false;
 
@@ -370,30 +456,37 @@ impl StatementConsumer for StaticSingleAssigner<'_> {
                 }))
             }
 
-            _ => unreachable!("Type checking guarantees that expression statements are always function calls."),
+            expression => {
+                self.emit_internal_err(format!("the expression statement `{expression}` is not a call"), input.span)
+            }
         }
 
         statements
     }
 
-    // TODO: Error message
-    fn consume_iteration(&mut self, _input: IterationStatement) -> Self::Output {
-        unreachable!("`IterationStatement`s should not be in the AST at this phase of compilation.");
+    /// The loop unrolling pass removes all iteration statements, so they are only reported if it was skipped.
+    fn consume_iteration(&mut self, input: IterationStatement) -> Self::Output {
+        self.emit_internal_err("iteration statements should be removed by loop unrolling", input.span);
+        Vec::new()
     }
 
     /// Reconstructs the expression associated with the return statement, returning a simplified `ReturnStatement`.
     /// Note that type checking guarantees that there is at most one `ReturnStatement` in a block.
     fn consume_return(&mut self, input: ReturnStatement) -> Self::Output {
         // Consume the return expression.
+        // Note that flattening requires the returned value to be a variable, so a propagated literal is assigned to one.
         let (expression, mut statements) = self.consume_expression(input.expression);
+        let expression = self.assign_literal(expression, &mut statements);
 
         // Consume the finalize arguments if they exist.
         // Process the arguments, accumulating any statements produced.
+        // Like returned values, the finalize arguments are folded by flattening, so propagated literals are assigned to variables.
         let finalize_args = input.finalize_arguments.map(|arguments| {
             arguments
                 .into_iter()
                 .map(|argument| {
-                    let (argument, stmts) = self.consume_expression(argument);
+                    let (argument, mut stmts) = self.consume_expression(argument);
+                    let argument = self.assign_literal(argument, &mut stmts);
                     statements.extend(stmts);
                     argument
                 })
@@ -16,9 +16,44 @@
 
 use crate::{Assigner, RenameTable, SymbolTable, TypeTable};
 
-use leo_ast::{Expression, Identifier, Node, NodeBuilder, Statement};
+use leo_ast::{
+    AccessExpression,
+    BinaryExpression,
+    BinaryOperation,
+    Expression,
+    ExpressionConsumer,
+    Function,
+    FunctionConsumer,
+    Identifier,
+    Literal,
+    MemberAccess,
+    Node,
+    NodeBuilder,
+    Program,
+    Statement,
+    Type,
+    UnaryExpression,
+    UnaryOperation,
+    Variant,
+};
+use leo_errors::{emitter::Handler, CompilerError};
+use leo_span::{sym, symbol::with_session_globals, Span, Symbol};
+
+use indexmap::IndexMap;
+use std::fmt::Display;
+
+/// The options that configure the static single assignment pass.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StaticSingleAssignerOptions {
+    /// Whether uses of variables that are known to equal a literal are replaced with the literal.
+    pub constant_propagation_enabled: bool,
+    /// Whether assertions of inequalities are rewritten into `assert_neq` statements.
+    pub assert_lowering_enabled: bool,
+}
 
 pub struct StaticSingleAssigner<'a> {
+    /// An error handler used for any errors found during the pass.
+    pub(crate) handler: &'a Handler,
     /// A counter used to generate unique node IDs.
     pub(crate) node_builder: &'a NodeBuilder,
     /// The `SymbolTable` of the program.
@@ -31,42 +66,240 @@ pub struct StaticSingleAssigner<'a> {
     pub(crate) is_lhs: bool,
     /// A struct used to construct (unique) assignment statements.
     pub(crate) assigner: &'a Assigner,
+    /// The options that configure the pass.
+    pub(crate) options: StaticSingleAssignerOptions,
+    /// A mapping from variables to the literals they are known to equal.
+    /// Since each variable is assigned exactly once, an entry remains valid for the rest of the function.
+    /// Note that a variable that is only constant in one branch of a conditional is merged into a new variable, which is not constant.
+    pub(crate) constants: IndexMap<Symbol, Literal>,
+    /// A mapping from the names of functions to the programs they are imported from.
+    /// Calls to these functions are rewritten into external calls, e.g. `foo()` is rewritten into `bar.aleo/foo()`.
+    pub(crate) call_targets: IndexMap<Symbol, Symbol>,
+    /// The temporaries introduced by the pass, in order, along with the spans of the expressions they were introduced for.
+    pub(crate) temporary_spans: Vec<(Symbol, Span)>,
+    /// A mapping from the variables assigned by the pass to the names of their struct or record types.
+    pub(crate) circuits: IndexMap<Symbol, Symbol>,
+    /// The name of the function that is being converted, if any.
+    pub(crate) function: Option<Symbol>,
 }
 
 impl<'a> StaticSingleAssigner<'a> {
     /// Initializes a new `StaticSingleAssigner` with an empty `RenameTable`.
-    pub(crate) fn new(
+    pub fn new(
+        handler: &'a Handler,
         node_builder: &'a NodeBuilder,
         symbol_table: &'a SymbolTable,
         type_table: &'a TypeTable,
         assigner: &'a Assigner,
+        options: StaticSingleAssignerOptions,
     ) -> Self {
-        Self { node_builder, symbol_table, type_table, rename_table: RenameTable::new(None), is_lhs: false, assigner }
+        Self {
+            handler,
+            node_builder,
+            symbol_table,
+            type_table,
+            rename_table: RenameTable::new(None),
+            is_lhs: false,
+            assigner,
+            options,
+            constants: IndexMap::new(),
+            call_targets: IndexMap::new(),
+            temporary_spans: Vec::new(),
+            circuits: IndexMap::new(),
+            function: None,
+        }
+    }
+
+    /// Sets the programs that functions are imported from, which is used to qualify calls to these functions.
+    /// Calls to functions without an entry are unchanged.
+    pub fn with_call_targets(mut self, call_targets: IndexMap<Symbol, Symbol>) -> Self {
+        self.call_targets = call_targets;
+        self
+    }
+
+    /// Returns the import resolution table of `program`, which maps each transition of an imported program to that program.
+    /// Note that only transitions are included, since they are the only functions that can be called across programs.
+    pub fn import_call_targets(program: &Program) -> IndexMap<Symbol, Symbol> {
+        program
+            .imports
+            .iter()
+            .flat_map(|(name, (import, _))| {
+                import
+                    .program_scopes
+                    .values()
+                    .flat_map(|scope| scope.functions.iter())
+                    .filter(|(_, function)| function.variant == Variant::Transition)
+                    .map(move |(function, _)| (*function, *name))
+            })
+            .collect()
+    }
+
+    /// Returns the temporaries introduced by the pass, along with the spans of the expressions they were introduced for.
+    /// This can be used to relate generated code to the source, e.g. for coverage tooling.
+    pub fn temporary_spans(&self) -> &[(Symbol, Span)] {
+        &self.temporary_spans
+    }
+
+    /// Returns the temporaries introduced by the pass, along with the line and column in the source, both starting from one,
+    /// at which the expressions they were introduced for start.
+    /// Synthesized expressions, e.g. those introduced by the pass itself, may not have a location in the source.
+    pub fn temporary_locations(&self) -> Vec<(Symbol, Option<(usize, usize)>)> {
+        self.temporary_spans
+            .iter()
+            .map(|(name, span)| {
+                let location = match span.is_dummy() {
+                    true => None,
+                    false => with_session_globals(|s| s.source_map.span_to_location(*span)),
+                };
+                (*name, location.map(|location| (location.line_start, location.col_start)))
+            })
+            .collect()
+    }
+
+    /// Returns a mapping from the variables assigned by the pass to the names of their struct or record types.
+    /// The mapping reflects the final state after reconstruction, so later passes can use it instead of walking the AST.
+    /// Note that function inputs are not assigned by the pass, and so are not included.
+    pub fn circuit_bindings(&self) -> &IndexMap<Symbol, Symbol> {
+        &self.circuits
+    }
+
+    /// Applies SSA to a single function, e.g. to incrementally recompile a function after it is edited.
+    /// The function is processed with a fresh `RenameTable`, and `temporary_spans` and `circuits` only contain its variables.
+    /// Note that references to other functions and structs are resolved through the shared `SymbolTable`.
+    pub fn reconstruct_single_function(&mut self, function: Function) -> Function {
+        self.rename_table = RenameTable::new(None);
+        self.constants.clear();
+        self.temporary_spans.clear();
+        self.circuits.clear();
+        self.consume_function(function)
+    }
+
+    /// Emits an error for a construct that the pass cannot convert, e.g. one that type checking should have rejected.
+    /// The offending construct is dropped or left unchanged, so that the remaining statements and functions are still processed
+    /// and all of their errors are reported at once.
+    pub(crate) fn emit_internal_err(&self, reason: impl Display, span: Span) {
+        let function = self.function.map(|function| function.to_string()).unwrap_or_default();
+        self.handler.emit_err(CompilerError::failed_to_apply_ssa(function, reason, span));
     }
 
     /// Pushes a new scope, setting the current scope as the new scope's parent.
     pub(crate) fn push(&mut self) {
-        let parent_table = core::mem::take(&mut self.rename_table);
-        self.rename_table = RenameTable::new(Some(Box::from(parent_table)));
+        self.rename_table.push_scope();
     }
 
     /// If the RenameTable has a parent, then `self.rename_table` is set to the parent, otherwise it is set to a default `RenameTable`.
     pub(crate) fn pop(&mut self) -> RenameTable {
-        let parent = self.rename_table.parent.clone().unwrap_or_default();
-        core::mem::replace(&mut self.rename_table, *parent)
+        self.rename_table.pop_scope()
     }
 
     pub(crate) fn simple_assign_statement(&mut self, identifier: Identifier, rhs: Expression) -> Statement {
         // Update the type table.
-        let type_ = match self.type_table.get(&rhs.id()) {
-            Some(type_) => type_,
-            None => unreachable!("Type checking guarantees that all expressions have a type."),
-        };
-        self.type_table.insert(identifier.id(), type_);
+        // Note that an error expression has no type, but has already been reported.
+        match (self.type_table.get(&rhs.id()), &rhs) {
+            (Some(type_), _) => {
+                // Record the struct or record type of the lhs, if any.
+                if let Type::Identifier(circuit) = &type_ {
+                    self.circuits.insert(identifier.name, circuit.name);
+                }
+                self.type_table.insert(identifier.id(), type_);
+            }
+            (None, Expression::Err(_)) => {}
+            (None, _) => self.emit_internal_err(format!("`{rhs}` does not have a type"), rhs.span()),
+        }
         // Update the rename table.
         self.rename_table.update(identifier.name, identifier.name, identifier.id);
-        // Construct the statement.
-        self.assigner.simple_assign_statement(identifier, rhs, self.node_builder.next_id())
+        // If the rhs is a literal or a variable known to equal a literal, then the lhs is known to equal it as well.
+        if self.options.constant_propagation_enabled {
+            let constant = match &rhs {
+                Expression::Literal(literal) => Some(literal.clone()),
+                Expression::Identifier(rhs) => self.constants.get(&rhs.name).cloned(),
+                _ => None,
+            };
+            if let Some(constant) = constant {
+                self.constants.insert(identifier.name, constant);
+            }
+        }
+        // Construct the statement, which is given the span of the rhs, so that it refers to the source of the value.
+        let span = rhs.span();
+        let mut statement = self.assigner.simple_assign_statement(identifier, rhs, self.node_builder.next_id());
+        statement.set_span(span);
+        statement
+    }
+
+    /// Returns `true` if the consumed expressions `first` and `second` are syntactically identical, ignoring node IDs and spans.
+    /// Since consumed operands are identifiers or literals, and each variable is assigned at most once,
+    /// identical expressions always evaluate to the same value.
+    pub(crate) fn is_same_value(first: &Expression, second: &Expression) -> bool {
+        match (first, second) {
+            (Expression::Identifier(first), Expression::Identifier(second)) => first.name == second.name,
+            // Note that the string representation of a literal includes its type, e.g. `1u8`.
+            (Expression::Literal(first), Expression::Literal(second)) => first.to_string() == second.to_string(),
+            _ => false,
+        }
+    }
+
+    /// Re-types the ternary branch `branch` to the integer type of the other branch `other`,
+    /// if `branch` is a literal of a different integer type, e.g. one left by constant folding.
+    /// Since AVM requires both branches of a ternary to have the same type, a literal that is out of the range of
+    /// the type of `other` is reported. Otherwise, `branch` is returned unchanged.
+    pub(crate) fn retype_literal_branch(&self, branch: Expression, other: &Expression) -> Expression {
+        match (&branch, self.type_table.get(&other.id())) {
+            (Expression::Literal(literal @ Literal::Integer(type_, ..)), Some(Type::Integer(other_type)))
+                if *type_ != other_type =>
+            {
+                match literal.with_integer_type(other_type) {
+                    Some(literal) => {
+                        self.type_table.insert(literal.id(), Type::Integer(other_type));
+                        Expression::Literal(literal)
+                    }
+                    None => {
+                        self.handler.emit_err(CompilerError::integer_literal_out_of_range(
+                            literal,
+                            other_type,
+                            literal.span(),
+                        ));
+                        branch
+                    }
+                }
+            }
+            _ => branch,
+        }
+    }
+
+    /// Consumes an operand of a binary expression or a call.
+    /// Associated constants, e.g. `group::GEN`, are used directly, since code generation accepts them as operands.
+    /// Elsewhere, e.g. in a return statement or a ternary branch, they are assigned to a new variable.
+    pub(crate) fn consume_operand(&mut self, input: Expression) -> (Expression, Vec<Statement>) {
+        match input {
+            constant @ Expression::Access(AccessExpression::AssociatedConstant(_)) => (constant, Vec::new()),
+            input => self.consume_expression(input),
+        }
+    }
+
+    /// Assigns `expression` to a new variable if it is a literal, e.g. one substituted by constant propagation.
+    /// This is necessary where flattening requires a variable, i.e. in return statements and the branches of ternary expressions.
+    /// The assignment is appended to `statements`. Otherwise, `expression` is returned unchanged.
+    pub(crate) fn assign_literal(&mut self, expression: Expression, statements: &mut Vec<Statement>) -> Expression {
+        match expression {
+            Expression::Literal(_) => {
+                let (place, statement) = self.unique_simple_assign_statement(expression);
+                statements.push(statement);
+                Expression::Identifier(place)
+            }
+            expression => expression,
+        }
+    }
+
+    /// Returns the literal that the variable `name` is known to equal, if any.
+    /// The literal is given a new node ID and the span of the use.
+    pub(crate) fn lookup_constant(&mut self, name: Symbol, span: Span) -> Option<Expression> {
+        let mut literal = self.constants.get(&name)?.clone();
+        let type_ = self.type_table.get(&literal.id())?;
+        let id = self.node_builder.next_id();
+        self.type_table.insert(id, type_);
+        literal.set_id(id);
+        literal.set_span(span);
+        Some(Expression::Literal(literal))
     }
 
     /// Constructs a simple assign statement for `expr` with a unique name.
@@ -76,12 +309,135 @@ impl<'a> StaticSingleAssigner<'a> {
         // Create a new variable for the expression.
         let name = self.assigner.unique_symbol("$var", "$");
 
-        // Create a new identifier for the variable.
-        let place = Identifier { name, span: Default::default(), id: self.node_builder.next_id() };
+        // Create a new identifier for the variable, which refers to the source of the expression.
+        let place = Identifier { name, span: expr.span(), id: self.node_builder.next_id() };
+
+        // Record the span of the expression that the variable was introduced for.
+        self.temporary_spans.push((name, expr.span()));
 
         // Construct the statement.
         let statement = self.simple_assign_statement(place, expr);
 
         (place, statement)
     }
+
+    /// Introduces a new unique name for a variable defined by a `DefinitionStatement`, returning the renamed identifier.
+    /// Note that this must only be called while consuming the left-hand side of the definition.
+    pub(crate) fn rename_definition(&mut self, identifier: Identifier) -> Identifier {
+        // Add the identifier to the rename table.
+        self.rename_table.define(identifier.name, identifier.name, identifier.id);
+        // Rename the identifier.
+        match self.consume_identifier(identifier).0 {
+            Expression::Identifier(identifier) => identifier,
+            _ => unreachable!("`self.consume_identifier` will always return an `Identifier`."),
+        }
+    }
+
+    /// Returns `true` if the variable `name` may be used without an entry in the `RenameTable`.
+    /// These are the variables named by the `Assigner`, which are never re-assigned,
+    /// the special variables `self` and `block`, and the mappings and constants of the program.
+    pub(crate) fn is_defined_outside_rename_table(&self, name: Symbol) -> bool {
+        name == sym::SelfLower
+            || name == sym::block
+            || self.assigner.is_unique_symbol(name)
+            || self.symbol_table.lookup_variable(name).is_some()
+    }
+
+    /// Returns the number of elements in `expression`, if it is a tuple expression or a variable of tuple type.
+    pub(crate) fn tuple_length(&self, expression: &Expression) -> Option<usize> {
+        match expression {
+            Expression::Tuple(tuple) => Some(tuple.elements.len()),
+            _ => match self.type_table.get(&expression.id()) {
+                Some(Type::Tuple(tuple_type)) => Some(tuple_type.length()),
+                _ => None,
+            },
+        }
+    }
+
+    /// If `expression` is of the form `a != b` or `!(a == b)`, returns the operands `a` and `b`.
+    /// Otherwise, returns the expression unchanged.
+    pub(crate) fn inequality_operands(expression: Expression) -> Result<(Expression, Expression), Expression> {
+        match expression {
+            Expression::Binary(BinaryExpression { left, right, op: BinaryOperation::Neq, .. }) => Ok((*left, *right)),
+            Expression::Unary(UnaryExpression { receiver, op: UnaryOperation::Not, span, id }) => match *receiver {
+                Expression::Binary(BinaryExpression { left, right, op: BinaryOperation::Eq, .. }) => {
+                    Ok((*left, *right))
+                }
+                receiver => Err(Expression::Unary(UnaryExpression {
+                    receiver: Box::new(receiver),
+                    op: UnaryOperation::Not,
+                    span,
+                    id,
+                })),
+            },
+            expression => Err(expression),
+        }
+    }
+
+    /// Folds an access into a struct expression, e.g. `Foo { a: x, b: y }.a`, into the initializer of the member, i.e. `x`.
+    /// A chain of accesses into nested struct expressions, e.g. `Foo { a: Bar { b: x } }.a.b`, collapses into a single value.
+    /// Returns an expression equivalent to the access, which has not been consumed yet, along with the statements produced
+    /// by consuming the other initializers, so that the statements they produce, e.g. calls, are kept.
+    /// If the access is not into a struct expression, it is returned unchanged.
+    pub(crate) fn fold_member_access(
+        &mut self,
+        access: MemberAccess,
+    ) -> Result<(Expression, Vec<Statement>), MemberAccess> {
+        let MemberAccess { inner, name, span, id } = access;
+        match *inner {
+            Expression::Struct(input) if input.members.iter().any(|member| member.identifier.name == name.name) => {
+                let mut statements = Vec::new();
+                let mut selected = None;
+                for member in input.members {
+                    let value = member.expression.unwrap_or(Expression::Identifier(member.identifier));
+                    match member.identifier.name == name.name {
+                        true => selected = Some(value),
+                        false => statements.append(&mut self.consume_expression(value).1),
+                    }
+                }
+                Ok((selected.expect("The member is guaranteed to exist."), statements))
+            }
+            Expression::Access(AccessExpression::Member(inner)) => match self.fold_member_access(inner) {
+                // The inner access was folded, so try to fold this access into its value.
+                Ok((value, mut statements)) => {
+                    match self.fold_member_access(MemberAccess { inner: Box::new(value), name, span, id }) {
+                        Ok((value, mut stmts)) => {
+                            statements.append(&mut stmts);
+                            Ok((value, statements))
+                        }
+                        Err(access) => Ok((Expression::Access(AccessExpression::Member(access)), statements)),
+                    }
+                }
+                Err(inner) => Err(MemberAccess {
+                    inner: Box::new(Expression::Access(AccessExpression::Member(inner))),
+                    name,
+                    span,
+                    id,
+                }),
+            },
+            inner => Err(MemberAccess { inner: Box::new(inner), name, span, id }),
+        }
+    }
+
+    /// Returns `true` if the statement contains an expression whose evaluation may have side effects, including failure.
+    /// These are calls, mapping updates, and mapping reads, which fail if the key is not present.
+    pub(crate) fn has_side_effects(statement: &Statement) -> bool {
+        match statement {
+            Statement::Assign(assign) => match &assign.value {
+                Expression::Call(_) => true,
+                Expression::Access(AccessExpression::AssociatedFunction(function)) => {
+                    matches!(&function.ty, Type::Identifier(Identifier { name: sym::Mapping, .. }))
+                        && function.name.name == sym::get
+                }
+                _ => false,
+            },
+            Statement::Block(block) => block.statements.iter().any(Self::has_side_effects),
+            Statement::Conditional(conditional) => {
+                conditional.then.statements.iter().any(Self::has_side_effects)
+                    || conditional.otherwise.as_ref().map_or(false, |otherwise| Self::has_side_effects(otherwise))
+            }
+            Statement::Expression(_) => true,
+            _ => false,
+        }
+    }
 }
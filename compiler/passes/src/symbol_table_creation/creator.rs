@@ -16,6 +16,7 @@
 
 use leo_ast::*;
 use leo_errors::emitter::Handler;
+use leo_span::Symbol;
 
 use crate::{SymbolTable, VariableSymbol, VariableType};
 
@@ -27,17 +28,44 @@ pub struct SymbolTableCreator<'a> {
     pub(crate) symbol_table: SymbolTable,
     /// The error handler.
     handler: &'a Handler,
+    /// Whether the calls in each function are recorded in the caller index of the symbol table.
+    caller_index_enabled: bool,
+    /// The name of the function being visited, if the caller index is enabled.
+    current_function: Option<Symbol>,
 }
 
 impl<'a> SymbolTableCreator<'a> {
     pub fn new(handler: &'a Handler) -> Self {
-        Self { symbol_table: Default::default(), handler }
+        Self { symbol_table: Default::default(), handler, caller_index_enabled: false, current_function: None }
+    }
+
+    /// Sets whether the calls in each function are recorded in the caller index of the symbol table.
+    /// Note that this requires visiting the body of each function, which is otherwise skipped.
+    pub fn with_caller_index(mut self, enabled: bool) -> Self {
+        self.caller_index_enabled = enabled;
+        self
     }
 }
 
 impl<'a> ExpressionVisitor<'a> for SymbolTableCreator<'a> {
     type AdditionalInput = ();
     type Output = ();
+
+    fn visit_call(&mut self, input: &'a CallExpression, additional: &Self::AdditionalInput) -> Self::Output {
+        // Record calls to local functions. Calls to external programs are not recorded.
+        if let (Some(caller), Expression::Identifier(callee), None) =
+            (self.current_function, &*input.function, &input.external)
+        {
+            self.symbol_table.insert_call(caller, callee.name);
+        }
+        input.arguments.iter().for_each(|argument| self.visit_expression(argument, additional));
+    }
+
+    fn visit_struct_init(&mut self, input: &'a StructExpression, additional: &Self::AdditionalInput) -> Self::Output {
+        input.members.iter().filter_map(|member| member.expression.as_ref()).for_each(|expression| {
+            self.visit_expression(expression, additional);
+        });
+    }
 }
 
 impl<'a> StatementVisitor<'a> for SymbolTableCreator<'a> {}
@@ -71,5 +99,15 @@ impl<'a> ProgramVisitor<'a> for SymbolTableCreator<'a> {
         if let Err(err) = self.symbol_table.insert_fn(input.name(), input) {
             self.handler.emit_err(err);
         }
+
+        // Record the calls in the function body and finalize block.
+        if self.caller_index_enabled {
+            self.current_function = Some(input.name());
+            self.visit_block(&input.block);
+            if let Some(finalize) = &input.finalize {
+                self.visit_block(&finalize.block);
+            }
+            self.current_function = None;
+        }
     }
 }
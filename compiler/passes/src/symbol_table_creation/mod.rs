@@ -35,3 +35,53 @@ impl<'a> Pass for SymbolTableCreator<'a> {
         Ok(visitor.symbol_table)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use leo_ast::NodeBuilder;
+    use leo_span::{symbol::create_session_if_not_set_then, Symbol};
+
+    #[test]
+    fn test_callers_of() {
+        create_session_if_not_set_then(|_| {
+            let handler = Handler::default();
+            let node_builder = NodeBuilder::default();
+            let program = "
+program test.aleo {
+    function helper(a: u8) -> u8 {
+        return a + 1u8;
+    }
+
+    function unused(a: u8) -> u8 {
+        return a;
+    }
+
+    transition first(a: u8) -> u8 {
+        return helper(a);
+    }
+
+    transition second(a: u8) -> u8 {
+        let b: u8 = helper(helper(a));
+        return b;
+    }
+}";
+            let ast = leo_parser::parse_ast(&handler, &node_builder, program, Default::default()).unwrap();
+            let callers_of = |symbol_table: &SymbolTable, name: &str| {
+                symbol_table.callers_of(Symbol::intern(name)).iter().map(Symbol::to_string).collect::<Vec<_>>()
+            };
+
+            // Each caller is recorded once, regardless of how many times it calls the function.
+            let mut creator = SymbolTableCreator::new(&handler).with_caller_index(true);
+            creator.visit_program(ast.as_repr());
+            assert_eq!(callers_of(&creator.symbol_table, "helper"), ["first", "second"]);
+            assert!(callers_of(&creator.symbol_table, "unused").is_empty());
+            assert!(callers_of(&creator.symbol_table, "first").is_empty());
+
+            // The caller index is not built by default.
+            let symbol_table = SymbolTableCreator::do_pass((&ast, &handler)).unwrap();
+            assert!(callers_of(&symbol_table, "helper").is_empty());
+        })
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{is_pure, TemporaryEliminator};
+
+use leo_ast::{
+    AssignStatement,
+    Block,
+    ConditionalStatement,
+    ConsoleStatement,
+    DefinitionStatement,
+    Expression,
+    ExpressionReconstructor,
+    IterationStatement,
+    Statement,
+    StatementReconstructor,
+};
+
+impl StatementReconstructor for TemporaryEliminator<'_> {
+    /// Eliminates an assignment statement if none of the assigned variables are live and its value is pure.
+    /// Otherwise, the variables used by the value become live.
+    fn reconstruct_assign(&mut self, input: AssignStatement) -> (Statement, Self::AdditionalOutput) {
+        // Note that a place that is neither an identifier nor a tuple of identifiers is conservatively treated as live.
+        let place_is_live = match &input.place {
+            Expression::Identifier(identifier) => self.live_variables.contains(&identifier.name),
+            Expression::Tuple(tuple) => tuple.elements.iter().any(|element| match element {
+                Expression::Identifier(identifier) => self.live_variables.contains(&identifier.name),
+                _ => true,
+            }),
+            _ => true,
+        };
+
+        // Calls, mapping operations, and random number generation may have side effects, so they are never eliminated.
+        match place_is_live || !is_pure(&input.value) {
+            true => (
+                Statement::Assign(Box::new(AssignStatement {
+                    place: input.place,
+                    value: self.reconstruct_expression(input.value).0,
+                    span: input.span,
+                    id: input.id,
+                })),
+                Default::default(),
+            ),
+            false => (Statement::dummy(Default::default(), self.node_builder.next_id()), Default::default()),
+        }
+    }
+
+    /// Reconstructs the statements inside a block in reverse, so that the uses of a variable precede its assignment.
+    fn reconstruct_block(&mut self, block: Block) -> (Block, Self::AdditionalOutput) {
+        let mut statements: Vec<Statement> =
+            block.statements.into_iter().rev().map(|statement| self.reconstruct_statement(statement).0).collect();
+        statements.reverse();
+
+        (Block { statements, span: block.span, id: block.id }, Default::default())
+    }
+
+    /// Reconstructs the branches of a conditional statement before its condition, as statements are visited in reverse.
+    /// Note that static single assignment ensures that a variable assigned in one branch is not used in the other.
+    fn reconstruct_conditional(&mut self, input: ConditionalStatement) -> (Statement, Self::AdditionalOutput) {
+        let otherwise = input.otherwise.map(|statement| Box::new(self.reconstruct_statement(*statement).0));
+        let then = self.reconstruct_block(input.then).0;
+        let condition = self.reconstruct_expression(input.condition).0;
+
+        (
+            Statement::Conditional(ConditionalStatement { condition, then, otherwise, span: input.span, id: input.id }),
+            Default::default(),
+        )
+    }
+
+    /// Parsing guarantees that console statements are not present in the program.
+    fn reconstruct_console(&mut self, _: ConsoleStatement) -> (Statement, Self::AdditionalOutput) {
+        unreachable!("`ConsoleStatement`s should not be in the AST at this phase of compilation.")
+    }
+
+    /// Static single assignment replaces definition statements with assignment statements.
+    fn reconstruct_definition(&mut self, _: DefinitionStatement) -> (Statement, Self::AdditionalOutput) {
+        unreachable!("`DefinitionStatement`s should not exist in the AST at this phase of compilation.")
+    }
+
+    /// Loop unrolling unrolls and removes iteration statements from the program.
+    fn reconstruct_iteration(&mut self, _: IterationStatement) -> (Statement, Self::AdditionalOutput) {
+        unreachable!("`IterationStatement`s should not be in the AST at this phase of compilation.");
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! The Temporary Elimination pass traverses the AST and removes the assignments to variables that are never used,
+//! e.g. the temporaries introduced by static single assignment that are left unused after flattening.
+//! The pass is run after the Common Subexpression Elimination pass and before the Dead Code Elimination pass.
+//!
+//! The pass computes the variables that are live at each statement of a `transition`, `function`, or finalize block,
+//! by visiting the statements in reverse.
+//! An assignment is removed only if none of the variables it assigns are live and its value is pure.
+//!
+//! Consider the following flattened Leo code.
+//! ```leo
+//! function main(a: u8, b: u8) -> u8 {
+//!     $var$0 = a + b;
+//!     $var$1 = foo(a);
+//!     $var$2 = a * b;
+//!     $var$3 = $var$2 - b;
+//!     return $var$2;
+//! }
+//! ```
+//!
+//! The temporary elimination pass produces the following code.
+//! ```leo
+//! function main(a: u8, b: u8) -> u8 {
+//!     $var$1 = foo(a);
+//!     $var$2 = a * b;
+//!     return $var$2;
+//! }
+//! ```
+//! Note that the pass is conservative about side effects. Calls, mapping operations, and random number generation
+//! are never removed, and neither are assertions or other statements that are not assignments.
+//!
+//! Note this pass relies on the following invariants:
+//! - Unique variable names (provided by SSA)
+//! - No loops (provided by loop unrolling)
+
+mod eliminate_expression;
+
+mod eliminate_statement;
+
+mod eliminate_program;
+
+pub mod temporary_eliminator;
+pub use temporary_eliminator::*;
+
+use crate::Pass;
+
+use leo_ast::{Ast, NodeBuilder, ProgramReconstructor};
+use leo_errors::Result;
+
+impl<'a> Pass for TemporaryEliminator<'a> {
+    type Input = (Ast, &'a NodeBuilder);
+    type Output = Result<Ast>;
+
+    fn do_pass((ast, node_builder): Self::Input) -> Self::Output {
+        let mut reconstructor = TemporaryEliminator::new(node_builder);
+        let program = reconstructor.reconstruct_program(ast.into_repr());
+
+        Ok(Ast::new(program))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_helpers::TestCompiler, SingleAssignmentVerifier};
+
+    use leo_span::symbol::create_session_if_not_set_then;
+
+    const PROGRAM: &str = "
+program test.aleo {
+    mapping balances: address => u8;
+
+    function helper(a: u8) -> u8 {
+        assert(a != 0u8);
+        return a;
+    }
+
+    transition main(flag: bool, a: u8, b: u8) -> u8 {
+        let unused_call: u8 = helper(a);
+        let unused_sum: u8 = a + b;
+        let unused_chain: u8 = unused_sum * b;
+        let product: u8 = a * b;
+        if (flag) {
+            let unused_difference: u8 = a - b;
+            product = product + a;
+        }
+        assert(b != 0u8);
+        return product then finalize(self.caller, a);
+    }
+
+    finalize main(owner: address, amount: u8) {
+        let unused_balance: u8 = Mapping::get(balances, owner);
+        let unused_amount: u8 = amount + amount;
+        Mapping::set(balances, owner, amount);
+    }
+}";
+
+    /// Returns the functions of the program, including their finalize blocks, as strings.
+    fn functions(ast: &Ast) -> Vec<String> {
+        let scope = ast.as_repr().program_scopes.values().next().unwrap();
+        scope.functions.iter().map(|(_, function)| function.to_string()).collect()
+    }
+
+    #[test]
+    fn test_eliminate_unused_temporaries() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+            let (ast, symbol_table, ..) = compiler.type_check(PROGRAM);
+            let ast = compiler.static_single_assignment(ast, &symbol_table);
+            let before = &functions(&ast)[1];
+            for name in ["unused_sum", "unused_chain", "unused_difference", "unused_amount"] {
+                assert!(before.contains(name), "{before}");
+            }
+
+            // The pass handles conditional statements, so it can also be run before flattening.
+            let ast = TemporaryEliminator::do_pass((ast, &compiler.node_builder)).unwrap();
+            let after = functions(&ast);
+            let main = &after[1];
+
+            // The unused variables with pure values are removed, including the chain of assignments that only they use.
+            for name in ["unused_sum", "unused_chain", "unused_difference", "unused_amount"] {
+                assert!(!main.contains(name), "{main}");
+            }
+
+            // The unused call and mapping operation, the assertion, and the returned product are preserved.
+            assert!(main.contains("unused_call"), "{main}");
+            assert!(main.contains("unused_balance"), "{main}");
+            assert!(main.contains("Mapping::set"), "{main}");
+            assert!(main.contains("b != 0u8"), "{main}");
+            assert!(main.contains("a * b"), "{main}");
+            assert!(main.contains(" + a"), "{main}");
+
+            // The assertion in the helper is preserved.
+            assert!(after[0].contains("a != 0u8"), "{}", after[0]);
+
+            // The output is still in single assignment form.
+            assert!(SingleAssignmentVerifier::do_pass((&ast, &compiler.handler)).is_ok());
+        })
+    }
+
+    #[test]
+    fn test_eliminate_flattened_temporaries() {
+        create_session_if_not_set_then(|_| {
+            let compiler = TestCompiler::default();
+            let (ast, symbol_table, ..) = compiler.type_check(PROGRAM);
+            let ast = compiler.static_single_assignment(ast, &symbol_table);
+            let ast = compiler.flatten(ast, &symbol_table);
+
+            let ast = TemporaryEliminator::do_pass((ast, &compiler.node_builder)).unwrap();
+            let after = functions(&ast);
+            let main = &after[1];
+
+            // After flattening, the assignments of the conditional are unconditional, and are removed in the same way.
+            for name in ["unused_sum", "unused_chain", "unused_difference", "unused_amount"] {
+                assert!(!main.contains(name), "{main}");
+            }
+            assert!(main.contains("unused_call"), "{main}");
+            assert!(main.contains("unused_balance"), "{main}");
+            assert!(main.contains("flag ? "), "{main}");
+
+            // Eliminating the temporaries again has no effect.
+            let again = TemporaryEliminator::do_pass((ast, &compiler.node_builder)).unwrap();
+            assert_eq!(functions(&again), after);
+        })
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Helpers shared by the unit tests of the compiler passes.
+
+use crate::{
+    Assigner,
+    CallGraph,
+    Flattener,
+    Pass,
+    StaticSingleAssigner,
+    StaticSingleAssignerOptions,
+    StructGraph,
+    SymbolTable,
+    SymbolTableCreator,
+    TypeChecker,
+    TypeTable,
+    Unroller,
+    DEFAULT_MAX_LOOP_ITERATIONS,
+};
+
+use leo_ast::{Ast, NodeBuilder};
+use leo_errors::emitter::{BufferEmitter, Handler};
+use leo_span::{source_map::FileName, symbol::with_session_globals};
+
+/// The state that the compiler threads through its passes.
+/// Note that the errors emitted to the handler are buffered, rather than printed.
+pub(crate) struct TestCompiler {
+    /// The error handler.
+    pub(crate) handler: Handler,
+    /// The buffer holding the errors and warnings emitted to the handler.
+    pub(crate) buf: BufferEmitter,
+    /// A counter used to generate unique node IDs.
+    pub(crate) node_builder: NodeBuilder,
+    /// A mapping between node IDs and their types.
+    pub(crate) type_table: TypeTable,
+    /// A struct used to construct (unique) assignment statements.
+    pub(crate) assigner: Assigner,
+}
+
+impl Default for TestCompiler {
+    fn default() -> Self {
+        let (handler, buf) = Handler::new_with_buf();
+        Self {
+            handler,
+            buf,
+            node_builder: NodeBuilder::default(),
+            type_table: TypeTable::default(),
+            assigner: Assigner::default(),
+        }
+    }
+}
+
+impl TestCompiler {
+    /// Parses `source`, registering it in the source map so that spans can be resolved to the source.
+    pub(crate) fn parse(&self, source: &str) -> Ast {
+        let source = with_session_globals(|s| s.source_map.new_source(source, FileName::Custom("test".into())));
+        leo_parser::parse_ast(&self.handler, &self.node_builder, &source.src, source.start_pos).unwrap()
+    }
+
+    /// Parses and type checks `source`, returning the AST along with its symbol table, struct graph, and call graph.
+    pub(crate) fn type_check(&self, source: &str) -> (Ast, SymbolTable, StructGraph, CallGraph) {
+        let ast = self.parse(source);
+        let (symbol_table, struct_graph, call_graph) = self.type_check_ast(&ast);
+        (ast, symbol_table, struct_graph, call_graph)
+    }
+
+    /// Type checks `ast`, returning its symbol table, struct graph, and call graph.
+    pub(crate) fn type_check_ast(&self, ast: &Ast) -> (SymbolTable, StructGraph, CallGraph) {
+        let symbol_table = SymbolTableCreator::do_pass((ast, &self.handler)).unwrap();
+        TypeChecker::do_pass((ast, &self.handler, symbol_table, &self.type_table)).unwrap()
+    }
+
+    /// Unrolls the loops in `ast`, with the default maximum number of iterations.
+    pub(crate) fn unroll(&self, ast: Ast, symbol_table: SymbolTable) -> (Ast, SymbolTable) {
+        Unroller::do_pass((
+            ast,
+            &self.handler,
+            &self.node_builder,
+            symbol_table,
+            &self.type_table,
+            DEFAULT_MAX_LOOP_ITERATIONS,
+        ))
+        .unwrap()
+    }
+
+    /// Returns a `StaticSingleAssigner` with the default options.
+    pub(crate) fn static_single_assigner<'a>(&'a self, symbol_table: &'a SymbolTable) -> StaticSingleAssigner<'a> {
+        StaticSingleAssigner::new(
+            &self.handler,
+            &self.node_builder,
+            symbol_table,
+            &self.type_table,
+            &self.assigner,
+            Default::default(),
+        )
+    }
+
+    /// Converts `ast` into static single assignment form with the default options.
+    pub(crate) fn static_single_assignment(&self, ast: Ast, symbol_table: &SymbolTable) -> Ast {
+        self.static_single_assignment_with_options(ast, symbol_table, Default::default())
+    }
+
+    /// Converts `ast` into static single assignment form with the given options.
+    pub(crate) fn static_single_assignment_with_options(
+        &self,
+        ast: Ast,
+        symbol_table: &SymbolTable,
+        options: StaticSingleAssignerOptions,
+    ) -> Ast {
+        let (ast, _) = StaticSingleAssigner::do_pass((
+            ast,
+            &self.handler,
+            &self.node_builder,
+            &self.assigner,
+            symbol_table,
+            &self.type_table,
+            options,
+        ))
+        .unwrap();
+        ast
+    }
+
+    /// Returns a `Flattener` with the default options.
+    pub(crate) fn flattener<'a>(&'a self, symbol_table: &'a SymbolTable) -> Flattener<'a> {
+        Flattener::new(symbol_table, &self.type_table, &self.handler, &self.node_builder, &self.assigner)
+    }
+
+    /// Flattens `ast` with the default options.
+    pub(crate) fn flatten(&self, ast: Ast, symbol_table: &SymbolTable) -> Ast {
+        Flattener::do_pass((
+            ast,
+            &self.handler,
+            symbol_table,
+            &self.type_table,
+            &self.node_builder,
+            &self.assigner,
+            Default::default(),
+        ))
+        .unwrap()
+    }
+
+    /// Returns the messages of the errors emitted so far, removing them from the buffer.
+    pub(crate) fn errors(&self) -> Vec<String> {
+        self.buf.extract_errs().into_inner().into_iter().map(|error| error.to_string()).collect()
+    }
+}
@@ -231,6 +231,16 @@ impl<'a> ExpressionVisitor<'a> for TypeChecker<'a> {
                                     self.emit_err(TypeCheckerError::undefined_type(&access.inner, access.inner.span()));
                                 }
                             }
+                            // The coordinates of a group element are fields.
+                            Some(Type::Group) => match access.name.name {
+                                sym::x | sym::y => {
+                                    return Some(self.assert_and_return_type(Type::Field, expected, access.span()));
+                                }
+                                _ => self.emit_err(TypeCheckerError::invalid_group_coordinate(
+                                    access.name,
+                                    access.name.span(),
+                                )),
+                            },
                             Some(type_) => {
                                 self.emit_err(TypeCheckerError::type_should_be(type_, "struct", access.inner.span()));
                             }
@@ -307,6 +317,8 @@ impl<'a> ExpressionVisitor<'a> for TypeChecker<'a> {
     }
 
     fn visit_binary(&mut self, input: &'a BinaryExpression, destination: &Self::AdditionalInput) -> Self::Output {
+        self.check_division_by_zero(input);
+
         match input.op {
             BinaryOperation::And | BinaryOperation::Or | BinaryOperation::Nand | BinaryOperation::Nor => {
                 // Only boolean types.
@@ -579,8 +591,12 @@ impl<'a> ExpressionVisitor<'a> for TypeChecker<'a> {
                             }
                         }
                         // If the function is a transition function, then check that the call is not to another local transition function.
+                        // Note that a call to an imported transition is not local, even if it is not qualified with its program.
                         Variant::Transition => {
-                            if matches!(func.variant, Variant::Transition) && input.external.is_none() {
+                            if matches!(func.variant, Variant::Transition)
+                                && input.external.is_none()
+                                && !self.imported_transitions.contains(&ident.name)
+                            {
                                 self.emit_err(TypeCheckerError::cannot_invoke_call_to_local_transition_function(
                                     input.span,
                                 ));
@@ -37,6 +37,17 @@ impl<'a> ProgramVisitor<'a> for TypeChecker<'a> {
             }
             // Otherwise, typecheck the imported programs.
             false => {
+                // Record the transitions of the imported programs.
+                // Calls to these transitions are qualified with their program by static single assignment.
+                self.imported_transitions = input
+                    .imports
+                    .values()
+                    .flat_map(|(import, _)| import.program_scopes.values())
+                    .flat_map(|scope| scope.functions.iter())
+                    .filter(|(_, function)| function.variant == Variant::Transition)
+                    .map(|(name, _)| *name)
+                    .collect();
+
                 // Set `self.is_imported`.
                 let previous_is_imported = core::mem::replace(&mut self.is_imported, true);
 
@@ -205,10 +216,12 @@ impl<'a> ProgramVisitor<'a> for TypeChecker<'a> {
 
     fn visit_function(&mut self, function: &'a Function) {
         // Check that the function's annotations are valid.
-        // Note that Leo does not natively support any specific annotations.
+        // Note that the only annotation supported by Leo is `@noflatten`.
         for annotation in function.annotations.iter() {
-            // TODO: Change to compiler warning.
-            self.emit_err(TypeCheckerError::unknown_annotation(annotation, annotation.span))
+            if annotation.identifier.name != sym::noflatten {
+                // TODO: Change to compiler warning.
+                self.emit_err(TypeCheckerError::unknown_annotation(annotation, annotation.span))
+            }
         }
 
         self.variant = Some(function.variant);
@@ -16,12 +16,26 @@
 
 use crate::{CallGraph, StructGraph, SymbolTable, TypeTable};
 
-use leo_ast::{CoreConstant, CoreFunction, Identifier, IntegerType, MappingType, Node, Type, Variant};
+use leo_ast::{
+    BinaryExpression,
+    BinaryOperation,
+    CoreConstant,
+    CoreFunction,
+    Expression,
+    Identifier,
+    IntegerType,
+    Literal,
+    MappingType,
+    Node,
+    Type,
+    Variant,
+};
 use leo_errors::{emitter::Handler, TypeCheckerError};
 use leo_span::{Span, Symbol};
 
 use snarkvm::console::network::{Network, Testnet3};
 
+use indexmap::IndexSet;
 use itertools::Itertools;
 use std::cell::RefCell;
 
@@ -51,6 +65,8 @@ pub struct TypeChecker<'a> {
     pub(crate) is_imported: bool,
     /// Whether or not we are currently traversing a return statement.
     pub(crate) is_return: bool,
+    /// The transitions of the imported programs, which may be called without qualifying them with their program.
+    pub(crate) imported_transitions: IndexSet<Symbol>,
 }
 
 const ADDRESS_TYPE: Type = Type::Address;
@@ -118,6 +134,7 @@ impl<'a> TypeChecker<'a> {
             is_finalize: false,
             is_imported: false,
             is_return: false,
+            imported_transitions: IndexSet::new(),
         }
     }
 
@@ -172,6 +189,28 @@ impl<'a> TypeChecker<'a> {
         }
     }
 
+    /// Emits an error if `input` divides by a literal zero, which always fails.
+    /// Note that divisors that are only known to be zero after constants are propagated are not reported.
+    pub(crate) fn check_division_by_zero(&self, input: &BinaryExpression) {
+        let is_division = matches!(
+            input.op,
+            BinaryOperation::Div
+                | BinaryOperation::DivWrapped
+                | BinaryOperation::Rem
+                | BinaryOperation::RemWrapped
+                | BinaryOperation::Mod
+        );
+        let is_zero = match &*input.right {
+            Expression::Literal(Literal::Integer(_, value, ..) | Literal::Field(value, ..)) => {
+                value.chars().all(|c| c == '0' || c == '_')
+            }
+            _ => false,
+        };
+        if is_division && is_zero {
+            self.emit_err(TypeCheckerError::division_by_zero(input.op, input.right.span()));
+        }
+    }
+
     /// Use this method when you know the actual type.
     /// Emits an error to the handler if the `actual` type is not equal to the `expected` type.
     pub(crate) fn assert_and_return_type(&self, actual: Type, expected: &Option<Type>, span: Span) -> Type {
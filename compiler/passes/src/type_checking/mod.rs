@@ -44,3 +44,85 @@ impl<'a> Pass for TypeChecker<'a> {
         Ok((visitor.symbol_table.take(), visitor.struct_graph, visitor.call_graph))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SymbolTableCreator;
+
+    use leo_ast::NodeBuilder;
+    use leo_span::symbol::create_session_if_not_set_then;
+
+    /// Parses and type checks the given program, returning the last error, if any.
+    fn type_check(program: &str) -> Option<String> {
+        let (handler, buf) = Handler::new_with_buf();
+        let node_builder = NodeBuilder::default();
+        let type_table = TypeTable::default();
+
+        let ast = leo_parser::parse_ast(&handler, &node_builder, program, Default::default()).unwrap();
+        let symbol_table = SymbolTableCreator::do_pass((&ast, &handler)).unwrap();
+        TypeChecker::do_pass((&ast, &handler, symbol_table, &type_table)).err()?;
+        buf.extract_errs().last_entry().map(|error| error.to_string())
+    }
+
+    #[test]
+    fn test_direct_recursion() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    inline foo(n: u8) -> u8 {
+        return foo(n);
+    }
+
+    transition main(n: u8) -> u8 {
+        return foo(n);
+    }
+}";
+            let error = type_check(program).unwrap();
+            assert!(error.contains("Cyclic dependency between functions: `foo` --> `foo`"));
+        })
+    }
+
+    #[test]
+    fn test_mutual_recursion() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    inline foo(n: u8) -> u8 {
+        return bar(n);
+    }
+
+    inline bar(n: u8) -> u8 {
+        return foo(n);
+    }
+
+    transition main(n: u8) -> u8 {
+        return foo(n);
+    }
+}";
+            let error = type_check(program).unwrap();
+            assert!(error.contains("Cyclic dependency between functions: `foo` --> `bar` --> `foo`"));
+        })
+    }
+
+    #[test]
+    fn test_no_recursion() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    inline foo(n: u8) -> u8 {
+        return bar(n) + bar(n);
+    }
+
+    inline bar(n: u8) -> u8 {
+        return n;
+    }
+
+    transition main(n: u8) -> u8 {
+        return foo(n) + bar(n);
+    }
+}";
+            assert_eq!(type_check(program), None);
+        })
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::SymbolTable;
+
+use leo_ast::{
+    Block,
+    ExpressionVisitor,
+    Function,
+    Identifier,
+    Input,
+    Program,
+    ProgramVisitor,
+    StatementVisitor,
+    StructExpression,
+};
+use leo_errors::{emitter::Handler, TypeCheckerWarning};
+use leo_span::Symbol;
+
+use indexmap::IndexSet;
+
+/// A compiler pass that warns about function inputs that are never used.
+pub struct UnusedInputDetector<'a> {
+    /// The error handler.
+    handler: &'a Handler,
+    /// The symbol table, which contains the inputs of each function and finalize block.
+    symbol_table: &'a SymbolTable,
+    /// The variables referenced in the current body.
+    used: IndexSet<Symbol>,
+}
+
+impl<'a> UnusedInputDetector<'a> {
+    /// Initializes a new `UnusedInputDetector`.
+    pub fn new(handler: &'a Handler, symbol_table: &'a SymbolTable) -> Self {
+        Self { handler, symbol_table, used: Default::default() }
+    }
+
+    /// Visits `block` and emits a warning for each input that is not referenced in it.
+    fn check_inputs(&mut self, inputs: &[Input], block: &'a Block) {
+        self.used.clear();
+        self.visit_block(block);
+
+        inputs.iter().map(|input| input.identifier()).filter(|input| !self.used.contains(&input.name)).for_each(
+            |input| self.handler.emit_warning(TypeCheckerWarning::unused_input(input.name, input.span).into()),
+        );
+    }
+}
+
+impl<'a> ExpressionVisitor<'a> for UnusedInputDetector<'a> {
+    type AdditionalInput = ();
+    type Output = ();
+
+    fn visit_identifier(&mut self, input: &'a Identifier, _additional: &Self::AdditionalInput) -> Self::Output {
+        self.used.insert(input.name);
+    }
+
+    fn visit_struct_init(&mut self, input: &'a StructExpression, additional: &Self::AdditionalInput) -> Self::Output {
+        input.members.iter().for_each(|member| match &member.expression {
+            Some(expression) => self.visit_expression(expression, additional),
+            // A member without an expression is initialized with the variable of the same name.
+            None => self.visit_identifier(&member.identifier, additional),
+        });
+    }
+}
+
+impl<'a> StatementVisitor<'a> for UnusedInputDetector<'a> {}
+
+impl<'a> ProgramVisitor<'a> for UnusedInputDetector<'a> {
+    // Note that imported programs are not checked.
+    fn visit_import(&mut self, _input: &'a Program) {}
+
+    fn visit_function(&mut self, input: &'a Function) {
+        // Note that this unwrap is safe since function metadata is stored in a prior pass.
+        let function = self.symbol_table.lookup_fn_symbol(input.identifier.name).unwrap();
+
+        self.check_inputs(&function.input, &input.block);
+
+        if let (Some(finalize_data), Some(finalize)) = (&function.finalize, &input.finalize) {
+            self.check_inputs(&finalize_data.input, &finalize.block);
+        }
+    }
+}
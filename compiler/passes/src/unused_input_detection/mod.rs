@@ -0,0 +1,113 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! The Unused Input Detection pass warns about function inputs that are never used.
+//! The inputs of each function and finalize block are looked up in the symbol table and compared against
+//! the variables referenced in the corresponding body.
+//! The pass is run on the type checked AST, so uses in branches that are later removed by the compiler are still counted.
+//! The pass only emits warnings and is only run if enabled in the build options.
+//!
+//! Consider the following Leo code.
+//! ```leo
+//! transition main(a: u8, b: u8) -> u8 {
+//!     return a + 1u8;
+//! }
+//! ```
+//!
+//! The unused input detection pass emits a warning for the input `b`.
+
+pub mod detector;
+pub use detector::*;
+
+use crate::{Pass, SymbolTable};
+
+use leo_ast::{Ast, ProgramVisitor};
+use leo_errors::{emitter::Handler, Result};
+
+impl<'a> Pass for UnusedInputDetector<'a> {
+    type Input = (&'a Ast, &'a Handler, &'a SymbolTable);
+    type Output = Result<()>;
+
+    fn do_pass((ast, handler, symbol_table): Self::Input) -> Self::Output {
+        let mut visitor = UnusedInputDetector::new(handler, symbol_table);
+        visitor.visit_program(ast.as_repr());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::TestCompiler;
+
+    use leo_span::symbol::create_session_if_not_set_then;
+
+    /// Parses and type checks the given program, then returns the warnings emitted by the pass.
+    fn unused_input_warnings(program: &str) -> Vec<String> {
+        let compiler = TestCompiler::default();
+        let (ast, symbol_table, ..) = compiler.type_check(program);
+        UnusedInputDetector::do_pass((&ast, &compiler.handler, &symbol_table)).unwrap();
+
+        assert_eq!(compiler.handler.err_count(), 0);
+        compiler.buf.extract_warnings().into_inner().iter().map(|warning| warning.to_string()).collect()
+    }
+
+    #[test]
+    fn test_unused_input() {
+        create_session_if_not_set_then(|_| {
+            let program = "
+program test.aleo {
+    mapping balances: address => u64;
+
+    transition main(a: u8, b: u8) -> u8 {
+        return a + 1u8 then finalize(self.caller, 1u64);
+    }
+
+    finalize main(owner: address, amount: u64) {
+        Mapping::set(balances, owner, 0u64);
+    }
+}";
+            let warnings = unused_input_warnings(program);
+            assert_eq!(warnings.len(), 2);
+            assert!(warnings[0].contains("The input `b` is never used."));
+            assert!(warnings[1].contains("The input `amount` is never used."));
+        })
+    }
+
+    #[test]
+    fn test_used_input() {
+        create_session_if_not_set_then(|_| {
+            // Note that `b` is only used in a branch that is removed by later passes.
+            let program = "
+program test.aleo {
+    struct Point {
+        x: u8,
+        y: u8,
+    }
+
+    transition main(a: u8, b: u8, y: u8) -> Point {
+        let x: u8 = a;
+        if (false) {
+            x = b;
+        }
+        return Point { x, y };
+    }
+}";
+            assert!(unused_input_warnings(program).is_empty());
+        })
+    }
+}
@@ -203,6 +203,10 @@ symbols! {
     to_y_coordinate,
     verify,
 
+    // group coordinates
+    x,
+    y,
+
     // types
     address,
     bool,
@@ -253,6 +257,7 @@ symbols! {
     main,
     mapping,
     Mut: "mut",
+    noflatten,
     Return: "return",
     SelfLower: "self",
     SelfUpper: "Self",
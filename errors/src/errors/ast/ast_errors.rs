@@ -145,4 +145,12 @@ create_messages!(
         msg: format!("failed to convert symbol_table to a json value {error}"),
         help: None,
     }
+
+    /// For when a saved symbol table was written with a different version of the format.
+    @backtraced
+    incompatible_symbol_table_cache {
+        args: (path: impl Debug, found: impl Display, expected: impl Display),
+        msg: format!("the saved symbol table `{path:?}` has version {found}, but version {expected} is required"),
+        help: Some("Delete the saved symbol table, so that it is recomputed.".to_string()),
+    }
 );
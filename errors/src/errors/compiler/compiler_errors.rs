@@ -70,4 +70,100 @@ create_messages!(
         msg: format!("The program scope name `{program_scope_name}` must match `{file_name}`."),
         help: None,
     }
+
+    /// For when the static single assignment pass produces more than one assignment to the same variable.
+    @formatted
+    variable_assigned_more_than_once_in_ssa {
+        args: (name: impl Display, previous_span: impl Display),
+        msg: format!("Internal compiler error: `{name}` is assigned more than once in SSA form. It was previously assigned at {previous_span}."),
+        help: Some("This indicates a bug in the static single assignment pass.".to_string()),
+    }
+
+    /// For when a conditional statement reaches code generation because the flattening pass is disabled.
+    @formatted
+    conditional_statement_not_flattened {
+        args: (),
+        msg: "Conditional statements cannot be compiled to Aleo instructions unless they are flattened.".to_string(),
+        help: Some("Enable the static single assignment and flattening passes.".to_string()),
+    }
+
+    /// For when an iteration statement reaches code generation because it was not unrolled.
+    @formatted
+    iteration_statement_not_unrolled {
+        args: (),
+        msg: "Iteration statements cannot be compiled to Aleo instructions unless they are unrolled.".to_string(),
+        help: Some("Enable the loop unrolling pass.".to_string()),
+    }
+
+    /// For when the map from Aleo instructions to source locations cannot be serialized.
+    @backtraced
+    failed_to_convert_instruction_map_to_json_string {
+        args: (error: impl ErrorArg),
+        msg: format!("Failed to convert the instruction map to a JSON string: {error}"),
+        help: None,
+    }
+
+    /// For when code generation reaches a construct that must be inside a function, but no function is being visited.
+    @backtraced
+    no_current_function {
+        args: (construct: impl Display),
+        msg: format!("Internal compiler error: cannot generate code for a {construct} outside of a function."),
+        help: Some("This indicates that code generation was entered out of order.".to_string()),
+    }
+
+    /// For when static single assignment fails on a function. The remaining functions are still processed.
+    @formatted
+    failed_to_apply_ssa {
+        args: (function: impl Display, reason: impl Display),
+        msg: format!("Internal compiler error: failed to convert `{function}` to static single assignment form: {reason}"),
+        help: None,
+    }
+
+    /// For when a standard function would be compiled to an Aleo closure without any instructions or outputs.
+    @formatted
+    empty_closure_body {
+        args: (function: impl Display),
+        msg: format!("The function `{function}` has an empty body, but an Aleo closure must contain at least one instruction."),
+        help: Some("Remove the function, or declare it as a `transition`.".to_string()),
+    }
+
+    /// For when static single assignment encounters a use of a variable that has not been defined.
+    @formatted
+    undefined_variable {
+        args: (name: impl Display),
+        msg: format!("variable `{name}` is not defined"),
+        help: Some("Make sure the variable is defined before it is used.".to_string()),
+    }
+
+    /// For when the branches of a ternary expression are tuples of different lengths.
+    @formatted
+    ternary_tuple_length_mismatch {
+        args: (if_true: impl Display, if_false: impl Display),
+        msg: format!("The branches of the ternary expression are tuples of different lengths, {if_true} and {if_false}."),
+        help: Some("Make sure both branches of the ternary expression have the same type.".to_string()),
+    }
+
+    /// For when an integer literal must take the type of another operand, but its value is out of the range of that type.
+    @formatted
+    integer_literal_out_of_range {
+        args: (literal: impl Display, type_: impl Display),
+        msg: format!("The literal `{literal}` must have the type `{type_}`, but its value is out of the range of `{type_}`."),
+        help: Some("Use a value within the range of the type, or cast the other operand to the type of the literal.".to_string()),
+    }
+
+    /// For when the right operand of a logical operator has side effects, which cannot be evaluated conditionally.
+    @formatted
+    short_circuit_side_effects {
+        args: (operator: impl Display),
+        msg: format!("The right operand of `{operator}` has side effects, but Aleo instructions evaluate it even if the left operand determines the result."),
+        help: Some("Evaluate the right operand in a separate statement, so that its side effects are explicitly unconditional.".to_string()),
+    }
+
+    /// For when the tuples compared by an assertion have different lengths.
+    @formatted
+    assertion_tuple_length_mismatch {
+        args: (left: impl Display, right: impl Display),
+        msg: format!("The tuples compared by the assertion have different lengths, {left} and {right}."),
+        help: Some("This is an internal compiler error. Type checking should ensure that both tuples have the same type.".to_string()),
+    }
 );
@@ -53,4 +53,76 @@ create_messages!(
         ),
         help: None,
     }
+
+    /// For when the branches of a ternary expression are tuples of different lengths.
+    @formatted
+    ternary_tuple_arity_mismatch {
+        args: (if_true: impl Display, if_false: impl Display),
+        msg: format!(
+            "The branches of the ternary expression are tuples of different lengths, `{if_true}` and `{if_false}`.",
+        ),
+        help: Some("This is an internal compiler error. Type checking should ensure that both branches have the same type.".to_string()),
+    }
+
+    /// For when a function annotated with `@noflatten` contains a conditional return statement.
+    @formatted
+    conditional_return_in_noflatten_function {
+        args: (),
+        msg: "A function annotated with `@noflatten` cannot return from within a conditional statement.".to_string(),
+        help: Some("Remove the `@noflatten` annotation or move the return statement to the end of the function.".to_string()),
+    }
+
+    /// For when the branches of a ternary expression are different structs.
+    @formatted
+    ternary_struct_mismatch {
+        args: (if_true: impl Display, if_false: impl Display),
+        msg: format!(
+            "The branches of the ternary expression are different structs, `{if_true}` and `{if_false}`.",
+        ),
+        help: Some("This is an internal compiler error. Type checking should ensure that both branches have the same type.".to_string()),
+    }
+
+    /// For when a return statement is in a conditional statement, but return folding is disabled.
+    @formatted
+    conditional_return_without_return_folding {
+        args: (),
+        msg: "Cannot return from within a conditional statement when return folding is disabled.".to_string(),
+        help: Some("Enable return folding or move the return statement to the end of the block.".to_string()),
+    }
+
+    /// For when the number of arguments to a finalize block does not match the number of its inputs.
+    @formatted
+    finalize_argument_count_mismatch {
+        args: (function: impl Display, expected: impl Display, received: impl Display),
+        msg: format!(
+            "The finalize block of `{function}` expects {expected} argument(s), but {received} were passed to it.",
+        ),
+        help: Some("This is an internal compiler error. Type checking should ensure that the finalize arguments match the inputs.".to_string()),
+    }
+
+    /// For when an argument to a finalize block does not have the type of the corresponding input.
+    @formatted
+    finalize_argument_type_mismatch {
+        args: (function: impl Display, position: impl Display, expected: impl Display, received: impl Display),
+        msg: format!(
+            "Argument {position} to the finalize block of `{function}` has type `{received}`, but the input has type `{expected}`.",
+        ),
+        help: Some("This is an internal compiler error. Type checking should ensure that the finalize arguments match the inputs.".to_string()),
+    }
+
+    /// For when the branches of a ternary expression are structs that are not defined in the symbol table.
+    @formatted
+    ternary_struct_not_found {
+        args: (struct_: impl Display),
+        msg: format!("The branches of the ternary expression are structs of type `{struct_}`, which is not defined."),
+        help: Some("This is an internal compiler error. Type checking should ensure that all structs are defined.".to_string()),
+    }
+
+    /// For when a mapping is updated inside a conditional statement and such updates are rejected.
+    @formatted
+    conditional_mapping_update {
+        args: (function: impl Display),
+        msg: format!("Cannot call `Mapping::{function}` inside a conditional statement, since Aleo instructions cannot update a mapping conditionally."),
+        help: Some("Move the update out of the conditional statement, e.g. by selecting the value to write with a ternary expression.".to_string()),
+    }
 );
@@ -15,7 +15,7 @@
 // along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::create_messages;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
 
 create_messages!(
     /// LoopUnrollerError enum that represents all the errors for the loop unrolling errors in the `leo-loop_unroller` crate.
@@ -36,4 +36,11 @@ create_messages!(
         msg: format!("The array index must be constant."),
         help: None,
     }
+
+    @formatted
+    loop_iterations_exceed_limit {
+        args: (start: impl Display, stop: impl Display, limit: impl Display),
+        msg: format!("The loop from `{start}` to `{stop}` has more than {limit} iterations, which is the maximum that the loop unroller unrolls."),
+        help: Some("Reduce the number of iterations, or increase the maximum number of loop iterations with `--max-loop-iterations` (zero for no maximum).".to_string()),
+    }
 );
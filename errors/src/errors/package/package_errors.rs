@@ -313,4 +313,28 @@ create_messages!(
         msg: format!("IO error env file from the provided file path - {error}"),
         help: None,
     }
+
+    /// For when reading the Aleo file failed.
+    @backtraced
+    failed_to_read_aleo_file {
+        args: (path: impl Debug, error: impl ErrorArg),
+        msg: format!("Cannot read Aleo file from the provided file path - {path:?}: {error}"),
+        help: None,
+    }
+
+    /// For when the Aleo file is not a well-formed program.
+    @backtraced
+    invalid_aleo_file {
+        args: (line_number: impl Display, line: impl Display, reason: impl Display),
+        msg: format!("Invalid Aleo file at line {line_number}: `{line}` - {reason}."),
+        help: None,
+    }
+
+    /// For when the map file cannot be written.
+    @backtraced
+    failed_to_write_map_file {
+        args: (error: impl Display),
+        msg: format!("Failed to write map file: {error}."),
+        help: None,
+    }
 );
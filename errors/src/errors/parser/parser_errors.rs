@@ -298,4 +298,25 @@ create_messages!(
         msg: format!("An array {kind} must have at least one element."),
         help: None,
     }
+
+    @formatted
+    array_length_must_be_constant {
+        args: (found: impl Display),
+        msg: format!("The length of an array must be a constant expression -- found '{found}'"),
+        help: Some("Array lengths may only use whole numbers, `+`, `-`, `*`, and parentheses.".to_string()),
+    }
+
+    @formatted
+    array_length_out_of_range {
+        args: (),
+        msg: format!("The length of the array is out of range."),
+        help: None,
+    }
+
+    @formatted
+    assert_message_cannot_interpolate {
+        args: (),
+        msg: format!("The message of an assertion cannot interpolate values."),
+        help: Some("Aleo instructions cannot print values, so the message must be a single string literal.".to_string()),
+    }
 );
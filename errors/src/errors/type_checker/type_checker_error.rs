@@ -712,4 +712,18 @@ create_messages!(
         msg: format!("An array cannot have a record as an element type"),
         help: None,
     }
+
+    @formatted
+    invalid_group_coordinate {
+        args: (name: impl Display),
+        msg: format!("`{name}` is not a coordinate of a group element."),
+        help: Some("The coordinates of a group element are accessed with `.x` and `.y`.".to_string()),
+    }
+
+    @formatted
+    division_by_zero {
+        args: (operation: impl Display),
+        msg: format!("The divisor of `{operation}` is zero, so the operation always fails."),
+        help: None,
+    }
 );
@@ -17,9 +17,9 @@
 use super::*;
 
 use leo_ast::{NodeBuilder, Struct};
-use leo_compiler::{Compiler, CompilerOptions, InputAst, OutputOptions};
+use leo_compiler::{Compiler, CompilerOptions, InputAst, OutputOptions, OverflowMode};
 use leo_package::{
-    build::BuildDirectory,
+    build::{AleoFile, BuildDirectory, MapFile},
     imports::ImportsDirectory,
     inputs::InputFile,
     outputs::OutputsDirectory,
@@ -33,15 +33,36 @@ use snarkvm::{
 };
 
 use indexmap::IndexMap;
-use std::{
-    io::Write,
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
 
 impl From<BuildOptions> for CompilerOptions {
     fn from(options: BuildOptions) -> Self {
         let mut out_options = Self {
-            build: leo_compiler::BuildOptions { dce_enabled: options.enable_dce },
+            build: leo_compiler::BuildOptions {
+                ssa_enabled: !options.disable_ssa,
+                flattening_enabled: !options.disable_flattening,
+                dce_enabled: options.enable_dce,
+                cse_enabled: options.enable_cse,
+                temporary_elimination_enabled: options.enable_temporary_elimination,
+                overflow_mode: match options.enable_wrapping_arithmetic {
+                    true => OverflowMode::Wrapping,
+                    false => OverflowMode::Checked,
+                },
+                ssa_verification_enabled: options.enable_ssa_verification,
+                constant_propagation_enabled: options.enable_constant_propagation,
+                unused_input_warnings_enabled: options.enable_unused_input_warnings,
+                assert_lowering_enabled: options.enable_assert_lowering,
+                return_folding_enabled: !options.disable_return_folding,
+                finalize_folding_enabled: !options.disable_finalize_folding,
+                mapping_update_rejection_enabled: options.enable_mapping_update_rejection,
+                debug_comments_enabled: options.enable_debug_comments,
+                source_map_enabled: options.enable_source_map,
+                annotation_comments_enabled: options.enable_annotation_comments,
+                trivial_inlining_threshold: options.trivial_inlining_threshold,
+                named_returns_enabled: options.enable_named_returns,
+                unreachable_assertions_enabled: options.enable_unreachable_assertions,
+                max_loop_iterations: options.max_loop_iterations,
+            },
             output: OutputOptions {
                 symbol_table_spans_enabled: options.enable_symbol_table_spans,
                 initial_symbol_table: options.enable_initial_symbol_table_snapshot,
@@ -56,6 +77,7 @@ impl From<BuildOptions> for CompilerOptions {
                 destructured_ast: options.enable_destructured_ast_snapshot,
                 inlined_ast: options.enable_inlined_ast_snapshot,
                 dce_ast: options.enable_dce_ast_snapshot,
+                ast_dumps: options.enable_ast_dumps,
             },
         };
         if options.enable_all_ast_snapshots {
@@ -222,12 +244,16 @@ fn compile_leo_file(
         true => file_name.strip_suffix(".leo").ok_or_else(PackageError::failed_to_get_file_name)?.to_string(),
     };
 
-    // Create the path to the Aleo file.
-    let mut aleo_file_path = build.to_path_buf();
-    aleo_file_path.push(match is_import {
-        true => format!("{program_name}.{}", program_id.network()),
-        false => format!("main.{}", program_id.network()),
-    });
+    // Create the Aleo file.
+    // Note that the main program is always written to `main.aleo`, regardless of its name.
+    let aleo_file = match is_import {
+        true => AleoFile::new(&program_name),
+        false => AleoFile::new(&program_name).with_file_stem("main"),
+    };
+
+    // Record whether to write the map file and the per-transition files before the options are consumed by the compiler.
+    let source_map_enabled = options.enable_source_map;
+    let split_transitions_enabled = options.enable_split_transitions;
 
     // Create a new instance of the Leo compiler.
     let mut compiler = Compiler::new(
@@ -242,11 +268,17 @@ fn compile_leo_file(
     // Compile the Leo program into Aleo instructions.
     let (symbol_table, instructions) = compiler.compile()?;
 
-    // Write the instructions.
-    std::fs::File::create(&aleo_file_path)
-        .map_err(CliError::failed_to_load_instructions)?
-        .write_all(instructions.as_bytes())
-        .map_err(CliError::failed_to_load_instructions)?;
+    // Write the instructions, and optionally each transition to a separate file.
+    if split_transitions_enabled {
+        aleo_file.write_transitions_to(build, &instructions)?;
+    }
+    aleo_file.write_to(build, instructions)?;
+
+    // Write the map from instructions to source locations alongside the instructions.
+    if source_map_enabled {
+        let map_file = MapFile::new(aleo_file.program_name.as_str()).with_file_stem(aleo_file.file_stem());
+        map_file.write_to(build, compiler.instruction_map()?)?;
+    }
 
     tracing::info!("✅ Compiled '{}' into Aleo instructions", file_name);
     Ok(symbol_table.structs)
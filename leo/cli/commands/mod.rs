@@ -133,6 +133,53 @@ pub struct BuildOptions {
     pub enable_ast_spans: bool,
     #[clap(long, help = "Enable spans in symbol table snapshots.")]
     pub enable_dce: bool,
+    #[clap(long, help = "Reuses the results of pure expressions that are computed more than once in a function.")]
+    pub enable_cse: bool,
+    #[clap(long, help = "Removes assignments to variables that are never used and whose values have no side effects.")]
+    pub enable_temporary_elimination: bool,
+    #[clap(long, help = "Emits wrapping instructions for integer arithmetic instead of checked instructions.")]
+    pub enable_wrapping_arithmetic: bool,
+    #[clap(long, help = "Verifies that each variable is assigned at most once after static single assignment.")]
+    pub enable_ssa_verification: bool,
+    #[clap(long, help = "Replaces uses of variables that are known to equal a literal with the literal.")]
+    pub enable_constant_propagation: bool,
+    #[clap(long, help = "Emits warnings for function inputs that are never used.")]
+    pub enable_unused_input_warnings: bool,
+    #[clap(long, help = "Lowers assertions of inequalities directly to `assert.neq` instructions.")]
+    pub enable_assert_lowering: bool,
+    #[clap(
+        long,
+        help = "Rejects mapping updates inside conditional statements, which are otherwise performed on every path."
+    )]
+    pub enable_mapping_update_rejection: bool,
+    #[clap(long, help = "Precedes the instructions for each statement with a comment containing the statement.")]
+    pub enable_debug_comments: bool,
+    #[clap(long, help = "Writes a map from each Aleo instruction to its Leo source location to a `.map` file.")]
+    pub enable_source_map: bool,
+    #[clap(long, help = "Precedes each function with comments containing its annotations.")]
+    pub enable_annotation_comments: bool,
+    #[clap(long, help = "Also writes each transition to a separate `<package>.<transition>.aleo` file.")]
+    pub enable_split_transitions: bool,
+    #[clap(long, default_value_t = 0, help = "Inlines standard functions with at most this many statements.")]
+    pub trivial_inlining_threshold: usize,
+    #[clap(long, help = "Names the variables produced by folding returns after the function, e.g. `main$out`.")]
+    pub enable_named_returns: bool,
+    #[clap(long, help = "Asserts that the guards of folded returns that can never be true are false.")]
+    pub enable_unreachable_assertions: bool,
+    #[clap(
+        long,
+        default_value_t = leo_compiler::DEFAULT_MAX_LOOP_ITERATIONS,
+        help = "Rejects loops with more than this many iterations when unrolling them. If zero, there is no maximum."
+    )]
+    pub max_loop_iterations: usize,
+    #[clap(long, help = "Disables static single assignment and all later passes except for code generation.")]
+    pub disable_ssa: bool,
+    #[clap(long, help = "Disables flattening and all later passes except for code generation.")]
+    pub disable_flattening: bool,
+    #[clap(long, help = "Leaves the returns in function bodies in place instead of folding them into one return.")]
+    pub disable_return_folding: bool,
+    #[clap(long, help = "Leaves the returns in finalize blocks in place instead of folding them into one return.")]
+    pub disable_finalize_folding: bool,
     #[clap(long, help = "Writes all AST snapshots for the different compiler phases.")]
     pub enable_all_ast_snapshots: bool,
     #[clap(long, help = "Writes Input AST snapshot of the initial parse.")]
@@ -151,4 +198,6 @@ pub struct BuildOptions {
     pub enable_inlined_ast_snapshot: bool,
     #[clap(long, help = "Writes AST snapshot of the dead code eliminated (DCE) AST.")]
     pub enable_dce_ast_snapshot: bool,
+    #[clap(long, help = "Writes the complete SSA and flattened ASTs to JSON files that can be read back.")]
+    pub enable_ast_dumps: bool,
 }
@@ -0,0 +1,689 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! The Aleo instructions file.
+
+use leo_errors::{PackageError, Result};
+
+use serde::Deserialize;
+use std::{
+    borrow::Cow,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+pub static ALEO_FILE_EXTENSION: &str = ".aleo";
+
+/// The keywords that declare an executable function in Aleo instructions.
+/// Leo emits `function`, but `transition` is accepted as well.
+const FUNCTION_KEYWORDS: [&str; 2] = ["function", "transition"];
+
+/// The keywords that declare a block in Aleo instructions.
+const BLOCK_KEYWORDS: [&str; 7] = ["function", "transition", "closure", "finalize", "struct", "record", "mapping"];
+
+#[derive(Deserialize)]
+pub struct AleoFile {
+    pub program_name: String,
+    /// The name of the file on disk, without the extension. Defaults to the program name.
+    #[serde(default)]
+    pub file_stem: Option<String>,
+    /// The path of the file on disk, if the file is bound to a path rather than resolved from a directory.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+impl AleoFile {
+    pub fn new(program_name: &str) -> Self {
+        Self { program_name: program_name.to_string(), file_stem: None, path: None }
+    }
+
+    /// Binds to the Aleo file at the given path, which need not be in an outputs or build directory.
+    /// The path passed to the other methods is then ignored, and the file at this path is used instead.
+    /// The program name defaults to the name of the file, without the extension.
+    /// Use `read_program_id` to recover the program declared by the file.
+    pub fn from_path(path: PathBuf) -> Self {
+        let program_name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+        Self { program_name, file_stem: None, path: Some(path) }
+    }
+
+    /// Sets the name of the file on disk, without the extension.
+    /// Note that this does not affect the program name.
+    pub fn with_file_stem(mut self, file_stem: &str) -> Self {
+        self.file_stem = Some(file_stem.to_string());
+        self
+    }
+
+    /// Returns the name of the file on disk, without the extension.
+    pub fn file_stem(&self) -> &str {
+        self.file_stem.as_deref().unwrap_or(&self.program_name)
+    }
+
+    pub fn exists_at(&self, path: &Path) -> bool {
+        let path = self.setup_file_path(path);
+        path.exists()
+    }
+
+    /// Reads the Aleo instructions from the given file path if it exists.
+    /// A leading byte order mark is removed and line endings are normalized to `\n`.
+    pub fn read_from(&self, path: &Path) -> Result<String> {
+        let path = self.setup_file_path(path);
+
+        // Note that the underlying error is included, so that the cause, e.g. missing permissions, is reported.
+        let string = fs::read_to_string(&path).map_err(|error| PackageError::failed_to_read_aleo_file(&path, error))?;
+        Ok(Self::normalize(&string).into_owned())
+    }
+
+    /// Writes the given Aleo instructions to a file, with `\n` line endings.
+    /// The instructions are written to a temporary file, which then atomically replaces the file at the given path.
+    /// This ensures that readers never observe a partially written file.
+    pub fn write_to(&self, path: &Path, program: String) -> Result<()> {
+        let path = self.setup_file_path(path);
+        let program = Self::normalize(&program);
+
+        Self::write_atomically(&path, |file| file.write_all(program.as_bytes()))
+            .map_err(PackageError::failed_to_write_aleo_file)?;
+        Ok(())
+    }
+
+    /// Reads the Aleo instructions from the given file path and returns the ID of the program they declare,
+    /// e.g. `token.aleo`. This scans for the program declaration rather than parsing the instructions.
+    pub fn read_program_id(&self, path: &Path) -> Result<String> {
+        let program = self.read_from(path)?;
+        program
+            .lines()
+            .map(|line| line.split_once("//").map_or(line, |(code, _)| code).split_whitespace().collect::<Vec<_>>())
+            .find(|tokens| tokens.first() == Some(&"program"))
+            .filter(|tokens| Self::is_declaration(tokens, ".aleo;"))
+            .map(|tokens| tokens[1].trim_end_matches(';').to_string())
+            .ok_or_else(|| PackageError::invalid_aleo_file(1, "", "expected a `program` declaration").into())
+    }
+
+    /// Writes each function of the given Aleo instructions to a separate file `<file stem>.<function>.aleo`
+    /// in the given directory, and returns the paths of the files in order of definition.
+    /// Each file repeats the imports, the program declaration, and all blocks other than functions,
+    /// so that it remains individually loadable. A function is followed by its finalize block, if any.
+    pub fn write_transitions_to(&self, directory: &Path, program: &str) -> Result<Vec<PathBuf>> {
+        let program = Self::normalize(program);
+        let (shared, transitions) = Self::split_transitions(&program);
+
+        transitions
+            .into_iter()
+            .map(|(name, body)| {
+                let path = directory.join(format!("{}.{name}{ALEO_FILE_EXTENSION}", self.file_stem()));
+                Self::write_atomically(&path, |file| file.write_all(format!("{shared}{body}").as_bytes()))
+                    .map_err(PackageError::failed_to_write_aleo_file)?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    /// Splits the Aleo instructions into the lines shared by all functions and the lines of each function.
+    /// A function owns the lines from its declaration up to the next block, other than its own finalize block.
+    fn split_transitions(program: &str) -> (String, Vec<(String, String)>) {
+        let mut shared = String::new();
+        let mut transitions: Vec<(String, String)> = Vec::new();
+        // Whether the lines that are currently visited belong to the last function.
+        let mut in_transition = false;
+
+        for line in program.split_inclusive('\n') {
+            let tokens = line.split_whitespace().collect::<Vec<_>>();
+            if Self::is_declaration(&tokens, ":") {
+                let name = tokens[1].trim_end_matches(':');
+                match tokens[0] {
+                    keyword if FUNCTION_KEYWORDS.contains(&keyword) => {
+                        transitions.push((name.to_string(), String::new()));
+                        in_transition = true;
+                    }
+                    "finalize" => {
+                        in_transition = in_transition && matches!(transitions.last(), Some((last, _)) if last == name)
+                    }
+                    keyword if BLOCK_KEYWORDS.contains(&keyword) => in_transition = false,
+                    _ => {}
+                }
+            }
+
+            match (in_transition, transitions.last_mut()) {
+                (true, Some((_, body))) => body.push_str(line),
+                _ => shared.push_str(line),
+            }
+        }
+
+        (shared, transitions)
+    }
+
+    /// Returns the program declaration that begins the Aleo instructions.
+    pub fn header(&self) -> String {
+        format!("program {}{ALEO_FILE_EXTENSION};\n\n", self.program_name)
+    }
+
+    /// Writes the program declaration to a file, replacing any existing file.
+    /// Together with `append_body`, this allows large programs to be written incrementally.
+    /// Note that unlike `write_to`, the file is not replaced atomically.
+    pub fn write_header(&self, path: &Path) -> Result<()> {
+        let path = self.setup_file_path(path);
+
+        fs::write(&path, self.header()).map_err(PackageError::failed_to_write_aleo_file)?;
+        Ok(())
+    }
+
+    /// Appends the given Aleo instructions to a file previously created with `write_header`, with `\n` line endings.
+    /// The resulting file is identical to the one written by `write_to` with the header followed by each body.
+    /// Note that each body is normalized independently, so a `\r\n` line ending must not be split across bodies.
+    pub fn append_body(&self, path: &Path, body: &str) -> Result<()> {
+        let path = self.setup_file_path(path);
+        let body = Self::normalize_line_endings(body);
+
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(body.as_bytes()))
+            .map_err(PackageError::failed_to_write_aleo_file)?;
+        Ok(())
+    }
+
+    /// Reads the Aleo instructions from the given file path and checks that they are structurally well-formed.
+    /// This is a lightweight check of the program declaration, block declarations, and `input`/`output` lines,
+    /// and does not type check the instructions.
+    pub fn validate(&self, path: &Path) -> Result<()> {
+        let program = self.read_from(path)?;
+        Self::validate_program(&program)
+    }
+
+    /// Checks that the program declaration is preceded only by imports and followed only by blocks,
+    /// that each `finalize` block directly follows the `function` block of the same name,
+    /// and that each `input` and `output` line in a `function`, `closure`, or `finalize` block is well-formed.
+    fn validate_program(program: &str) -> Result<()> {
+        let invalid = |index: usize, line: &str, reason: &str| PackageError::invalid_aleo_file(index + 1, line, reason);
+
+        let mut has_program = false;
+        // The kind of the block that is currently open, if any.
+        let mut block = None;
+        // The name of the function whose block was most recently opened, if it is not yet followed by another block.
+        let mut function = None;
+
+        for (index, line) in program.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+
+            let tokens = line.split_whitespace().collect::<Vec<_>>();
+            match tokens[0] {
+                "import" if !has_program => {
+                    if !Self::is_declaration(&tokens, ".aleo;") {
+                        return Err(invalid(index, line, "expected `import <name>.aleo;`").into());
+                    }
+                }
+                "program" if !has_program => {
+                    if !Self::is_declaration(&tokens, ".aleo;") {
+                        return Err(invalid(index, line, "expected `program <name>.aleo;`").into());
+                    }
+                    has_program = true;
+                }
+                "program" => return Err(invalid(index, line, "the program is declared more than once").into()),
+                _ if !has_program => return Err(invalid(index, line, "expected a `program` declaration").into()),
+                kind if BLOCK_KEYWORDS.contains(&kind) => {
+                    if !Self::is_declaration(&tokens, ":") {
+                        return Err(invalid(index, line, &format!("expected `{kind} <name>:`")).into());
+                    }
+                    let name = tokens[1].trim_end_matches(':');
+                    if kind == "finalize" && function != Some(name) {
+                        let reason = "expected to directly follow the function of the same name";
+                        return Err(invalid(index, line, reason).into());
+                    }
+                    block = Some(kind);
+                    function = FUNCTION_KEYWORDS.contains(&kind).then_some(name);
+                }
+                kind @ ("input" | "output") => {
+                    let has_inputs =
+                        |block: &str| FUNCTION_KEYWORDS.contains(&block) || ["closure", "finalize"].contains(&block);
+                    if !block.map_or(false, has_inputs) {
+                        let reason = "expected to be in a function, closure, or finalize block";
+                        return Err(invalid(index, line, reason).into());
+                    }
+                    // Inputs are always assigned to a register, while outputs may be any operand.
+                    let is_register = |token: &str| match token.strip_prefix('r') {
+                        Some(number) => !number.is_empty() && number.bytes().all(|byte| byte.is_ascii_digit()),
+                        None => false,
+                    };
+                    let is_well_formed = tokens.len() == 4
+                        && (kind == "output" || is_register(tokens[1]))
+                        && tokens[2] == "as"
+                        && tokens[3].len() > 1
+                        && tokens[3].ends_with(';');
+                    if !is_well_formed {
+                        return Err(invalid(index, line, &format!("expected `{kind} <operand> as <type>;`")).into());
+                    }
+                }
+                _ if block.is_none() => return Err(invalid(index, line, "expected a block declaration").into()),
+                _ => {}
+            }
+        }
+
+        match has_program {
+            true => Ok(()),
+            false => Err(PackageError::invalid_aleo_file(1, "", "expected a `program` declaration").into()),
+        }
+    }
+
+    /// Reads the Aleo instructions from the given file path and returns the names of the functions and closures
+    /// they define, in order of definition. This scans the block declarations rather than parsing the instructions.
+    pub fn list_functions(&self, path: &Path) -> Result<Vec<String>> {
+        let program = self.read_from(path)?;
+        Ok(Self::declared_names(&program, &[FUNCTION_KEYWORDS.as_slice(), &["closure"]].concat()))
+    }
+
+    /// Reads the Aleo instructions from the given file path and returns the names of the functions and transitions
+    /// they define, in order of definition. Unlike `list_functions`, closures are skipped, since they cannot be executed.
+    pub fn list_transitions(&self, path: &Path) -> Result<Vec<String>> {
+        let program = self.read_from(path)?;
+        Ok(Self::declared_names(&program, &FUNCTION_KEYWORDS))
+    }
+
+    /// Returns the names declared by the lines of the program that begin with one of the given keywords.
+    /// Comments, including trailing comments, are ignored, and all other lines are skipped.
+    fn declared_names(program: &str, keywords: &[&str]) -> Vec<String> {
+        program
+            .lines()
+            .filter_map(|line| {
+                let line = line.split_once("//").map_or(line, |(code, _)| code);
+                let tokens = line.split_whitespace().collect::<Vec<_>>();
+                match tokens.first() {
+                    Some(keyword) if keywords.contains(keyword) && Self::is_declaration(&tokens, ":") => {
+                        Some(tokens[1].trim_end_matches(':').to_string())
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the tokens are a keyword followed by a single name with the given suffix.
+    fn is_declaration(tokens: &[&str], suffix: &str) -> bool {
+        tokens.len() == 2 && tokens[1].len() > suffix.len() && tokens[1].ends_with(suffix)
+    }
+
+    /// Removes the Aleo file at the given path if it exists. Returns `true` on success,
+    /// `false` if the file doesn't exist, and `Error` if the file system fails during operation.
+    pub fn remove(&self, path: &Path) -> Result<bool> {
+        let path = self.setup_file_path(path);
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        fs::remove_file(&path).map_err(PackageError::failed_to_remove_aleo_file)?;
+        Ok(true)
+    }
+
+    /// Writes to a temporary file in the same directory as `path` and renames it to `path`.
+    /// Since both files are in the same directory, the rename does not cross file systems and is atomic.
+    /// If any step fails, the temporary file is removed and the file at `path` is left untouched.
+    fn write_atomically(path: &Path, write: impl FnOnce(&mut File) -> io::Result<()>) -> io::Result<()> {
+        let temporary_path = Self::temporary_file_path(path);
+
+        let result = File::create(&temporary_path)
+            .and_then(|mut file| {
+                write(&mut file)?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&temporary_path, path));
+
+        if result.is_err() {
+            // Note that the temporary file may not have been created, so the result is ignored.
+            let _ = fs::remove_file(&temporary_path);
+        }
+
+        result
+    }
+
+    /// Removes a leading UTF-8 byte order mark and replaces `\r\n` and `\r` line endings with `\n`.
+    fn normalize(program: &str) -> Cow<'_, str> {
+        Self::normalize_line_endings(program.strip_prefix('\u{feff}').unwrap_or(program))
+    }
+
+    /// Replaces `\r\n` and `\r` line endings with `\n`.
+    fn normalize_line_endings(program: &str) -> Cow<'_, str> {
+        match program.contains('\r') {
+            true => Cow::Owned(program.replace("\r\n", "\n").replace('\r', "\n")),
+            false => Cow::Borrowed(program),
+        }
+    }
+
+    fn temporary_file_path(path: &Path) -> PathBuf {
+        let mut file_name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+        file_name.push(".tmp");
+        path.with_file_name(file_name)
+    }
+
+    /// Resolves the path to the Aleo file.
+    /// Since Aleo files are written to both the build directory and its imports directory,
+    /// a directory path is used as is, rather than being resolved to the build directory.
+    /// If the file is bound to a path with `from_path`, that path is used instead.
+    fn setup_file_path<'a>(&'a self, path: &'a Path) -> Cow<'a, Path> {
+        if let Some(path) = &self.path {
+            return Cow::from(path.as_path());
+        }
+
+        let mut path = Cow::from(path);
+        if path.is_dir() {
+            path.to_mut().push(format!("{}{ALEO_FILE_EXTENSION}", self.file_stem()));
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build::BuildDirectory;
+
+    /// Returns an empty build directory for the test with the given name.
+    fn build_directory(test_name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!("leo-aleo-file-{test_name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        BuildDirectory::create(&path).unwrap()
+    }
+
+    #[test]
+    fn test_write_to() {
+        let directory = build_directory("write_to");
+        let aleo_file = AleoFile::new("main");
+
+        aleo_file.write_to(&directory, "program test.aleo;\n".to_string()).unwrap();
+        assert_eq!(aleo_file.read_from(&directory).unwrap(), "program test.aleo;\n");
+
+        // Overwrite the existing file.
+        aleo_file.write_to(&directory, "program other.aleo;\n".to_string()).unwrap();
+        assert_eq!(aleo_file.read_from(&directory).unwrap(), "program other.aleo;\n");
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_file_stem() {
+        let directory = build_directory("file_stem");
+
+        // By default, the file is named after the program.
+        let aleo_file = AleoFile::new("my_project");
+        assert_eq!(aleo_file.file_stem(), "my_project");
+        aleo_file.write_to(&directory, "program my_project.aleo;\n".to_string()).unwrap();
+        assert!(directory.join("my_project.aleo").exists());
+
+        // The file name can be set independently of the program name.
+        let aleo_file = AleoFile::new("my_project").with_file_stem("main");
+        assert_eq!(aleo_file.program_name, "my_project");
+        assert_eq!(aleo_file.file_stem(), "main");
+        aleo_file.write_to(&directory, "program my_project.aleo;\n".to_string()).unwrap();
+        assert_eq!(fs::read_to_string(directory.join("main.aleo")).unwrap(), "program my_project.aleo;\n");
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_read_from_normalizes_content() {
+        let directory = build_directory("read_from_normalizes_content");
+        let aleo_file = AleoFile::new("main");
+        let path = aleo_file.setup_file_path(&directory).into_owned();
+
+        // A file with a byte order mark.
+        fs::write(&path, "\u{feff}program test.aleo;\n").unwrap();
+        assert_eq!(aleo_file.read_from(&directory).unwrap(), "program test.aleo;\n");
+
+        // A file with CRLF line endings.
+        fs::write(&path, "program test.aleo;\r\n\r\nfunction main:\r\n").unwrap();
+        assert_eq!(aleo_file.read_from(&directory).unwrap(), "program test.aleo;\n\nfunction main:\n");
+
+        // Written files always use `\n` line endings.
+        aleo_file.write_to(&directory, "\u{feff}program test.aleo;\r\n".to_string()).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "program test.aleo;\n");
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_write_to_uses_line_feeds() {
+        let directory = build_directory("write_to_uses_line_feeds");
+        let aleo_file = AleoFile::new("main");
+
+        // A body with mixed line endings.
+        let program = "program test.aleo;\r\n\nfunction main:\r    input r0 as u8.private;\r\n";
+        aleo_file.write_to(&directory, program.to_string()).unwrap();
+
+        let written = fs::read(directory.join("main.aleo")).unwrap();
+        assert!(!written.contains(&b'\r'));
+        assert_eq!(written, b"program test.aleo;\n\nfunction main:\n    input r0 as u8.private;\n");
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_streamed_write() {
+        let directory = build_directory("streamed_write");
+        let functions =
+            ["function foo:\r\n    input r0 as u8.private;\r\n", "\nfunction bar:\n    input r0 as u8.private;\n"];
+
+        // Write the program in one shot.
+        let aleo_file = AleoFile::new("test");
+        assert_eq!(aleo_file.header(), "program test.aleo;\n\n");
+        aleo_file.write_to(&directory, aleo_file.header() + &functions.concat()).unwrap();
+        let expected = fs::read(directory.join("test.aleo")).unwrap();
+
+        // Stream the program into a different file.
+        let aleo_file = AleoFile::new("test").with_file_stem("streamed");
+        aleo_file.write_header(&directory).unwrap();
+        for function in functions {
+            aleo_file.append_body(&directory, function).unwrap();
+        }
+        assert_eq!(fs::read(directory.join("streamed.aleo")).unwrap(), expected);
+
+        // The header must be written before the body is appended.
+        assert!(AleoFile::new("missing").append_body(&directory, functions[0]).is_err());
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_validate() {
+        let directory = build_directory("validate");
+        let aleo_file = AleoFile::new("main");
+
+        let program = "import token.aleo;\nprogram test.aleo;\n\nstruct point:\n    x as u8;\n\n\
+            function main:\n    input r0 as u8.private;\n    add r0 1u8 into r1;\n    output r1 as u8.private;\n\n\
+            finalize main:\n    input r0 as u8.public;\n";
+        aleo_file.write_to(&directory, program.to_string()).unwrap();
+        aleo_file.validate(&directory).unwrap();
+
+        // Transitions are accepted wherever functions are, as when listing them.
+        let program = program.replace("function main:", "transition main:");
+        aleo_file.write_to(&directory, program).unwrap();
+        aleo_file.validate(&directory).unwrap();
+        assert_eq!(aleo_file.list_transitions(&directory).unwrap(), ["main"]);
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_validate_missing_program() {
+        let directory = build_directory("validate_missing_program");
+        let aleo_file = AleoFile::new("main");
+
+        let program = "function main:\n    input r0 as u8.private;\n    output r0 as u8.private;\n";
+        aleo_file.write_to(&directory, program.to_string()).unwrap();
+        let error = aleo_file.validate(&directory).unwrap_err().to_string();
+        assert!(error.contains("line 1: `function main:`"), "{error}");
+
+        // An empty file is missing the declaration as well.
+        aleo_file.write_to(&directory, String::new()).unwrap();
+        assert!(aleo_file.validate(&directory).is_err());
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_validate_malformed_lines() {
+        let program = |body: &str| format!("program test.aleo;\n\n{body}");
+
+        assert!(AleoFile::validate_program(&program("function main:\n    input r0 u8.private;\n")).is_err());
+        assert!(AleoFile::validate_program(&program("function main:\n    input 1u8 as u8.private;\n")).is_err());
+        assert!(AleoFile::validate_program(&program("function main:\n    output r0 as u8.private\n")).is_err());
+        assert!(AleoFile::validate_program(&program("struct point:\n    input r0 as u8.private;\n")).is_err());
+        assert!(AleoFile::validate_program(&program("    add r0 r1 into r2;\n")).is_err());
+        assert!(AleoFile::validate_program(&program("function main\n")).is_err());
+        assert!(AleoFile::validate_program(&program("function foo:\n\nfinalize bar:\n")).is_err());
+        assert!(AleoFile::validate_program(&program("program other.aleo;\n")).is_err());
+    }
+
+    #[test]
+    fn test_list_functions() {
+        let directory = build_directory("list_functions");
+        let aleo_file = AleoFile::new("main");
+
+        let program = "// The program declaration is not a function.\nprogram test.aleo;\n\n\
+            closure helper:\n    input r0 as u8;\n    output r0 as u8;\n\n\
+            // function commented:\n\
+            function mint:\n    input r0 as u8.private;\n    call helper r0 into r1;\n    output r1 as u8.private;\n\n\
+            finalize mint:\n    input r0 as u8.public;\n\n\
+            function transfer:\n    input r0 as u8.private;\n    output r0 as u8.private;\n";
+        aleo_file.write_to(&directory, program.to_string()).unwrap();
+        assert_eq!(aleo_file.list_functions(&directory).unwrap(), ["helper", "mint", "transfer"]);
+
+        // A missing file cannot be listed.
+        assert!(AleoFile::new("missing").list_functions(&directory).is_err());
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_io_errors_include_cause() {
+        let directory = build_directory("io_errors_include_cause");
+        let aleo_file = AleoFile::new("main");
+
+        // Reading a file that does not exist reports the path and the underlying error.
+        let path = directory.join("missing.aleo");
+        let cause = fs::read_to_string(&path).unwrap_err().to_string();
+        let error = aleo_file.read_from(&path).unwrap_err().to_string();
+        assert!(error.contains("missing.aleo"), "{error}");
+        assert!(error.contains(&cause), "{error}");
+
+        // Writing to a directory that does not exist reports the underlying error.
+        let path = directory.join("missing").join("main.aleo");
+        let cause = fs::write(&path, "").unwrap_err().to_string();
+        let error = aleo_file.write_header(&path).unwrap_err().to_string();
+        assert!(error.contains(&cause), "{error}");
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_list_transitions() {
+        let directory = build_directory("list_transitions");
+        let aleo_file = AleoFile::new("main");
+
+        let program = "program test.aleo;\n\n\
+            closure helper:\n    input r0 as u8;\n    output r0 as u8;\n\n\
+            // function commented:\n\
+            \tfunction mint: // Indented, with a trailing comment.\n    input r0 as u8.private;\n\n\
+            finalize mint:\n    input r0 as u8.public;\n\n\
+            transition transfer:\n    input r0 as u8.private;\n\n\
+            function burn:\n    input r0 as u8.private;\n";
+        aleo_file.write_to(&directory, program.to_string()).unwrap();
+        assert_eq!(aleo_file.list_transitions(&directory).unwrap(), ["mint", "transfer", "burn"]);
+
+        // A program with only closures has no transitions.
+        let program = "program test.aleo;\n\nclosure helper:\n    input r0 as u8;\n    output r0 as u8;\n";
+        aleo_file.write_to(&directory, program.to_string()).unwrap();
+        assert!(aleo_file.list_transitions(&directory).unwrap().is_empty());
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_write_transitions_to() {
+        let directory = build_directory("write_transitions_to");
+        let aleo_file = AleoFile::new("token");
+
+        let header = "import credits.aleo;\nprogram token.aleo;\n\nmapping balances:\n    key as address.public;\n\n";
+        let helper = "closure helper:\n    input r0 as u8;\n    output r0 as u8;\n\n";
+        let mint = "function mint:\n    input r0 as u8.private;\n    output r0 as u8.private;\n\n\
+            finalize mint:\n    input r0 as u8.public;\n\n";
+        let burn = "function burn:\n    input r0 as u8.private;\n    call helper r0 into r1;\n";
+        let program = format!("{header}{mint}{helper}{burn}");
+
+        let paths = aleo_file.write_transitions_to(&directory, &program).unwrap();
+        assert_eq!(paths, [directory.join("token.mint.aleo"), directory.join("token.burn.aleo")]);
+
+        // Each file contains the header and the blocks shared by all functions, followed by a single function.
+        assert_eq!(fs::read_to_string(&paths[0]).unwrap(), format!("{header}{helper}{mint}"));
+        assert_eq!(fs::read_to_string(&paths[1]).unwrap(), format!("{header}{helper}{burn}"));
+        for path in &paths {
+            AleoFile::from_path(path.clone()).validate(path).unwrap();
+        }
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_from_path() {
+        // A directory that is not a build or outputs directory.
+        let mut directory = std::env::temp_dir();
+        directory.push(format!("leo-aleo-file-from_path-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+
+        let path = directory.join("downloaded.aleo");
+        fs::write(&path, "// A dependency.\nprogram token.aleo;\n\nfunction mint:\n").unwrap();
+
+        let aleo_file = AleoFile::from_path(path.clone());
+        assert_eq!(aleo_file.program_name, "downloaded");
+        // The bound path is used regardless of the path that is passed in.
+        let other = Path::new("unrelated");
+        assert!(aleo_file.exists_at(other));
+        assert_eq!(aleo_file.read_from(other).unwrap(), "// A dependency.\nprogram token.aleo;\n\nfunction mint:\n");
+        assert_eq!(aleo_file.read_program_id(other).unwrap(), "token.aleo");
+
+        // A file without a program declaration has no program ID.
+        fs::write(&path, "function mint:\n").unwrap();
+        assert!(aleo_file.read_program_id(other).is_err());
+
+        assert!(aleo_file.remove(other).unwrap());
+        assert!(!path.exists());
+        assert!(!aleo_file.remove(other).unwrap());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_write_to_failure_preserves_file() {
+        let directory = build_directory("write_to_failure");
+        let aleo_file = AleoFile::new("main");
+        aleo_file.write_to(&directory, "program test.aleo;\n".to_string()).unwrap();
+
+        // Fail after partially writing the new instructions.
+        let path = aleo_file.setup_file_path(&directory).into_owned();
+        let result = AleoFile::write_atomically(&path, |file| {
+            file.write_all(b"program ")?;
+            Err(io::Error::new(io::ErrorKind::Interrupted, "injected failure"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(aleo_file.read_from(&directory).unwrap(), "program test.aleo;\n");
+        assert!(!AleoFile::temporary_file_path(&path).exists());
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+}
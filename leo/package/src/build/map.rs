@@ -0,0 +1,102 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Leo library.
+
+// The Leo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Leo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Leo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! The map file, which maps each Aleo instruction to the location of the Leo statement that produced it.
+
+use leo_errors::{PackageError, Result};
+
+use serde::Deserialize;
+use std::{borrow::Cow, fs, path::Path};
+
+pub static MAP_FILE_EXTENSION: &str = ".map";
+
+#[derive(Deserialize)]
+pub struct MapFile {
+    pub program_name: String,
+    /// The name of the file on disk, without the extension. Defaults to the program name.
+    #[serde(default)]
+    pub file_stem: Option<String>,
+}
+
+impl MapFile {
+    pub fn new(program_name: &str) -> Self {
+        Self { program_name: program_name.to_string(), file_stem: None }
+    }
+
+    /// Sets the name of the file on disk, without the extension.
+    /// This should match the file stem of the corresponding Aleo file.
+    pub fn with_file_stem(mut self, file_stem: &str) -> Self {
+        self.file_stem = Some(file_stem.to_string());
+        self
+    }
+
+    /// Returns the name of the file on disk, without the extension.
+    pub fn file_stem(&self) -> &str {
+        self.file_stem.as_deref().unwrap_or(&self.program_name)
+    }
+
+    pub fn exists_at(&self, path: &Path) -> bool {
+        let path = self.setup_file_path(path);
+        path.exists()
+    }
+
+    /// Writes the given JSON map to a file, replacing any existing file.
+    pub fn write_to(&self, path: &Path, map: String) -> Result<()> {
+        let path = self.setup_file_path(path);
+
+        fs::write(&path, map).map_err(PackageError::failed_to_write_map_file)?;
+        Ok(())
+    }
+
+    fn setup_file_path<'a>(&self, path: &'a Path) -> Cow<'a, Path> {
+        let mut path = Cow::from(path);
+        if path.is_dir() {
+            path.to_mut().push(format!("{}{MAP_FILE_EXTENSION}", self.file_stem()));
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build::{AleoFile, BuildDirectory};
+
+    use std::path::PathBuf;
+
+    /// Returns an empty build directory for the test with the given name.
+    fn build_directory(test_name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!("leo-map-file-{test_name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        BuildDirectory::create(&path).unwrap()
+    }
+
+    #[test]
+    fn test_write_to() {
+        let directory = build_directory("write_to");
+        let aleo_file = AleoFile::new("my_project").with_file_stem("main");
+        let map_file = MapFile::new("my_project").with_file_stem("main");
+
+        // The map file is written alongside the Aleo file.
+        aleo_file.write_to(&directory, "program my_project.aleo;\n".to_string()).unwrap();
+        map_file.write_to(&directory, "[]".to_string()).unwrap();
+        assert!(map_file.exists_at(&directory));
+        assert_eq!(fs::read_to_string(directory.join("main.map")).unwrap(), "[]");
+
+        fs::remove_dir_all(directory.parent().unwrap()).unwrap();
+    }
+}
@@ -16,3 +16,9 @@
 
 pub mod directory;
 pub use directory::*;
+
+pub mod aleo;
+pub use aleo::*;
+
+pub mod map;
+pub use map::*;
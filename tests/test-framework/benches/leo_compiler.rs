@@ -16,7 +16,7 @@
 
 //! This file contains tools for benchmarking the Leo compiler and its stages.
 
-use leo_compiler::{BuildOptions, Compiler, CompilerOptions, OutputOptions};
+use leo_compiler::{BuildOptions, Compiler, CompilerOptions, OutputOptions, DEFAULT_MAX_LOOP_ITERATIONS};
 use leo_errors::emitter::{Emitter, Handler};
 use leo_span::{source_map::FileName, symbol::SESSION_GLOBALS};
 use leo_test_framework::get_benches;
@@ -88,7 +88,28 @@ fn new_compiler(handler: &Handler) -> Compiler<'_> {
         PathBuf::from(String::new()),
         PathBuf::from(String::new()),
         Some(CompilerOptions {
-            build: BuildOptions { dce_enabled: true },
+            build: BuildOptions {
+                ssa_enabled: true,
+                flattening_enabled: true,
+                dce_enabled: true,
+                cse_enabled: false,
+                temporary_elimination_enabled: false,
+                overflow_mode: Default::default(),
+                ssa_verification_enabled: false,
+                constant_propagation_enabled: false,
+                unused_input_warnings_enabled: false,
+                assert_lowering_enabled: false,
+                return_folding_enabled: true,
+                finalize_folding_enabled: true,
+                mapping_update_rejection_enabled: false,
+                debug_comments_enabled: false,
+                source_map_enabled: false,
+                annotation_comments_enabled: false,
+                trivial_inlining_threshold: 0,
+                named_returns_enabled: false,
+                unreachable_assertions_enabled: false,
+                max_loop_iterations: DEFAULT_MAX_LOOP_ITERATIONS,
+            },
             output: OutputOptions {
                 symbol_table_spans_enabled: false,
                 initial_symbol_table: false,
@@ -103,6 +124,7 @@ fn new_compiler(handler: &Handler) -> Compiler<'_> {
                 destructured_ast: false,
                 inlined_ast: false,
                 dce_ast: false,
+                ast_dumps: false,
             },
         }),
     )